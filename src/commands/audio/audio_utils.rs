@@ -0,0 +1,486 @@
+extern crate ffmpeg_next as ffmpeg;
+
+use anyhow::{Context, Result};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use rubato::{
+    calculate_cutoff, FastFixedIn, FftFixedIn, PolynomialDegree, SincFixedIn,
+    SincInterpolationParameters, SincInterpolationType, VecResampler, WindowFunction,
+};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::{SincInterpolation, SincWindow};
+
+// Number of input frames fed to the resampler at once
+const RESAMPLE_CHUNK: usize = 1024;
+
+// Intermediate points of sinc filters when the ratio can't be represented exactly
+const SINC_OVERSAMPLING: usize = 256;
+
+// Largest upsampling factor of a reduced ratio handled with exact polyphase filters
+const MAX_EXACT_OVERSAMPLING: usize = 1024;
+
+// Input frames by which the sinc and linear resamplers lag behind their output
+const SINC_OFFSET: usize = 1;
+const LINEAR_OFFSET: usize = 4;
+
+/// Resampling algorithm and its settings
+#[derive(Debug, Clone, Copy)]
+pub enum ResampleQuality {
+    /// Synchronous FFT resampler
+    Fft,
+    /// Windowed sinc interpolation, with a cutoff relative to the lowest Nyquist frequency
+    Sinc {
+        interpolation: SincInterpolation,
+        window: SincWindow,
+        sinc_len: usize,
+        cutoff: Option<f64>,
+    },
+    /// Linear interpolation without anti-aliasing, for previews
+    Linear,
+}
+
+/// Read samples, de-interleave them per channel and convert to f64 in [-1, 1]
+pub fn read_samples(
+    reader: &mut WavReader<BufReader<File>>,
+    spec: WavSpec,
+) -> Result<Vec<Vec<f64>>> {
+    let channels = spec.channels as usize;
+
+    // Init samples vec
+    let mut samples: Vec<Vec<f64>> = vec![Vec::new(); channels];
+
+    match spec.sample_format {
+        // Float samples are already normalized
+        SampleFormat::Float => reader
+            .samples::<f32>()
+            .map(|s| s.with_context(|| "Couldn't read samples".to_string()))
+            .collect::<Result<Vec<_>, _>>()?
+            .iter()
+            .enumerate()
+            .for_each(|(i, &sample)| samples[i % channels].push(sample as f64)),
+        SampleFormat::Int => {
+            // Calculate the maximum value based on bits_per_sample
+            let max_value = 2_f64.powi(spec.bits_per_sample as i32 - 1);
+
+            reader
+                .samples::<i32>()
+                .map(|s| s.with_context(|| "Couldn't read samples".to_string()))
+                .collect::<Result<Vec<_>, _>>()?
+                .iter()
+                .enumerate()
+                .for_each(|(i, &sample)| {
+                    // Normalize by dividing by max_value
+                    samples[i % channels].push(sample as f64 / max_value);
+                })
+        }
+    }
+
+    Ok(samples)
+}
+
+/// Open a WAV file and read its spec and de-interleaved samples
+pub fn read_wav(input: &Path) -> Result<(WavSpec, Vec<Vec<f64>>)> {
+    // Open the WAV file
+    let mut reader = WavReader::open(input).with_context(|| "Failed to open WavReader")?;
+
+    // Extract info from file
+    let spec = reader.spec();
+
+    // Read samples
+    let samples = read_samples(&mut reader, spec).with_context(|| "Couldn't read file")?;
+
+    Ok((spec, samples))
+}
+
+/// Interleave per-channel samples in [-1, 1] and write them with the given spec
+pub fn write_samples(output: &Path, spec: WavSpec, samples: &[Vec<f64>]) -> Result<()> {
+    // Init writer
+    let mut writer = WavWriter::create(output, spec)
+        .with_context(|| format!("Couldn't write to {:?}", output))?;
+
+    // Calculate the max value based on bits_per_sample for proper scaling
+    let max_value = 2_f64.powi(spec.bits_per_sample as i32 - 1);
+
+    // Write samples interleaved
+    let n_frames = samples.first().map_or(0, |channel| channel.len());
+    for i in 0..n_frames {
+        for channel in samples {
+            match spec.sample_format {
+                SampleFormat::Float => writer.write_sample(channel[i] as f32)?,
+                SampleFormat::Int => {
+                    // Scale back to the appropriate integer range
+                    let scaled_sample = (channel[i] * max_value)
+                        .round()
+                        .clamp(-max_value, max_value - 1.0);
+
+                    // Write sample based on bits_per_sample
+                    match spec.bits_per_sample {
+                        8 => writer.write_sample(scaled_sample as i8)?,
+                        16 => writer.write_sample(scaled_sample as i16)?,
+                        24 | 32 => writer.write_sample(scaled_sample as i32)?,
+                        _ => {
+                            return Err(anyhow::Error::msg(format!(
+                                "Unsupported bits per sample: {}",
+                                spec.bits_per_sample
+                            )))
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    writer
+        .finalize()
+        .with_context(|| format!("Couldn't finalize {:?}", output))?;
+
+    Ok(())
+}
+
+/// Resample de-interleaved samples from one sample rate to another with the FFT resampler
+pub fn resample_samples(samples: &[Vec<f64>], original_sr: u32, sr: u32) -> Result<Vec<Vec<f64>>> {
+    resample_samples_with(samples, original_sr, sr, ResampleQuality::Fft)
+}
+
+/// Resample de-interleaved samples from one sample rate to another
+///
+/// Samples are fed in chunks and the resampler is flushed with silence, so that its delay
+/// can be dropped and the output stays aligned with the input. Rational ratios of the
+/// asynchronous resamplers are aligned exactly by leading the input with silence.
+pub fn resample_samples_with(
+    samples: &[Vec<f64>],
+    original_sr: u32,
+    sr: u32,
+    quality: ResampleQuality,
+) -> Result<Vec<Vec<f64>>> {
+    // Initialize the resampler
+    let mut resampler = new_resampler(original_sr, sr, samples.len(), quality)
+        .with_context(|| "Can't initiate resampler")?;
+
+    // Length of the resampled signal, silence fed first and frames to drop at the start
+    let n_frames = samples.first().map_or(0, |channel| channel.len());
+    let expected = (n_frames as f64 * sr as f64 / original_sr as f64).round() as usize;
+    let (lead, delay) = match quality {
+        ResampleQuality::Fft => (0, resampler.output_delay()),
+        _ => asynchronous_alignment(original_sr, sr, quality),
+    };
+
+    let mut resampled: Vec<Vec<f64>> = vec![Vec::with_capacity(expected + delay); samples.len()];
+    let mut position = 0;
+
+    // Perform the resampling, padding the end of the input with silence
+    while resampled[0].len() < expected + delay {
+        let frames = resampler.input_frames_next();
+        let chunk: Vec<Vec<f64>> = samples
+            .iter()
+            .map(|channel| {
+                (position..position + frames)
+                    .map(|i| {
+                        i.checked_sub(lead)
+                            .and_then(|i| channel.get(i))
+                            .copied()
+                            .unwrap_or(0.0)
+                    })
+                    .collect()
+            })
+            .collect();
+        let output = resampler
+            .process(&chunk, None)
+            .with_context(|| "Can't resample file")?;
+        resampled
+            .iter_mut()
+            .zip(output)
+            .for_each(|(channel, output)| channel.extend(output));
+        position += frames;
+    }
+
+    resampled.iter_mut().for_each(|channel| {
+        channel.drain(..delay);
+        channel.truncate(expected);
+    });
+
+    Ok(resampled)
+}
+
+// Build the resampler for a conversion
+fn new_resampler(
+    original_sr: u32,
+    sr: u32,
+    channels: usize,
+    quality: ResampleQuality,
+) -> Result<Box<dyn VecResampler<f64>>> {
+    let ratio = sr as f64 / original_sr as f64;
+
+    let resampler: Box<dyn VecResampler<f64>> = match quality {
+        ResampleQuality::Fft => Box::new(FftFixedIn::<f64>::new(
+            original_sr as usize,
+            sr as usize,
+            RESAMPLE_CHUNK,
+            2,
+            channels,
+        )?),
+        ResampleQuality::Sinc {
+            interpolation,
+            window,
+            sinc_len,
+            cutoff,
+        } => {
+            let window = match window {
+                SincWindow::Blackman => WindowFunction::Blackman,
+                SincWindow::Blackman2 => WindowFunction::Blackman2,
+                SincWindow::BlackmanHarris => WindowFunction::BlackmanHarris,
+                SincWindow::BlackmanHarris2 => WindowFunction::BlackmanHarris2,
+                SincWindow::Hann => WindowFunction::Hann,
+                SincWindow::Hann2 => WindowFunction::Hann2,
+            };
+
+            // A rational ratio L/M only needs the L phases of the filter, which are
+            // computed exactly rather than interpolated
+            let upsampling = sr as usize / gcd(sr as usize, original_sr as usize);
+            let (oversampling_factor, interpolation) = if upsampling <= MAX_EXACT_OVERSAMPLING {
+                (upsampling, SincInterpolationType::Nearest)
+            } else {
+                let interpolation = match interpolation {
+                    SincInterpolation::Nearest => SincInterpolationType::Nearest,
+                    SincInterpolation::Linear => SincInterpolationType::Linear,
+                    SincInterpolation::Quadratic => SincInterpolationType::Quadratic,
+                    SincInterpolation::Cubic => SincInterpolationType::Cubic,
+                };
+                (SINC_OVERSAMPLING, interpolation)
+            };
+
+            let parameters = SincInterpolationParameters {
+                sinc_len,
+                f_cutoff: cutoff.map_or_else(|| calculate_cutoff(sinc_len, window), |c| c as f32),
+                oversampling_factor,
+                interpolation,
+                window,
+            };
+            Box::new(SincFixedIn::<f64>::new(
+                ratio,
+                1.0,
+                parameters,
+                RESAMPLE_CHUNK,
+                channels,
+            )?)
+        }
+        ResampleQuality::Linear => Box::new(FastFixedIn::<f64>::new(
+            ratio,
+            1.0,
+            PolynomialDegree::Linear,
+            RESAMPLE_CHUNK,
+            channels,
+        )?),
+    };
+
+    Ok(resampler)
+}
+
+// Frames of silence to feed first and output frames to drop for an asynchronous resampler
+//
+// With a reduced ratio L/M, output frame j is the input at ((j + 1)·M - a) / L where a is
+// the lag of the resampler in L-ths of an input frame, so dropping k - 1 frames aligns the
+// output once (k·M - a) / L frames of silence have been fed, if that is a whole number.
+fn asynchronous_alignment(original_sr: u32, sr: u32, quality: ResampleQuality) -> (usize, usize) {
+    let divisor = gcd(sr as usize, original_sr as usize);
+    let (up, down) = (sr as usize / divisor, original_sr as usize / divisor);
+
+    // Sinc filters are off by one of their phases, which only matters with few phases
+    let lag = match quality {
+        ResampleQuality::Linear => LINEAR_OFFSET * up,
+        _ if up <= MAX_EXACT_OVERSAMPLING => SINC_OFFSET * up - 1,
+        _ => SINC_OFFSET * up,
+    };
+
+    let k = (1..)
+        .find(|k| k * down >= lag && (k * down - lag) % up == 0)
+        .unwrap_or(1);
+    ((k * down - lag) / up, k - 1)
+}
+
+// Greatest common divisor
+fn gcd(a: usize, b: usize) -> usize {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+/// Adapt de-interleaved samples to a channel count and sample rate
+///
+/// Mono is copied to every channel and anything is averaged down to mono, other changes of
+/// the channel count are an error.
+pub fn conform_samples(
+    samples: Vec<Vec<f64>>,
+    sample_rate: u32,
+    channels: usize,
+    target_sr: u32,
+) -> Result<Vec<Vec<f64>>> {
+    let samples = match (samples.len(), channels) {
+        (n, c) if n == c => samples,
+        (1, c) => vec![samples[0].clone(); c],
+        (n, 1) => vec![(0..samples[0].len())
+            .map(|i| samples.iter().map(|channel| channel[i]).sum::<f64>() / n as f64)
+            .collect()],
+        (n, c) => {
+            return Err(anyhow::Error::msg(format!(
+                "Can't convert {} channels to {}",
+                n, c
+            )))
+        }
+    };
+
+    if sample_rate == target_sr {
+        Ok(samples)
+    } else {
+        resample_samples(&samples, sample_rate, target_sr)
+    }
+}
+
+/// Read any audio file into its sample rate and de-interleaved samples
+///
+/// WAV files are read with hound and everything else is decoded with ffmpeg.
+pub fn read_audio(input: &Path) -> Result<(u32, Vec<Vec<f64>>)> {
+    let is_wav = input
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"));
+
+    if is_wav {
+        let (spec, samples) = read_wav(input)?;
+        Ok((spec.sample_rate, samples))
+    } else {
+        let decoded = decode_audio(input)?;
+        match decoded.error {
+            Some(error) => Err(anyhow::Error::msg(error)),
+            None => Ok((decoded.sample_rate, decoded.samples)),
+        }
+    }
+}
+
+/// Audio decoded with ffmpeg, with the first error that interrupted decoding if any
+pub struct DecodedAudio {
+    pub sample_rate: u32,
+    pub samples: Vec<Vec<f64>>,
+    pub error: Option<String>,
+}
+
+/// Decode the best audio stream of any file ffmpeg can read into de-interleaved samples
+///
+/// Failing to open the file is an error, while read and decode errors stop decoding and are
+/// reported in the result along with the samples decoded so far.
+pub fn decode_audio(input: &Path) -> Result<DecodedAudio> {
+    // Read context
+    let mut context = ffmpeg::format::input(&input).with_context(|| "Couldn't read audio")?;
+
+    // Only select the audio stream, throw away video, subtitles etc
+    let (stream_index, parameters) = {
+        let stream = context
+            .streams()
+            .best(ffmpeg::media::Type::Audio)
+            .with_context(|| "No audio stream found in file")?;
+        (stream.index(), stream.parameters())
+    };
+
+    // Create decoder
+    let context_decoder = ffmpeg::codec::context::Context::from_parameters(parameters)
+        .with_context(|| "Failed to create decoder context from audio stream parameters")?;
+    let mut decoder = context_decoder
+        .decoder()
+        .audio()
+        .with_context(|| "Failed to create audio decoder from decoder context")?;
+
+    let mut samples: Vec<Vec<f64>> = vec![Vec::new(); decoder.channels() as usize];
+    let mut frame = ffmpeg::frame::Audio::empty();
+    let mut error = None;
+
+    // Read packets until the end of the file or the first error
+    loop {
+        let mut packet = ffmpeg::Packet::empty();
+        match packet.read(&mut context) {
+            Ok(()) => {}
+            Err(ffmpeg::Error::Eof) => break,
+            Err(e) => {
+                error = Some(format!("Failed to read packet: {}", e));
+                break;
+            }
+        }
+        if packet.stream() != stream_index {
+            continue;
+        }
+        if let Err(e) = decoder.send_packet(&packet) {
+            error = Some(format!("Failed to decode packet: {}", e));
+            break;
+        }
+        while decoder.receive_frame(&mut frame).is_ok() {
+            append_frame(&frame, &mut samples)?;
+        }
+    }
+
+    // Drain the decoder
+    if decoder.send_eof().is_ok() {
+        while decoder.receive_frame(&mut frame).is_ok() {
+            append_frame(&frame, &mut samples)?;
+        }
+    }
+
+    Ok(DecodedAudio {
+        sample_rate: decoder.rate(),
+        samples,
+        error,
+    })
+}
+
+// Convert a decoded frame to f64 and append it to the per-channel samples
+fn append_frame(frame: &ffmpeg::frame::Audio, samples: &mut [Vec<f64>]) -> Result<()> {
+    use ffmpeg::format::Sample;
+
+    if frame.channels() as usize != samples.len() {
+        return Err(anyhow::Error::msg(
+            "Number of channels changed while decoding",
+        ));
+    }
+
+    match frame.format() {
+        Sample::U8(_) => append_typed::<u8>(frame, samples, |s| (s as f64 - 128.0) / 128.0),
+        Sample::I16(_) => append_typed::<i16>(frame, samples, |s| s as f64 / 32768.0),
+        Sample::I32(_) => append_typed::<i32>(frame, samples, |s| s as f64 / 2147483648.0),
+        Sample::F32(_) => append_typed::<f32>(frame, samples, |s| s as f64),
+        Sample::F64(_) => append_typed::<f64>(frame, samples, |s| s),
+        format => Err(anyhow::Error::msg(format!(
+            "Unsupported sample format: {:?}",
+            format
+        ))),
+    }
+}
+
+// Append planar or packed samples of a given type
+fn append_typed<T>(
+    frame: &ffmpeg::frame::Audio,
+    samples: &mut [Vec<f64>],
+    convert: impl Fn(T) -> f64,
+) -> Result<()>
+where
+    T: ffmpeg::frame::audio::Sample + bytemuck::Pod,
+{
+    let channels = samples.len();
+    let n_samples = frame.samples();
+
+    if frame.is_planar() {
+        // One plane per channel
+        for (i, channel) in samples.iter_mut().enumerate() {
+            channel.extend(frame.plane::<T>(i).iter().map(|&s| convert(s)));
+        }
+    } else {
+        // Interleaved samples in the first plane
+        let data: &[T] = bytemuck::try_cast_slice(frame.data(0))
+            .map_err(|e| anyhow::Error::msg(format!("Misaligned audio frame: {}", e)))?;
+        data.iter()
+            .take(n_samples * channels)
+            .enumerate()
+            .for_each(|(i, &s)| samples[i % channels].push(convert(s)));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use anyhow::{Context, Result};
+use hound::WavSpec;
+use rayon::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+use crate::commands::audio::audio_utils::{read_wav, resample_samples, write_samples};
+use crate::utils::{file_has_right_extension, perform_io_sanity_check};
+use crate::{AudioChannelsArgs, ChannelMode};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 1] = ["wav"];
+
+/// Options shared by all the files processed
+struct Options<'a> {
+    mode: ChannelMode,
+    channel: Option<usize>,
+    map: &'a [usize],
+    sr: Option<u32>,
+    overwrite: bool,
+}
+
+pub fn execute(args: AudioChannelsArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    let options = Options {
+        mode: args.mode,
+        channel: args.channel,
+        map: &args.map,
+        sr: args.sr,
+        overwrite: args.overwrite,
+    };
+
+    // A channel map is needed to remap
+    if options.mode == ChannelMode::Remap && options.map.is_empty() {
+        return Err(anyhow::Error::msg("Remapping requires a channel map"));
+    }
+
+    // Splitting produces several files per input, so the output must be a directory
+    let allow_output_file = options.mode != ChannelMode::Split;
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, allow_output_file)
+        .with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, output, &options).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(input: &Path, output: &Path, options: &Options) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+        process_file(input, output, options)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output path
+            let file_output = output.join(relative_path);
+
+            let output_directory = file_output.parent().with_context(|| {
+                format!("Failed to get parent directory of: {:?}", relative_path)
+            })?;
+
+            // Ensure the output directory exists
+            fs::create_dir_all(output_directory).with_context(|| {
+                format!("Failed to create output directory: {:?}", output_directory)
+            })?;
+
+            // Split files are written next to each other in the output directory
+            let file_output = match options.mode {
+                ChannelMode::Split => output_directory,
+                _ => file_output.as_path(),
+            };
+
+            // Process the file
+            process_file(file, file_output, options)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// Process a single file
+fn process_file(input: &Path, output: &Path, options: &Options) -> Result<()> {
+    // Check that we can overwrite
+    if input == output && !options.overwrite {
+        return Err(anyhow::Error::msg("Can't overwrite files"));
+    }
+
+    // Read samples
+    let (spec, mut samples) = read_wav(input)?;
+    let channels = samples.len();
+
+    // Resample once, before the channel operations
+    let sample_rate = match options.sr {
+        Some(sr) if sr != spec.sample_rate => {
+            samples = resample_samples(&samples, spec.sample_rate, sr)?;
+            sr
+        }
+        _ => spec.sample_rate,
+    };
+
+    // Check that the requested channels exist
+    let check_channel = |channel: usize| {
+        if channel >= channels {
+            Err(anyhow::Error::msg(format!(
+                "Channel {} out of range for file with {} channels",
+                channel, channels
+            )))
+        } else {
+            Ok(())
+        }
+    };
+
+    match options.mode {
+        ChannelMode::Mono => {
+            let mono = match options.channel {
+                // Keep a single channel
+                Some(channel) => {
+                    check_channel(channel)?;
+                    samples.swap_remove(channel)
+                }
+                // Average all channels
+                None => (0..samples[0].len())
+                    .map(|i| {
+                        samples.iter().map(|channel| channel[i]).sum::<f64>() / channels as f64
+                    })
+                    .collect(),
+            };
+            write_samples(output, output_spec(spec, 1, sample_rate), &[mono])?;
+        }
+        ChannelMode::Split => {
+            // Calculate the number of digits needed when padding the name with 0's
+            let padding_width = format!("{}", channels - 1).len();
+
+            // Calculate the stem
+            let stem = input
+                .file_stem()
+                .with_context(|| format!("Failed to extract stem from: {:?}", input))?
+                .to_str()
+                .with_context(|| format!("Failed to convert stem to string for: {:?}", input))?;
+
+            for (i, channel) in samples.iter().enumerate() {
+                // Define the output path for the channel
+                let output_path = output.join(format!(
+                    "{}@ch{:0width$}.wav",
+                    stem,
+                    i,
+                    width = padding_width
+                ));
+
+                write_samples(
+                    &output_path,
+                    output_spec(spec, 1, sample_rate),
+                    std::slice::from_ref(channel),
+                )?;
+            }
+        }
+        ChannelMode::Remap => {
+            // Each output channel takes the input channel at the same position in the map
+            let remapped: Vec<Vec<f64>> = options
+                .map
+                .iter()
+                .map(|&channel| {
+                    check_channel(channel)?;
+                    Ok(samples[channel].clone())
+                })
+                .collect::<Result<_>>()?;
+            write_samples(
+                output,
+                output_spec(spec, remapped.len() as u16, sample_rate),
+                &remapped,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+// Spec of the written file, with the original sample format
+fn output_spec(spec: WavSpec, channels: u16, sample_rate: u32) -> WavSpec {
+    WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: spec.bits_per_sample,
+        sample_format: spec.sample_format,
+    }
+}
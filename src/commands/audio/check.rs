@@ -0,0 +1,365 @@
+use anyhow::{Context, Result};
+use hound::{SampleFormat, WavReader};
+use lofty::{AudioFile, Probe};
+use polars::prelude::*;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::audio::audio_utils::decode_audio;
+use crate::commands::audio::loudness::from_db;
+use crate::utils::{file_has_right_extension, write_table};
+use crate::AudioCheckArgs;
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 6] = ["mp3", "wav", "ogg", "flac", "aac", "m4a"];
+
+// Extensions decoded with hound, everything else goes through ffmpeg
+const WAV_EXTENSIONS: [&str; 1] = ["wav"];
+
+// Clipping level of formats without an integer full scale
+const FLOAT_CLIP_LEVEL: f64 = 0.999;
+
+// Window used to measure silence, in seconds
+const SILENCE_WINDOW: f64 = 0.05;
+
+/// Thresholds used to flag issues
+#[derive(Debug, Clone, Copy)]
+struct Thresholds {
+    clip_run: usize,
+    dc_offset: f64,
+    silence: f64,
+    silence_ratio: f64,
+    duration_tolerance: f64,
+}
+
+/// Decoded content of a file along with what went wrong while decoding it
+struct Decoded {
+    sample_rate: u32,
+    samples: Vec<Vec<f64>>,
+    clip_level: f64,
+    error: Option<String>,
+}
+
+/// Result of checking a single file
+struct CheckReport {
+    path: String,
+    header_duration: Option<f64>,
+    decoded_duration: f64,
+    clipped_runs: usize,
+    dc_offset: f64,
+    silence_ratio: f64,
+    issues: Vec<String>,
+}
+
+pub fn execute(args: AudioCheckArgs) -> Result<()> {
+    // Parse the arguments
+    let target = Path::new(&args.target);
+
+    let thresholds = Thresholds {
+        clip_run: args.clip_run,
+        dc_offset: args.dc_threshold,
+        silence: from_db(args.silence_threshold),
+        silence_ratio: args.silence_ratio,
+        duration_tolerance: args.duration_tolerance,
+    };
+
+    // A run of zero samples would flag every file
+    if thresholds.clip_run == 0 {
+        return Err(anyhow::Error::msg("Clip run must be at least 1 sample"));
+    }
+
+    // Error if it does not exist at all
+    if !target.exists() {
+        return Err(anyhow::Error::msg(
+            "Target file or directory does not exist",
+        ));
+    }
+
+    // Find all admissible files
+    let files: Vec<PathBuf> = match target.is_file() {
+        true => {
+            if file_has_right_extension(target, &EXTENSIONS).is_ok() {
+                vec![target.to_path_buf()]
+            } else {
+                vec![]
+            }
+        }
+        false => WalkDir::new(target)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect(),
+    };
+
+    // Raise error if no files are admissible
+    if files.is_empty() {
+        return Err(anyhow::Error::msg("No admissible audio files detected"));
+    }
+
+    // Check files, unreadable files are reported rather than skipped
+    let mut reports: Vec<CheckReport> = files
+        .par_iter()
+        .map(|file| check_file(file, thresholds))
+        .collect();
+    reports.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // Print files with issues
+    let flagged: Vec<&CheckReport> = reports.iter().filter(|r| !r.issues.is_empty()).collect();
+    for report in &flagged {
+        println!("{}: {}", report.path, report.issues.join("; "));
+    }
+
+    println!("Checked files: {}", reports.len());
+    println!("Files with issues: {}", flagged.len());
+
+    // Write the full report
+    if let Some(output) = &args.output {
+        let mut df = reports_to_table(&reports)?;
+        write_table(&mut df, Path::new(output))
+            .with_context(|| format!("Failed to write table to {:?}", output))?;
+    }
+
+    // Fail so that scripts can rely on the exit code
+    if !flagged.is_empty() {
+        return Err(anyhow::Error::msg(format!(
+            "{} of {} files have issues",
+            flagged.len(),
+            reports.len()
+        )));
+    }
+
+    Ok(())
+}
+
+// Build a table with one row per file
+fn reports_to_table(reports: &[CheckReport]) -> Result<DataFrame> {
+    df!(
+        "path" => reports.iter().map(|r| r.path.clone()).collect::<Vec<_>>(),
+        "header_duration" => reports.iter().map(|r| r.header_duration).collect::<Vec<_>>(),
+        "decoded_duration" => reports.iter().map(|r| r.decoded_duration).collect::<Vec<_>>(),
+        "clipped_runs" => reports.iter().map(|r| r.clipped_runs as u64).collect::<Vec<_>>(),
+        "dc_offset" => reports.iter().map(|r| r.dc_offset).collect::<Vec<_>>(),
+        "silence_ratio" => reports.iter().map(|r| r.silence_ratio).collect::<Vec<_>>(),
+        "issues" => reports.iter().map(|r| r.issues.join("; ")).collect::<Vec<_>>(),
+    )
+    .with_context(|| "Failed to build check table")
+}
+
+// Decode a file and run all the checks on it
+fn check_file(file: &Path, thresholds: Thresholds) -> CheckReport {
+    let mut issues = Vec::new();
+
+    // Duration announced by the header
+    let header_duration = match Probe::open(file).and_then(|probe| probe.read()) {
+        Ok(audio_file) => Some(audio_file.properties().duration().as_secs_f64()),
+        Err(e) => {
+            issues.push(format!("unreadable header: {}", e));
+            None
+        }
+    };
+
+    // Decode the samples
+    let decoded = match file_has_right_extension(file, &WAV_EXTENSIONS) {
+        Ok(()) => decode_wav(file),
+        Err(_) => decode_audio(file).map(|decoded| Decoded {
+            sample_rate: decoded.sample_rate,
+            samples: decoded.samples,
+            clip_level: FLOAT_CLIP_LEVEL,
+            error: decoded.error,
+        }),
+    };
+    let decoded = match decoded {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            issues.push(format!("undecodable: {:#}", e));
+            return CheckReport {
+                path: file.to_string_lossy().to_string(),
+                header_duration,
+                decoded_duration: 0.0,
+                clipped_runs: 0,
+                dc_offset: 0.0,
+                silence_ratio: 0.0,
+                issues,
+            };
+        }
+    };
+
+    if let Some(error) = &decoded.error {
+        issues.push(format!("corrupt or truncated stream: {}", error));
+    }
+
+    let n_frames = decoded.samples.first().map_or(0, |channel| channel.len());
+    let decoded_duration = if decoded.sample_rate > 0 {
+        n_frames as f64 / decoded.sample_rate as f64
+    } else {
+        0.0
+    };
+
+    // Empty streams make the remaining checks meaningless
+    if n_frames == 0 {
+        issues.push("empty stream".to_string());
+    }
+
+    // Header and decoded durations should agree
+    if let Some(header_duration) = header_duration {
+        if (header_duration - decoded_duration).abs() > thresholds.duration_tolerance {
+            issues.push(format!(
+                "duration mismatch: header {:.3} s, decoded {:.3} s",
+                header_duration, decoded_duration
+            ));
+        }
+    }
+
+    // Clipping
+    let clipped_runs =
+        count_clipped_runs(&decoded.samples, decoded.clip_level, thresholds.clip_run);
+    if clipped_runs > 0 {
+        issues.push(format!("clipping: {} runs", clipped_runs));
+    }
+
+    // DC offset, as the largest channel mean
+    let dc_offset = decoded
+        .samples
+        .iter()
+        .filter(|channel| !channel.is_empty())
+        .map(|channel| (channel.iter().sum::<f64>() / channel.len() as f64).abs())
+        .fold(0.0, f64::max);
+    if dc_offset > thresholds.dc_offset {
+        issues.push(format!("dc offset: {:.4}", dc_offset));
+    }
+
+    // Silence
+    let window = ((SILENCE_WINDOW * decoded.sample_rate as f64) as usize).max(1);
+    let silence_ratio = silence_ratio(&decoded.samples, thresholds.silence, window);
+    if n_frames > 0 && silence_ratio >= 1.0 {
+        issues.push("silent".to_string());
+    } else if silence_ratio >= thresholds.silence_ratio {
+        issues.push(format!("mostly silent: {:.0}%", silence_ratio * 100.0));
+    }
+
+    CheckReport {
+        path: file.to_string_lossy().to_string(),
+        header_duration,
+        decoded_duration,
+        clipped_runs,
+        dc_offset,
+        silence_ratio,
+        issues,
+    }
+}
+
+// Read a WAV file, keeping the samples read before any error
+fn decode_wav(file: &Path) -> Result<Decoded> {
+    // Open the WAV file
+    let mut reader = WavReader::open(file).with_context(|| "Failed to open WavReader")?;
+
+    // Extract info from file
+    let spec = reader.spec();
+    let channels = spec.channels as usize;
+
+    let mut samples: Vec<Vec<f64>> = vec![Vec::new(); channels];
+    let mut error = None;
+
+    // Integer formats clip at the largest representable value
+    let clip_level = match spec.sample_format {
+        SampleFormat::Float => FLOAT_CLIP_LEVEL,
+        SampleFormat::Int => {
+            let max_value = 2_f64.powi(spec.bits_per_sample as i32 - 1);
+            (max_value - 1.0) / max_value
+        }
+    };
+
+    match spec.sample_format {
+        SampleFormat::Float => {
+            for (i, sample) in reader.samples::<f32>().enumerate() {
+                match sample {
+                    Ok(sample) => samples[i % channels].push(sample as f64),
+                    Err(e) => {
+                        error = Some(e.to_string());
+                        break;
+                    }
+                }
+            }
+        }
+        SampleFormat::Int => {
+            let max_value = 2_f64.powi(spec.bits_per_sample as i32 - 1);
+            for (i, sample) in reader.samples::<i32>().enumerate() {
+                match sample {
+                    Ok(sample) => samples[i % channels].push(sample as f64 / max_value),
+                    Err(e) => {
+                        error = Some(e.to_string());
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    // Drop a partial frame left by a truncated file
+    let n_frames = samples
+        .iter()
+        .map(|channel| channel.len())
+        .min()
+        .unwrap_or(0);
+    samples
+        .iter_mut()
+        .for_each(|channel| channel.truncate(n_frames));
+
+    Ok(Decoded {
+        sample_rate: spec.sample_rate,
+        samples,
+        clip_level,
+        error,
+    })
+}
+
+// Count runs of at least `min_run` consecutive samples at or above the clipping level
+fn count_clipped_runs(samples: &[Vec<f64>], clip_level: f64, min_run: usize) -> usize {
+    samples
+        .iter()
+        .map(|channel| {
+            let mut runs = 0;
+            let mut run = 0;
+            for sample in channel {
+                if sample.abs() >= clip_level {
+                    run += 1;
+                } else {
+                    if run >= min_run {
+                        runs += 1;
+                    }
+                    run = 0;
+                }
+            }
+            if run >= min_run {
+                runs += 1;
+            }
+            runs
+        })
+        .sum()
+}
+
+// Fraction of windows whose RMS over all channels is below the threshold
+fn silence_ratio(samples: &[Vec<f64>], threshold: f64, window: usize) -> f64 {
+    let n_frames = samples.first().map_or(0, |channel| channel.len());
+    if n_frames == 0 {
+        return 0.0;
+    }
+
+    let n_windows = n_frames.div_ceil(window);
+    let silent_windows = (0..n_windows)
+        .filter(|w| {
+            let start = w * window;
+            let end = usize::min(start + window, n_frames);
+            let sum_squares: f64 = samples
+                .iter()
+                .map(|channel| channel[start..end].iter().map(|s| s * s).sum::<f64>())
+                .sum();
+            let rms = (sum_squares / ((end - start) * samples.len()) as f64).sqrt();
+            rms < threshold
+        })
+        .count();
+
+    silent_windows as f64 / n_windows as f64
+}
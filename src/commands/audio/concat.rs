@@ -0,0 +1,157 @@
+use anyhow::{Context, Result};
+use hound::{WavReader, WavSpec};
+use polars::prelude::*;
+use rayon::prelude::*;
+use std::f64::consts::FRAC_PI_2;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::audio::audio_utils::{conform_samples, read_wav, write_samples};
+use crate::utils::{file_has_right_extension, perform_io_sanity_check, read_table, write_table};
+use crate::AudioConcatArgs;
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 1] = ["wav"];
+
+// Extensions of manifests listing the files to join
+const MANIFEST_EXTENSIONS: [&str; 2] = ["csv", "parquet"];
+
+pub fn execute(args: AudioConcatArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    // Cue list next to the output unless given
+    let cues = match &args.cues {
+        Some(cues) => PathBuf::from(cues),
+        None => output.with_extension("csv"),
+    };
+
+    let crossfade: f64 = args.crossfade;
+
+    // The output is a single WAV file
+    file_has_right_extension(output, &EXTENSIONS).with_context(|| "Output must be a WAV file")?;
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, true, true).with_context(|| "Sanity check failed")?;
+
+    // Files to join, in order
+    let files = list_files(input)?;
+    if files.is_empty() {
+        return Err(anyhow::Error::msg("No admissible audio files detected"));
+    }
+
+    // Process files
+    process(&files, output, &cues, crossfade, args.sr).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Sorted WAV files of a directory, or the `path` column of a manifest
+fn list_files(input: &Path) -> Result<Vec<PathBuf>> {
+    // Case of a manifest
+    if input.is_file() {
+        file_has_right_extension(input, &MANIFEST_EXTENSIONS).with_context(|| {
+            "Input must be a directory or a CSV/Parquet manifest with a path column"
+        })?;
+
+        let df = read_table(input)?
+            .select([col("path")])
+            .collect()
+            .with_context(|| format!("Failed to read path column from: {:?}", input))?;
+
+        df.column("path")?
+            .str()
+            .with_context(|| "Path column must contain strings")?
+            .into_iter()
+            .map(|path| {
+                path.map(PathBuf::from)
+                    .with_context(|| "Path column must not contain nulls")
+            })
+            .collect()
+    }
+    // Case of a directory
+    else {
+        let mut files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+}
+
+// Join files into one, crossfading between them
+fn process(
+    files: &[PathBuf],
+    output: &Path,
+    cues: &Path,
+    crossfade: f64,
+    sr: Option<u32>,
+) -> Result<()> {
+    // The first file decides the output spec
+    let first_spec = WavReader::open(&files[0])
+        .with_context(|| format!("Failed to open file: {:?}", files[0]))?
+        .spec();
+    let spec = WavSpec {
+        sample_rate: sr.unwrap_or(first_spec.sample_rate),
+        ..first_spec
+    };
+    let channels = spec.channels as usize;
+
+    // Read files in parallel, converting those with another spec
+    let tracks: Vec<Vec<Vec<f64>>> = files
+        .par_iter()
+        .map(|file| -> Result<Vec<Vec<f64>>> {
+            let (file_spec, samples) = read_wav(file)?;
+            conform_samples(samples, file_spec.sample_rate, channels, spec.sample_rate)
+        })
+        .enumerate()
+        .map(|(i, track)| track.with_context(|| format!("Failed to read file: {:?}", files[i])))
+        .collect::<Result<_>>()?;
+
+    let crossfade_frames = (crossfade * spec.sample_rate as f64).round() as usize;
+
+    let mut joined: Vec<Vec<f64>> = vec![Vec::new(); channels];
+    let mut starts = Vec::with_capacity(tracks.len());
+    let mut ends = Vec::with_capacity(tracks.len());
+
+    for track in &tracks {
+        let length = joined[0].len();
+        let track_length = track[0].len();
+
+        // Overlap with the end of what was joined so far
+        let overlap = crossfade_frames.min(length).min(track_length);
+        let start = length - overlap;
+
+        for (channel, samples) in joined.iter_mut().zip(track) {
+            // Equal-power crossfade
+            for (i, sample) in samples[..overlap].iter().enumerate() {
+                let t = (i + 1) as f64 / (overlap + 1) as f64;
+                channel[start + i] =
+                    channel[start + i] * (t * FRAC_PI_2).cos() + sample * (t * FRAC_PI_2).sin();
+            }
+            channel.extend_from_slice(&samples[overlap..]);
+        }
+
+        starts.push(start as f64 / spec.sample_rate as f64);
+        ends.push((start + track_length) as f64 / spec.sample_rate as f64);
+    }
+
+    // Write to file
+    write_samples(output, spec, &joined)?;
+
+    // Write the boundaries of the original files
+    let mut df = df!(
+        "path" => files.iter().map(|file| file.to_string_lossy().to_string()).collect::<Vec<_>>(),
+        "start" => starts,
+        "end" => ends,
+    )
+    .with_context(|| "Failed to build cue list")?;
+    write_table(&mut df, cues)
+        .with_context(|| format!("Failed to write cue list to {:?}", cues))?;
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use anyhow::{Context, Result};
+use hound::{SampleFormat, WavSpec};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::path::Path;
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+use crate::commands::audio::audio_utils::{read_wav, write_samples};
+use crate::utils::{file_has_right_extension, perform_io_sanity_check, seed_from_path};
+use crate::{AudioConvertArgs, Dither, WavSampleFormat};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 1] = ["wav"];
+
+/// Target spec and quantization options shared by all the files processed
+#[derive(Debug, Clone, Copy)]
+struct Options {
+    bits: Option<u16>,
+    format: Option<WavSampleFormat>,
+    dither: Dither,
+    noise_shaping: bool,
+    seed: u64,
+    overwrite: bool,
+}
+
+pub fn execute(args: AudioConvertArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    let options = Options {
+        bits: args.bits,
+        format: args.format,
+        dither: args.dither,
+        noise_shaping: args.noise_shaping,
+        seed: args.seed,
+        overwrite: args.overwrite,
+    };
+
+    // Validate the requested spec
+    match (options.format, options.bits) {
+        (_, Some(bits)) if ![8, 16, 24, 32].contains(&bits) => {
+            return Err(anyhow::Error::msg(format!(
+                "Unsupported bits per sample: {}",
+                bits
+            )))
+        }
+        (Some(WavSampleFormat::Float), Some(bits)) if bits != 32 => {
+            return Err(anyhow::Error::msg("Float samples must be 32 bits"))
+        }
+        _ => {}
+    }
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, output, options).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(input: &Path, output: &Path, options: Options) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+        let relative_path = Path::new(input.file_name().unwrap_or_default());
+        process_file(input, relative_path, output, options)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output path
+            let file_output = output.join(relative_path);
+
+            // Ensure the output directory exists
+            if let Some(parent) = file_output.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+            }
+
+            // Process the file
+            process_file(file, relative_path, &file_output, options)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// Process a single file, whose path relative to the input seeds the dither
+fn process_file(input: &Path, relative_path: &Path, output: &Path, options: Options) -> Result<()> {
+    // Check that we can overwrite
+    if input == output && !options.overwrite {
+        return Err(anyhow::Error::msg("Can't overwrite files"));
+    }
+
+    // Read samples
+    let (spec, mut samples) = read_wav(input)?;
+
+    // Resolve the target spec, keeping whatever was not requested
+    let sample_format = match options.format {
+        Some(WavSampleFormat::Int) => SampleFormat::Int,
+        Some(WavSampleFormat::Float) => SampleFormat::Float,
+        None if options.bits.is_some_and(|bits| bits != 32) => SampleFormat::Int,
+        None => spec.sample_format,
+    };
+    let bits_per_sample = match (sample_format, options.bits) {
+        (SampleFormat::Float, _) => 32,
+        (SampleFormat::Int, Some(bits)) => bits,
+        (SampleFormat::Int, None) if spec.sample_format == SampleFormat::Float => 24,
+        (SampleFormat::Int, None) => spec.bits_per_sample,
+    };
+    let converted_spec = WavSpec {
+        channels: spec.channels,
+        sample_rate: spec.sample_rate,
+        bits_per_sample,
+        sample_format,
+    };
+
+    // Dither only when the resolution is reduced
+    let reduces_resolution = sample_format == SampleFormat::Int
+        && (spec.sample_format == SampleFormat::Float || bits_per_sample < spec.bits_per_sample);
+    if reduces_resolution {
+        // Seed per file so results do not depend on the processing order
+        let mut rng = StdRng::seed_from_u64(options.seed ^ seed_from_path(relative_path));
+        samples.iter_mut().for_each(|channel| {
+            quantize(
+                channel,
+                bits_per_sample,
+                options.dither,
+                options.noise_shaping,
+                &mut rng,
+            )
+        });
+    }
+
+    // Write to file
+    write_samples(output, converted_spec, &samples)?;
+
+    Ok(())
+}
+
+/// Quantize a channel to the target bit depth with optional dither and noise shaping
+fn quantize(
+    channel: &mut [f64],
+    bits_per_sample: u16,
+    dither: Dither,
+    noise_shaping: bool,
+    rng: &mut StdRng,
+) {
+    let max_value = 2_f64.powi(bits_per_sample as i32 - 1);
+
+    // Quantization error of the previous sample, fed back for noise shaping
+    let mut error = 0.0;
+
+    channel.iter_mut().for_each(|sample| {
+        // Subtract the previous error to push the noise towards high frequencies
+        let target = if noise_shaping {
+            *sample * max_value - error
+        } else {
+            *sample * max_value
+        };
+
+        // Triangular noise spanning +/- 1 LSB
+        let noise = match dither {
+            Dither::None => 0.0,
+            Dither::Tpdf => rng.gen::<f64>() - rng.gen::<f64>(),
+        };
+
+        let quantized = (target + noise).round().clamp(-max_value, max_value - 1.0);
+        error = quantized - target;
+        *sample = quantized / max_value;
+    });
+}
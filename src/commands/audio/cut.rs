@@ -0,0 +1,183 @@
+use anyhow::{Context, Result};
+use hound::{SampleFormat, WavSpec};
+use polars::prelude::*;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::audio::audio_utils::{read_audio, read_wav, write_samples};
+use crate::utils::{
+    file_has_right_extension, float_column, perform_io_sanity_check, read_table,
+    relative_directory, sanitize, string_column,
+};
+use crate::AudioCutArgs;
+
+// Extensions of manifests
+const MANIFEST_EXTENSIONS: [&str; 2] = ["csv", "parquet"];
+
+// Extensions read with hound, keeping their spec
+const WAV_EXTENSIONS: [&str; 1] = ["wav"];
+
+/// A segment to extract, with times in seconds
+#[derive(Debug, Clone)]
+struct Segment {
+    start: f64,
+    end: f64,
+    output: PathBuf,
+}
+
+pub fn execute(args: AudioCutArgs) -> Result<()> {
+    // Parse the arguments
+    let manifest = Path::new(&args.manifest);
+    let output = Path::new(&args.output);
+    let root = args.root.as_deref().map(Path::new);
+
+    // Sanity checks on I/O
+    file_has_right_extension(manifest, &MANIFEST_EXTENSIONS)
+        .with_context(|| "Manifest must be a CSV or Parquet file")?;
+    perform_io_sanity_check(manifest, output, false, false)
+        .with_context(|| "Sanity check failed")?;
+
+    // Read segments grouped by source file
+    let segments = read_segments(&args, root, output)?;
+
+    // Process files
+    segments
+        .par_iter()
+        .try_for_each(|(file, segments)| -> Result<()> {
+            process_file(file, segments)
+                .with_context(|| format!("Failed to process file: {:?}", file))
+        })
+        .with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Read the manifest into segments grouped by source file, in manifest order
+fn read_segments(
+    args: &AudioCutArgs,
+    root: Option<&Path>,
+    output: &Path,
+) -> Result<BTreeMap<PathBuf, Vec<Segment>>> {
+    let df = read_table(Path::new(&args.manifest))?
+        .collect()
+        .with_context(|| format!("Failed to read manifest: {:?}", args.manifest))?;
+
+    // Required columns
+    let files = string_column(&df, &args.file_column)?
+        .with_context(|| format!("Missing column: {:?}", args.file_column))?;
+    let starts = float_column(&df, &args.start_column)?;
+    let ends = float_column(&df, &args.end_column)?;
+
+    // Optional columns, used when present
+    let labels = string_column(&df, &args.label_column)?;
+    let names = string_column(&df, &args.name_column)?;
+
+    let mut segments: BTreeMap<PathBuf, Vec<Segment>> = BTreeMap::new();
+    let mut outputs = HashSet::new();
+
+    for row in 0..df.height() {
+        let row_error = |message: &str| anyhow::Error::msg(format!("Row {}: {}", row, message));
+
+        let file = files[row]
+            .as_deref()
+            .ok_or_else(|| row_error("missing file"))?;
+        let (start, end) = match (starts[row], ends[row]) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Err(row_error("missing start or end")),
+        };
+        if start < 0.0 || end <= start {
+            return Err(row_error("end must come after a non-negative start"));
+        }
+
+        // Relative paths are taken from the root when given
+        let file = match root {
+            Some(root) => root.join(file),
+            None => PathBuf::from(file),
+        };
+
+        // Organised by label when there is one
+        let directory = match labels.as_ref().and_then(|labels| labels[row].as_deref()) {
+            Some(label) => output.join(sanitize(label)),
+            None => output.to_path_buf(),
+        };
+
+        // Segments are numbered per source file, mirroring its directory, unless named
+        let index = segments.get(&file).map_or(0, |segments| segments.len());
+        let segment_output = match names.as_ref().and_then(|names| names[row].as_deref()) {
+            Some(name) => directory.join(format!("{}.wav", sanitize(name))),
+            None => {
+                let stem = file
+                    .file_stem()
+                    .with_context(|| format!("Failed to extract stem from: {:?}", file))?
+                    .to_string_lossy()
+                    .to_string();
+                directory
+                    .join(relative_directory(&file, root.unwrap_or(Path::new(""))))
+                    .join(format!("{}@{}.wav", stem, index))
+            }
+        };
+
+        // Two rows must not write to the same file
+        if !outputs.insert(segment_output.clone()) {
+            return Err(row_error(&format!(
+                "output {:?} is already used by another row",
+                segment_output
+            )));
+        }
+
+        segments.entry(file).or_default().push(Segment {
+            start,
+            end,
+            output: segment_output,
+        });
+    }
+
+    Ok(segments)
+}
+
+// Extract all the segments of a file
+fn process_file(input: &Path, segments: &[Segment]) -> Result<()> {
+    // WAV files keep their spec, other formats are written as 16 bits
+    let (spec, samples) = match file_has_right_extension(input, &WAV_EXTENSIONS) {
+        Ok(()) => read_wav(input)?,
+        Err(_) => {
+            let (sample_rate, samples) = read_audio(input)?;
+            let spec = WavSpec {
+                channels: samples.len() as u16,
+                sample_rate,
+                bits_per_sample: 16,
+                sample_format: SampleFormat::Int,
+            };
+            (spec, samples)
+        }
+    };
+    let n_frames = samples.first().map_or(0, |channel| channel.len());
+
+    for segment in segments {
+        // Ends past the file are clamped, as labelling tools often round them up
+        let start = (segment.start * spec.sample_rate as f64).round() as usize;
+        let end = ((segment.end * spec.sample_rate as f64).round() as usize).min(n_frames);
+        if start >= end {
+            return Err(anyhow::Error::msg(format!(
+                "Segment {:.3}-{:.3} s is outside of the file",
+                segment.start, segment.end
+            )));
+        }
+
+        // Ensure the output directory exists
+        if let Some(parent) = segment.output.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+        }
+
+        let cut: Vec<Vec<f64>> = samples
+            .iter()
+            .map(|channel| channel[start..end].to_vec())
+            .collect();
+        write_samples(&segment.output, spec, &cut)?;
+    }
+
+    Ok(())
+}
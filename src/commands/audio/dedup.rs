@@ -0,0 +1,467 @@
+use anyhow::{Context, Result};
+use polars::prelude::*;
+use rayon::prelude::*;
+use realfft::RealFftPlanner;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::f64::consts::PI;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::audio::audio_utils::read_audio;
+use crate::utils::{file_has_right_extension, write_table};
+use crate::{AudioDedupArgs, DedupAction};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 6] = ["mp3", "wav", "ogg", "flac", "aac", "m4a"];
+
+// Time between fingerprint frames in seconds
+const FRAME_HOP: f64 = 0.05;
+
+// Frequency range folded into the chroma, in Hz
+const CHROMA_MIN_FREQUENCY: f64 = 80.0;
+const CHROMA_MAX_FREQUENCY: f64 = 5000.0;
+
+// Consecutive frames hashed together in the candidate index
+const NGRAM: usize = 3;
+
+// Bands of the MinHash signature and hashes per band, files sharing a band are compared
+const BANDS: usize = 32;
+const ROWS: usize = 4;
+
+/// Chroma fingerprint of a file
+struct Fingerprint {
+    path: PathBuf,
+    duration: f64,
+    /// Mean-centered and normalized chroma vector of each frame
+    frames: Vec<[f32; 12]>,
+    /// Hash of each band of the MinHash signature, empty for silent files
+    bands: Vec<u64>,
+}
+
+pub fn execute(args: AudioDedupArgs) -> Result<()> {
+    // Parse the arguments
+    let target = Path::new(&args.target);
+
+    let threshold: f64 = args.threshold;
+    let max_offset = (args.max_offset / FRAME_HOP).round() as usize;
+
+    // Duplicates can only be found within a directory
+    if !target.is_dir() {
+        return Err(anyhow::Error::msg("Target must be an existing directory"));
+    }
+
+    // Moving requires a destination
+    let destination = match (args.action, &args.destination) {
+        (DedupAction::Move, Some(destination)) => Some(Path::new(destination)),
+        (DedupAction::Move, None) => {
+            return Err(anyhow::Error::msg(
+                "Moving duplicates requires a destination",
+            ))
+        }
+        _ => None,
+    };
+
+    // Find all admissible files
+    let mut files: Vec<PathBuf> = WalkDir::new(target)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+        .map(|e| e.path().to_path_buf())
+        .collect();
+    files.sort();
+
+    // Raise error if no files are admissible
+    if files.is_empty() {
+        return Err(anyhow::Error::msg("No admissible audio files detected"));
+    }
+
+    // Fingerprint files, skipping those that can't be decoded
+    let fingerprints: Vec<Fingerprint> = files
+        .par_iter()
+        .filter_map(|file| match fingerprint(file) {
+            Ok(fingerprint) => Some(fingerprint),
+            Err(err) => {
+                eprintln!("Skipping file {:?} due to error: {:#}", file, err);
+                None
+            }
+        })
+        .collect();
+
+    // Group files whose fingerprints match
+    let clusters = find_clusters(&fingerprints, threshold, max_offset);
+
+    // Report clusters, files only joined through another one are related but not duplicates
+    let mut rows: Vec<(String, usize, bool, f64)> = Vec::new();
+    for (i, cluster) in clusters.iter().enumerate() {
+        println!("Cluster {}:", i + 1);
+        for (j, &(file, similarity)) in cluster.iter().enumerate() {
+            let path = &fingerprints[file].path;
+            match (j, similarity >= threshold) {
+                (0, _) => println!("  keep      {}", path.display()),
+                (_, true) => println!("  duplicate {} ({:.3})", path.display(), similarity),
+                (_, false) => println!("  related   {} ({:.3})", path.display(), similarity),
+            }
+            rows.push((
+                path.to_string_lossy().to_string(),
+                i + 1,
+                j == 0,
+                similarity,
+            ));
+        }
+    }
+
+    let n_duplicates: usize = clusters
+        .iter()
+        .map(|cluster| {
+            cluster[1..]
+                .iter()
+                .filter(|(_, similarity)| *similarity >= threshold)
+                .count()
+        })
+        .sum();
+    println!("Fingerprinted files: {}", fingerprints.len());
+    println!("Duplicate clusters: {}", clusters.len());
+    println!("Duplicates: {}", n_duplicates);
+
+    // Write the clusters
+    if let Some(output) = &args.output {
+        let mut df = df!(
+            "path" => rows.iter().map(|row| row.0.clone()).collect::<Vec<_>>(),
+            "cluster" => rows.iter().map(|row| row.1 as u32).collect::<Vec<_>>(),
+            "keep" => rows.iter().map(|row| row.2).collect::<Vec<_>>(),
+            "similarity" => rows.iter().map(|row| row.3).collect::<Vec<_>>(),
+        )
+        .with_context(|| "Failed to build duplicate table")?;
+        write_table(&mut df, Path::new(output))
+            .with_context(|| format!("Failed to write table to {:?}", output))?;
+    }
+
+    // Act on duplicates, the first file of each cluster is kept
+    for cluster in &clusters {
+        let kept = &fingerprints[cluster[0].0].path;
+        for &(file, similarity) in &cluster[1..] {
+            if similarity < threshold {
+                continue;
+            }
+            let duplicate = &fingerprints[file].path;
+            match (args.action, destination) {
+                // Only identical files can share their content
+                (DedupAction::Hardlink, _) => match same_content(kept, duplicate)
+                    .with_context(|| format!("Failed to compare file: {:?}", duplicate))?
+                {
+                    true => hardlink(kept, duplicate)
+                        .with_context(|| format!("Failed to hardlink file: {:?}", duplicate))?,
+                    false => eprintln!(
+                        "Not hardlinking {:?}, its content differs from {:?}",
+                        duplicate, kept
+                    ),
+                },
+                (DedupAction::Move, Some(destination)) => move_file(duplicate, target, destination)
+                    .with_context(|| format!("Failed to move file: {:?}", duplicate))?,
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Compute the chroma fingerprint of a file
+fn fingerprint(file: &Path) -> Result<Fingerprint> {
+    let (sample_rate, samples) = read_audio(file)?;
+
+    // Work on the average of all channels
+    let n_frames = samples.first().map_or(0, |channel| channel.len());
+    let mono: Vec<f64> = (0..n_frames)
+        .map(|i| samples.iter().map(|channel| channel[i]).sum::<f64>() / samples.len() as f64)
+        .collect();
+
+    // Windows of 0.1 to 0.2 s whatever the sample rate, so that frames are comparable
+    let window = ((sample_rate / 10) as usize).next_power_of_two();
+    let hop = ((FRAME_HOP * sample_rate as f64).round() as usize).max(1);
+
+    let mut planner = RealFftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(window);
+    let hann: Vec<f64> = (0..window)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / window as f64).cos())
+        .collect();
+
+    // Pitch class of each FFT bin in the chroma range, with A as 0
+    let pitch_classes: Vec<Option<usize>> = (0..window / 2 + 1)
+        .map(|k| {
+            let frequency = k as f64 * sample_rate as f64 / window as f64;
+            if (CHROMA_MIN_FREQUENCY..=CHROMA_MAX_FREQUENCY).contains(&frequency) {
+                let semitones = (12.0 * (frequency / 440.0).log2()).round() as i64;
+                Some(semitones.rem_euclid(12) as usize)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let mut frame = fft.make_input_vec();
+    let mut spectrum = fft.make_output_vec();
+    let mut frames = Vec::new();
+
+    let mut start = 0;
+    while start + window <= mono.len() {
+        for (i, sample) in frame.iter_mut().enumerate() {
+            *sample = mono[start + i] * hann[i];
+        }
+        fft.process(&mut frame, &mut spectrum)
+            .with_context(|| "Failed to compute FFT")?;
+
+        // Fold magnitudes into pitch classes
+        let mut chroma = [0.0; 12];
+        for (bin, pitch_class) in spectrum.iter().zip(&pitch_classes) {
+            if let Some(pitch_class) = pitch_class {
+                chroma[*pitch_class] += bin.norm();
+            }
+        }
+
+        // Center and normalize, so that only the shape of the chroma matters
+        let mean = chroma.iter().sum::<f64>() / 12.0;
+        chroma.iter_mut().for_each(|value| *value -= mean);
+        let norm = chroma.iter().map(|value| value * value).sum::<f64>().sqrt();
+        let mut normalized = [0.0; 12];
+        if norm > 1e-9 {
+            for (normalized, value) in normalized.iter_mut().zip(chroma) {
+                *normalized = (value / norm) as f32;
+            }
+        }
+        frames.push(normalized);
+
+        start += hop;
+    }
+
+    Ok(Fingerprint {
+        path: file.to_path_buf(),
+        duration: n_frames as f64 / sample_rate as f64,
+        bands: bands(&frames),
+        frames,
+    })
+}
+
+/// Band hashes of the MinHash signature of a fingerprint, used to index candidates
+///
+/// Each frame is quantised to its strongest pitch class, and the set of n-grams of
+/// consecutive classes is summarised by its MinHash. Files whose sets overlap a lot are
+/// likely to share at least one band.
+fn bands(frames: &[[f32; 12]]) -> Vec<u64> {
+    // Silent frames have no strongest class and get their own
+    let classes: Vec<u64> = frames
+        .iter()
+        .map(|frame| {
+            frame
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| **value != 0.0)
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map_or(12, |(class, _)| class as u64)
+        })
+        .collect();
+    let ngrams: HashSet<u64> = classes
+        .windows(NGRAM)
+        .filter(|ngram| ngram.iter().any(|&class| class != 12))
+        .map(|ngram| ngram.iter().fold(0, |key, &class| key * 13 + class))
+        .collect();
+    if ngrams.is_empty() {
+        return Vec::new();
+    }
+
+    (0..BANDS)
+        .map(|band| {
+            (0..ROWS).fold(0, |hash, row| {
+                let seed = mix((band * ROWS + row) as u64);
+                let min = ngrams.iter().map(|&key| mix(key ^ seed)).min().unwrap_or(0);
+                mix(hash ^ min)
+            })
+        })
+        .collect()
+}
+
+// SplitMix64 finalizer, a cheap hash with well spread bits
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// Best average similarity of two fingerprints over offsets of up to `max_offset` frames
+///
+/// Only alignments where the fingerprints overlap over at least half of the shorter one
+/// are considered.
+fn similarity(a: &[[f32; 12]], b: &[[f32; 12]], max_offset: usize) -> f64 {
+    let min_overlap = (a.len().min(b.len()) / 2).max(1);
+
+    (-(max_offset as isize)..=max_offset as isize)
+        .filter_map(|offset| {
+            // Frame i of a is aligned with frame i + offset of b
+            let start = (-offset).max(0) as usize;
+            let end = (a.len() as isize).min(b.len() as isize - offset).max(0) as usize;
+            if end < start + min_overlap {
+                return None;
+            }
+            let sum: f64 = (start..end)
+                .map(|i| {
+                    let j = (i as isize + offset) as usize;
+                    a[i].iter()
+                        .zip(&b[j])
+                        .map(|(x, y)| (x * y) as f64)
+                        .sum::<f64>()
+                })
+                .sum();
+            Some(sum / (end - start) as f64)
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Group files whose similarity reaches the threshold
+///
+/// Each cluster lists file indices with their similarity to the kept file, which comes
+/// first and is the longest one. Clusters are transitive, so files only matching another
+/// member can be below the threshold with the kept file.
+fn find_clusters(
+    fingerprints: &[Fingerprint],
+    threshold: f64,
+    max_offset: usize,
+) -> Vec<Vec<(usize, f64)>> {
+    // Index files by band, so that only files sharing a bucket are compared
+    let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+    for (file, fingerprint) in fingerprints.iter().enumerate() {
+        for (band, &hash) in fingerprint.bands.iter().enumerate() {
+            buckets.entry((band, hash)).or_default().push(file);
+        }
+    }
+
+    // Files can only match if trimming accounts for their difference in duration
+    let max_duration_difference = 2.0 * max_offset as f64 * FRAME_HOP;
+
+    // Compare the candidates of each file as they are found, each pair from its first file
+    let matches: Vec<(usize, usize, f64)> = (0..fingerprints.len())
+        .into_par_iter()
+        .flat_map_iter(|a| {
+            let candidates: BTreeSet<usize> = fingerprints[a]
+                .bands
+                .iter()
+                .enumerate()
+                .flat_map(|(band, &hash)| &buckets[&(band, hash)])
+                .copied()
+                .filter(|&b| {
+                    b > a
+                        && (fingerprints[a].duration - fingerprints[b].duration).abs()
+                            <= max_duration_difference
+                })
+                .collect();
+            candidates.into_iter().filter_map(move |b| {
+                let similarity =
+                    similarity(&fingerprints[a].frames, &fingerprints[b].frames, max_offset);
+                (similarity >= threshold).then_some((a, b, similarity))
+            })
+        })
+        .collect();
+
+    // Union-find over matching pairs
+    let mut parents: Vec<usize> = (0..fingerprints.len()).collect();
+    fn root(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+    for &(a, b, _) in &matches {
+        let (root_a, root_b) = (root(&mut parents, a), root(&mut parents, b));
+        parents[root_a.max(root_b)] = root_a.min(root_b);
+    }
+
+    // Collect clusters with more than one file
+    let mut groups: Vec<Vec<usize>> = vec![Vec::new(); fingerprints.len()];
+    for i in 0..fingerprints.len() {
+        let root = root(&mut parents, i);
+        groups[root].push(i);
+    }
+
+    groups
+        .into_iter()
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            // Keep the longest file, the least likely to have been trimmed
+            group.sort_by(|&a, &b| {
+                fingerprints[b]
+                    .duration
+                    .total_cmp(&fingerprints[a].duration)
+                    .then(a.cmp(&b))
+            });
+            let kept = group[0];
+            group
+                .into_iter()
+                .map(|file| {
+                    let similarity = match file == kept {
+                        true => 1.0,
+                        false => similarity(
+                            &fingerprints[kept].frames,
+                            &fingerprints[file].frames,
+                            max_offset,
+                        ),
+                    };
+                    (file, similarity)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Check whether two files are byte-identical
+fn same_content(a: &Path, b: &Path) -> Result<bool> {
+    if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+
+    let mut reader_a = BufReader::new(File::open(a)?);
+    let mut reader_b = BufReader::new(File::open(b)?);
+    let mut buffer_a = [0u8; 8192];
+    let mut buffer_b = [0u8; 8192];
+    loop {
+        let read = reader_a.read(&mut buffer_a)?;
+        if read == 0 {
+            return Ok(true);
+        }
+        reader_b.read_exact(&mut buffer_b[..read])?;
+        if buffer_a[..read] != buffer_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+// Replace a duplicate with a hardlink to the kept file
+fn hardlink(kept: &Path, duplicate: &Path) -> Result<()> {
+    // Link next to the duplicate first, so that it is never lost
+    let mut temporary = duplicate.as_os_str().to_owned();
+    temporary.push(".dedup");
+    let temporary = PathBuf::from(temporary);
+
+    fs::hard_link(kept, &temporary)?;
+    fs::rename(&temporary, duplicate)?;
+
+    Ok(())
+}
+
+// Move a duplicate to the same relative path in the destination
+fn move_file(duplicate: &Path, target: &Path, destination: &Path) -> Result<()> {
+    let relative_path = duplicate
+        .strip_prefix(target)
+        .with_context(|| format!("Failed to strip prefix from path: {:?}", duplicate))?;
+    let moved = destination.join(relative_path);
+
+    if let Some(parent) = moved.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+    }
+    fs::rename(duplicate, &moved)?;
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use std::f64::consts::PI;
+
+// Gating parameters from EBU R128 / ITU-R BS.1770-4
+const BLOCK_DURATION: f64 = 0.4;
+const BLOCK_STEP: f64 = 0.1;
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE: f64 = -10.0;
+
+// Oversampling used for true peak estimation
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+const TRUE_PEAK_TAPS: usize = 12;
+
+/// Loudness measurements of a signal, all expressed in dB
+#[derive(Debug, Clone, Copy)]
+pub struct Loudness {
+    /// Integrated loudness in LUFS
+    pub integrated: f64,
+    /// True peak in dBTP
+    pub true_peak: f64,
+    /// Sample peak in dBFS
+    pub sample_peak: f64,
+    /// RMS level in dBFS
+    pub rms: f64,
+}
+
+/// Measure integrated loudness, true peak, sample peak and RMS of de-interleaved samples
+pub fn measure(samples: &[Vec<f64>], sample_rate: u32) -> Loudness {
+    Loudness {
+        integrated: integrated_loudness(samples, sample_rate),
+        true_peak: to_db(true_peak(samples)),
+        sample_peak: to_db(sample_peak(samples)),
+        rms: to_db(rms(samples)),
+    }
+}
+
+/// Convert a linear amplitude to dB
+pub fn to_db(value: f64) -> f64 {
+    20.0 * value.log10()
+}
+
+/// Convert dB to a linear amplitude
+pub fn from_db(value: f64) -> f64 {
+    10_f64.powf(value / 20.0)
+}
+
+/// Largest absolute sample value across all channels
+pub fn sample_peak(samples: &[Vec<f64>]) -> f64 {
+    samples
+        .iter()
+        .flatten()
+        .fold(0.0, |peak, sample| f64::max(peak, sample.abs()))
+}
+
+/// Root mean square over all channels
+pub fn rms(samples: &[Vec<f64>]) -> f64 {
+    let n_samples: usize = samples.iter().map(|channel| channel.len()).sum();
+    if n_samples == 0 {
+        return 0.0;
+    }
+    let sum_squares: f64 = samples.iter().flatten().map(|sample| sample * sample).sum();
+    (sum_squares / n_samples as f64).sqrt()
+}
+
+/// Peak of the signal after 4x oversampling with a windowed sinc interpolator
+pub fn true_peak(samples: &[Vec<f64>]) -> f64 {
+    // Polyphase filter bank, one set of taps per intermediate phase
+    let phases: Vec<Vec<f64>> = (1..TRUE_PEAK_OVERSAMPLING)
+        .map(|phase| {
+            let fraction = phase as f64 / TRUE_PEAK_OVERSAMPLING as f64;
+            (0..2 * TRUE_PEAK_TAPS)
+                .map(|tap| {
+                    // Distance from the interpolated point to this tap
+                    let t = tap as f64 - (TRUE_PEAK_TAPS as f64 - 1.0) - fraction;
+                    let sinc = if t == 0.0 {
+                        1.0
+                    } else {
+                        (PI * t).sin() / (PI * t)
+                    };
+                    // Hann window over the filter span
+                    let window = 0.5 + 0.5 * (PI * t / TRUE_PEAK_TAPS as f64).cos();
+                    sinc * window
+                })
+                .collect()
+        })
+        .collect();
+
+    samples
+        .iter()
+        .map(|channel| {
+            let mut peak = channel.iter().fold(0.0, |peak, s| f64::max(peak, s.abs()));
+            for i in 0..channel.len() {
+                for taps in &phases {
+                    let value: f64 = taps
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(tap, coefficient)| {
+                            (i + tap)
+                                .checked_sub(TRUE_PEAK_TAPS - 1)
+                                .and_then(|index| channel.get(index))
+                                .map(|sample| sample * coefficient)
+                        })
+                        .sum();
+                    peak = f64::max(peak, value.abs());
+                }
+            }
+            peak
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Integrated loudness in LUFS following ITU-R BS.1770-4 with EBU R128 gating
+pub fn integrated_loudness(samples: &[Vec<f64>], sample_rate: u32) -> f64 {
+    let channels = samples.len();
+    let n_frames = samples.first().map_or(0, |channel| channel.len());
+
+    let block_size = (BLOCK_DURATION * sample_rate as f64).round() as usize;
+    let step_size = (BLOCK_STEP * sample_rate as f64).round() as usize;
+
+    // Signal shorter than a single gating block has no measurable loudness
+    if channels == 0 || block_size == 0 || n_frames < block_size {
+        return f64::NEG_INFINITY;
+    }
+
+    // Prefix sums of squared K-weighted samples per channel, for fast block means
+    let prefix_sums: Vec<Vec<f64>> = samples
+        .iter()
+        .map(|channel| {
+            let weighted = k_weighting(channel, sample_rate);
+            let mut sums = Vec::with_capacity(weighted.len() + 1);
+            sums.push(0.0);
+            weighted.iter().fold(0.0, |acc, sample| {
+                let acc = acc + sample * sample;
+                sums.push(acc);
+                acc
+            });
+            sums
+        })
+        .collect();
+
+    // Weighted mean square energy of every gating block
+    let blocks: Vec<f64> = (0..=(n_frames - block_size) / step_size)
+        .map(|j| {
+            let start = j * step_size;
+            let end = start + block_size;
+            prefix_sums
+                .iter()
+                .enumerate()
+                .map(|(i, sums)| channel_weight(i, channels) * (sums[end] - sums[start]))
+                .sum::<f64>()
+                / block_size as f64
+        })
+        .collect();
+
+    // Absolute gate
+    let absolute: Vec<f64> = blocks
+        .into_iter()
+        .filter(|&energy| energy_to_lufs(energy) > ABSOLUTE_GATE)
+        .collect();
+    if absolute.is_empty() {
+        return f64::NEG_INFINITY;
+    }
+
+    // Relative gate
+    let relative_threshold =
+        energy_to_lufs(absolute.iter().sum::<f64>() / absolute.len() as f64) + RELATIVE_GATE;
+    let relative: Vec<f64> = absolute
+        .into_iter()
+        .filter(|&energy| energy_to_lufs(energy) > relative_threshold)
+        .collect();
+    if relative.is_empty() {
+        return f64::NEG_INFINITY;
+    }
+
+    energy_to_lufs(relative.iter().sum::<f64>() / relative.len() as f64)
+}
+
+// Convert mean square energy to LUFS
+fn energy_to_lufs(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.log10()
+}
+
+// Channel weights, surround channels of a 5.1 layout are boosted and LFE is ignored
+fn channel_weight(channel: usize, channels: usize) -> f64 {
+    match (channels, channel) {
+        (6, 3) => 0.0,
+        (6, 4) | (6, 5) => 1.41,
+        _ => 1.0,
+    }
+}
+
+// Apply the two-stage K-weighting pre-filter (high shelf followed by high pass)
+fn k_weighting(channel: &[f64], sample_rate: u32) -> Vec<f64> {
+    let rate = sample_rate as f64;
+
+    // Stage 1: high shelf modelling the acoustic effect of the head
+    let f0 = 1681.974450955533;
+    let gain = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (PI * f0 / rate).tan();
+    let vh = 10_f64.powf(gain / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf_b = [
+        (vh + vb * k / q + k * k) / a0,
+        2.0 * (k * k - vh) / a0,
+        (vh - vb * k / q + k * k) / a0,
+    ];
+    let shelf_a = [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0];
+
+    // Stage 2: RLB high pass filter
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass_b = [1.0, -2.0, 1.0];
+    let high_pass_a = [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0];
+
+    biquad(&biquad(channel, shelf_b, shelf_a), high_pass_b, high_pass_a)
+}
+
+// Direct form I biquad filter with normalized denominator
+fn biquad(input: &[f64], b: [f64; 3], a: [f64; 2]) -> Vec<f64> {
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+    input
+        .iter()
+        .map(|&x| {
+            let y = b[0] * x + b[1] * x1 + b[2] * x2 - a[0] * y1 - a[1] * y2;
+            x2 = x1;
+            x1 = x;
+            y2 = y1;
+            y1 = y;
+            y
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::path::Path;
+
+use crate::commands::audio::audio_utils::{conform_samples, read_wav, write_samples};
+use crate::commands::audio::loudness::{from_db, rms};
+use crate::utils::file_has_right_extension;
+use crate::AudioMixArgs;
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 1] = ["wav"];
+
+pub fn execute(args: AudioMixArgs) -> Result<()> {
+    // Parse the arguments
+    let inputs: Vec<&Path> = args.inputs.iter().map(Path::new).collect();
+    let output = Path::new(&args.output);
+
+    let overwrite: bool = args.overwrite;
+
+    // One gain per track, or none at all
+    if !args.gains.is_empty() && args.gains.len() != inputs.len() {
+        return Err(anyhow::Error::msg(format!(
+            "Expected {} gains, one per track, got {}",
+            inputs.len(),
+            args.gains.len()
+        )));
+    }
+
+    // Sanity checks on I/O
+    for input in &inputs {
+        if !input.is_file() {
+            return Err(anyhow::Error::msg(format!(
+                "Input file does not exist: {:?}",
+                input
+            )));
+        }
+        file_has_right_extension(input, &EXTENSIONS)
+            .with_context(|| format!("Input must be a WAV file: {:?}", input))?;
+        if *input == output && !overwrite {
+            return Err(anyhow::Error::msg("Can't overwrite files"));
+        }
+    }
+    file_has_right_extension(output, &EXTENSIONS).with_context(|| "Output must be a WAV file")?;
+
+    // Process files
+    process(&inputs, output, &args.gains, args.snr, args.loop_tracks)
+        .with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Overlay all the tracks onto the first one
+fn process(
+    inputs: &[&Path],
+    output: &Path,
+    gains: &[f64],
+    snr: Option<f64>,
+    loop_tracks: bool,
+) -> Result<()> {
+    // The first track decides the spec and the length
+    let (spec, first) =
+        read_wav(inputs[0]).with_context(|| format!("Failed to read file: {:?}", inputs[0]))?;
+    let channels = spec.channels as usize;
+    let n_frames = first[0].len();
+
+    // Read the other tracks in parallel and fit them to the first
+    let mut tracks: Vec<Vec<Vec<f64>>> = vec![first];
+    tracks.extend(
+        inputs[1..]
+            .par_iter()
+            .map(|input| -> Result<Vec<Vec<f64>>> {
+                let (track_spec, samples) =
+                    read_wav(input).with_context(|| format!("Failed to read file: {:?}", input))?;
+                let samples =
+                    conform_samples(samples, track_spec.sample_rate, channels, spec.sample_rate)
+                        .with_context(|| format!("Failed to convert file: {:?}", input))?;
+                Ok(fit_length(samples, n_frames, loop_tracks))
+            })
+            .collect::<Result<Vec<_>>>()?,
+    );
+
+    // Per-track gains
+    for (track, &gain) in tracks.iter_mut().zip(gains) {
+        let gain = from_db(gain);
+        track
+            .iter_mut()
+            .flatten()
+            .for_each(|sample| *sample *= gain);
+    }
+
+    // Sum of the tracks overlaid onto the first
+    let mut background: Vec<Vec<f64>> = vec![vec![0.0; n_frames]; channels];
+    for track in &tracks[1..] {
+        for (sum, channel) in background.iter_mut().zip(track) {
+            sum.iter_mut().zip(channel).for_each(|(sum, s)| *sum += s);
+        }
+    }
+
+    // Scale the background to reach the requested signal-to-noise ratio
+    if let Some(snr) = snr {
+        let signal_rms = rms(&tracks[0]);
+        let background_rms = rms(&background);
+        if background_rms > 0.0 {
+            let scale = signal_rms / (background_rms * from_db(snr));
+            background
+                .iter_mut()
+                .flatten()
+                .for_each(|sample| *sample *= scale);
+        }
+    }
+
+    let mixed: Vec<Vec<f64>> = tracks[0]
+        .iter()
+        .zip(&background)
+        .map(|(signal, noise)| signal.iter().zip(noise).map(|(s, n)| s + n).collect())
+        .collect();
+
+    // Write to file
+    write_samples(output, spec, &mixed)?;
+
+    Ok(())
+}
+
+// Cut or extend a track to a length, with silence or by repeating it
+fn fit_length(samples: Vec<Vec<f64>>, length: usize, loop_track: bool) -> Vec<Vec<f64>> {
+    samples
+        .into_iter()
+        .map(|channel| {
+            if loop_track && !channel.is_empty() {
+                channel.iter().copied().cycle().take(length).collect()
+            } else {
+                let mut channel = channel;
+                channel.resize(length, 0.0);
+                channel
+            }
+        })
+        .collect()
+}
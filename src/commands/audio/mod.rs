@@ -1,4 +1,17 @@
+pub mod audio_utils;
+pub mod channels;
+pub mod check;
+pub mod concat;
+pub mod convert;
+pub mod cut;
+pub mod dedup;
+pub mod loudness;
+pub mod mix;
+pub mod normalize;
 pub mod resample;
+pub mod spectrogram;
 pub mod split;
 pub mod summary;
+pub mod transform;
 pub mod trim;
+pub mod vad;
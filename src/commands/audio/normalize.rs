@@ -0,0 +1,206 @@
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::path::Path;
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+use crate::commands::audio::audio_utils::{read_wav, write_samples};
+use crate::commands::audio::loudness::{from_db, measure};
+use crate::utils::{file_has_right_extension, perform_io_sanity_check};
+use crate::AudioNormalizeArgs;
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 1] = ["wav"];
+
+// Default target loudness as recommended by EBU R128
+const DEFAULT_LUFS: f64 = -23.0;
+
+// Limiter time constants in seconds
+const LIMITER_ATTACK: f64 = 0.005;
+const LIMITER_RELEASE: f64 = 0.05;
+
+/// Level that the normalization aims for
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    /// Integrated loudness in LUFS
+    Lufs(f64),
+    /// Sample peak in dBFS
+    Peak(f64),
+}
+
+pub fn execute(args: AudioNormalizeArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    // Only one of the targets can be given, which clap enforces
+    let target = match args.peak {
+        Some(peak) => Target::Peak(peak),
+        None => Target::Lufs(args.lufs.unwrap_or(DEFAULT_LUFS)),
+    };
+
+    let ceiling: f64 = args.ceiling;
+
+    // A peak target above the ceiling would be limited back down to it
+    if let Target::Peak(peak) = target {
+        if peak > ceiling {
+            return Err(anyhow::Error::msg(format!(
+                "Peak target of {} dBFS is above the {} dBFS ceiling",
+                peak, ceiling
+            )));
+        }
+    }
+
+    let overwrite: bool = args.overwrite;
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, target, ceiling, output, overwrite).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+/// Process all the content (single file or directory of files)
+fn process(
+    input: &Path,
+    target: Target,
+    ceiling: f64,
+    output: &Path,
+    overwrite: bool,
+) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+        process_file(input, target, ceiling, output, overwrite)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output path
+            let file_output = output.join(relative_path);
+
+            // Ensure the output directory exists
+            if let Some(parent) = file_output.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+            }
+
+            // Process the file
+            process_file(file, target, ceiling, &file_output, overwrite)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// Process a single file
+fn process_file(
+    input: &Path,
+    target: Target,
+    ceiling: f64,
+    output: &Path,
+    overwrite: bool,
+) -> Result<()> {
+    // Check that we can overwrite
+    if input == output && !overwrite {
+        return Err(anyhow::Error::msg("Can't overwrite files"));
+    }
+
+    // Read samples
+    let (spec, mut samples) = read_wav(input)?;
+
+    // Measure the current level
+    let loudness = measure(&samples, spec.sample_rate);
+
+    // Gain needed to reach the target, silent files are left untouched
+    let gain_db = match target {
+        Target::Lufs(lufs) => lufs - loudness.integrated,
+        Target::Peak(peak) => peak - loudness.sample_peak,
+    };
+    let gain = if gain_db.is_finite() {
+        from_db(gain_db)
+    } else {
+        1.0
+    };
+
+    // Apply gain
+    samples
+        .iter_mut()
+        .flatten()
+        .for_each(|sample| *sample *= gain);
+
+    // Keep peaks under the ceiling
+    limit(&mut samples, from_db(ceiling), spec.sample_rate);
+
+    // Write to file
+    write_samples(output, spec, &samples)?;
+
+    Ok(())
+}
+
+/// Lookahead peak limiter linked across channels
+///
+/// The gain reduction needed by each frame is smoothed backwards in time for the attack and
+/// forwards for the release, so the resulting gain never exceeds what the ceiling allows.
+fn limit(samples: &mut [Vec<f64>], ceiling: f64, sample_rate: u32) {
+    let n_frames = samples.first().map_or(0, |channel| channel.len());
+
+    // Gain each frame needs to stay under the ceiling
+    let mut gains: Vec<f64> = (0..n_frames)
+        .map(|i| {
+            let peak = samples
+                .iter()
+                .fold(0.0, |peak, channel| f64::max(peak, channel[i].abs()));
+            if peak > ceiling {
+                ceiling / peak
+            } else {
+                1.0
+            }
+        })
+        .collect();
+
+    // Nothing to do if no frame exceeds the ceiling
+    if gains.iter().all(|&gain| gain == 1.0) {
+        return;
+    }
+
+    let attack = 1.0 - (-1.0 / (LIMITER_ATTACK * sample_rate as f64)).exp();
+    let release = 1.0 - (-1.0 / (LIMITER_RELEASE * sample_rate as f64)).exp();
+
+    // Attack: start reducing gain ahead of each peak
+    for i in (0..n_frames.saturating_sub(1)).rev() {
+        gains[i] = f64::min(gains[i], gains[i + 1] + (1.0 - gains[i + 1]) * attack);
+    }
+
+    // Release: recover gain smoothly after each peak
+    for i in 1..n_frames {
+        gains[i] = f64::min(gains[i], gains[i - 1] + (1.0 - gains[i - 1]) * release);
+    }
+
+    samples.iter_mut().for_each(|channel| {
+        channel
+            .iter_mut()
+            .zip(&gains)
+            .for_each(|(sample, gain)| *sample *= gain)
+    });
+}
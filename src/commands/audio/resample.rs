@@ -1,15 +1,17 @@
 use anyhow::{Context, Result};
-use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use hound::{SampleFormat, WavReader, WavSpec};
 use rayon::prelude::*;
-use rubato::{FftFixedIn, Resampler};
-use std::fs::{copy, File};
-use std::io::BufReader;
+use std::fs::copy;
 use std::path::Path;
 use std::path::PathBuf;
 use walkdir::WalkDir;
 
+use crate::commands::audio::audio_utils::{
+    read_samples, resample_samples_with, write_samples, ResampleQuality,
+};
+use crate::commands::audio::loudness::{sample_peak, to_db};
 use crate::utils::{file_has_right_extension, perform_io_sanity_check};
-use crate::AudioResampleArgs;
+use crate::{AudioResampleArgs, ClipHandling, ResampleMethod};
 
 // Admissible extensions for this command
 const EXTENSIONS: [&str; 1] = ["wav"];
@@ -21,24 +23,54 @@ pub fn execute(args: AudioResampleArgs) -> Result<()> {
 
     let sr: u32 = args.sr;
 
+    let quality = match args.method {
+        ResampleMethod::Fft => ResampleQuality::Fft,
+        ResampleMethod::Sinc => ResampleQuality::Sinc {
+            interpolation: args.interpolation,
+            window: args.window,
+            sinc_len: args.sinc_len,
+            cutoff: args.cutoff,
+        },
+        ResampleMethod::Linear => ResampleQuality::Linear,
+    };
+    let clipping = args.clipping;
+
     let overwrite: bool = args.overwrite;
 
+    // The sinc filter needs taps and a cutoff under the Nyquist frequency
+    if args.sinc_len == 0 {
+        return Err(anyhow::Error::msg("Sinc length must be positive"));
+    }
+    if let Some(cutoff) = args.cutoff {
+        if cutoff <= 0.0 || cutoff > 1.0 {
+            return Err(anyhow::Error::msg("Cutoff must be in (0, 1]"));
+        }
+    }
+
     // Sanity checks on I/O
     perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
 
     // Process files
-    process(input, sr, output, overwrite).with_context(|| "Processing failed")?;
+    process(input, sr, output, quality, clipping, overwrite)
+        .with_context(|| "Processing failed")?;
 
     Ok(())
 }
 
 /// Process all the content (single file or directory of files)
-fn process(input: &Path, sr: u32, output: &Path, overwrite: bool) -> Result<()> {
+fn process(
+    input: &Path,
+    sr: u32,
+    output: &Path,
+    quality: ResampleQuality,
+    clipping: ClipHandling,
+    overwrite: bool,
+) -> Result<()> {
     // Case of single input file
     if input.is_file() {
         // Check if the file has the right extension and process it
         file_has_right_extension(input, &EXTENSIONS)?;
-        process_file(input, sr, output, overwrite)
+        process_file(input, sr, output, quality, clipping, overwrite)
             .with_context(|| format!("Failed to process file: {:?}", input))?;
     }
     // Case of input being a directory
@@ -68,7 +100,7 @@ fn process(input: &Path, sr: u32, output: &Path, overwrite: bool) -> Result<()>
             }
 
             // Process the file
-            process_file(file, sr, &file_output, overwrite)
+            process_file(file, sr, &file_output, quality, clipping, overwrite)
                 .with_context(|| format!("Failed to process file: {:?}", file))?;
 
             Ok(())
@@ -77,35 +109,15 @@ fn process(input: &Path, sr: u32, output: &Path, overwrite: bool) -> Result<()>
     Ok(())
 }
 
-/// Read samples and convert to f64
-fn read_samples(
-    reader: &mut WavReader<BufReader<File>>,
-    channels: usize,
-    bits_per_sample: u16,
-) -> Result<Vec<Vec<f64>>> {
-    // Init samples vec
-    let mut samples: Vec<Vec<f64>> = vec![Vec::new(); channels];
-
-    // Calculate the maximum value based on bits_per_sample
-    let max_value = 2_f64.powi(bits_per_sample as i32 - 1);
-
-    // Read into samples vec
-    reader
-        .samples::<i32>()
-        .map(|s| s.with_context(|| "Couldn't read samples".to_string()))
-        .collect::<Result<Vec<_>, _>>()?
-        .iter()
-        .enumerate()
-        .for_each(|(i, &sample)| {
-            // Normalize by dividing by max_value
-            samples[i % channels].push(sample as f64 / max_value);
-        });
-
-    Ok(samples)
-}
-
 /// Process a single file
-fn process_file(input: &Path, sr: u32, output: &Path, overwrite: bool) -> Result<()> {
+fn process_file(
+    input: &Path,
+    sr: u32,
+    output: &Path,
+    quality: ResampleQuality,
+    clipping: ClipHandling,
+    overwrite: bool,
+) -> Result<()> {
     // Check that we can overwrite
     if input == output && !overwrite {
         return Err(anyhow::Error::msg("Can't overwrite files"));
@@ -130,59 +142,60 @@ fn process_file(input: &Path, sr: u32, output: &Path, overwrite: bool) -> Result
     }
 
     // Read samples
-    let samples = read_samples(&mut reader, channels, spec.bits_per_sample)
-        .with_context(|| "Couldn't read file")?;
-
-    // Initialize the resampler
-    let mut resampler = FftFixedIn::<f64>::new(
-        original_sr as usize,
-        sr as usize,
-        samples[0].len(), // Number of frames per channel
-        1024,
-        channels,
-    )
-    .with_context(|| "Can't initiate resampler")?;
+    let samples = read_samples(&mut reader, spec).with_context(|| "Couldn't read file")?;
 
     // Perform the resampling
-    let resampled_64 = resampler
-        .process(&samples, None)
-        .with_context(|| "Can't resample file")?;
+    let mut resampled_64 = resample_samples_with(&samples, original_sr, sr, quality)?;
+
+    // Filter ringing can push peaks close to full scale past it
+    let full_scale = match spec.sample_format {
+        SampleFormat::Float => 1.0,
+        SampleFormat::Int => {
+            let max_value = 2_f64.powi(spec.bits_per_sample as i32 - 1);
+            (max_value - 1.0) / max_value
+        }
+    };
+    let peak = sample_peak(&resampled_64);
+    if peak > full_scale {
+        match clipping {
+            ClipHandling::Report => {
+                let clipped = resampled_64
+                    .iter()
+                    .flatten()
+                    .filter(|sample| sample.abs() > full_scale)
+                    .count();
+                println!(
+                    "Clipping in {:?}: {} samples, peak {:+.2} dBFS",
+                    output,
+                    clipped,
+                    to_db(peak)
+                );
+            }
+            ClipHandling::Prevent => {
+                let gain = full_scale / peak;
+                resampled_64
+                    .iter_mut()
+                    .flatten()
+                    .for_each(|sample| *sample *= gain);
+                println!(
+                    "Lowered gain of {:?} by {:.2} dB to prevent clipping",
+                    output,
+                    -to_db(gain)
+                );
+            }
+        }
+    }
 
     // Create a new WAV specification for the resampled audio
     let resampled_spec = WavSpec {
         channels: channels as u16,
         sample_rate: sr,
         bits_per_sample: spec.bits_per_sample,
-        sample_format: SampleFormat::Int,
+        sample_format: spec.sample_format,
     };
 
-    // Init writer
-    let mut writer = WavWriter::create(output, resampled_spec)
-        .with_context(|| format!("Couldn't write to {:?}", output))?;
-
-    // Calculate the max value based on bits_per_sample for proper scaling
-    let max_value = 2_f64.powi((spec.bits_per_sample - 1) as i32);
-
     // Write samples interleaved
-    for i in 0..resampled_64[0].len() {
-        for channel in &resampled_64 {
-            // Scale back to the appropriate integer range
-            let scaled_sample = (channel[i] * max_value).round();
-
-            // Write sample based on bits_per_sample
-            match spec.bits_per_sample {
-                8 => writer.write_sample(scaled_sample as i8)?,
-                16 => writer.write_sample(scaled_sample as i16)?,
-                24 | 32 => writer.write_sample(scaled_sample as i32)?,
-                _ => {
-                    return Err(anyhow::Error::msg(format!(
-                        "Unsupported bits per sample: {}",
-                        spec.bits_per_sample
-                    )))
-                }
-            }
-        }
-    }
+    write_samples(output, resampled_spec, &resampled_64)?;
 
     Ok(())
 }
@@ -0,0 +1,407 @@
+use anyhow::{Context, Result};
+use image::{GrayImage, Luma};
+use polars::prelude::*;
+use rayon::prelude::*;
+use realfft::RealFftPlanner;
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+use crate::commands::audio::audio_utils::read_wav;
+use crate::utils::{file_has_right_extension, perform_io_sanity_check, write_table};
+use crate::{AudioSpectrogramArgs, FeatureFormat, SpectrogramKind};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 1] = ["wav"];
+
+// Dynamic range kept when rendering decibel features to images
+const TOP_DB: f32 = 80.0;
+
+// Floor applied to powers before taking their log
+const POWER_FLOOR: f64 = 1e-10;
+
+/// Feature extraction parameters shared by all the files processed
+#[derive(Debug, Clone, Copy)]
+struct Options {
+    kind: SpectrogramKind,
+    format: FeatureFormat,
+    window: usize,
+    hop: usize,
+    n_mels: usize,
+    n_mfcc: usize,
+}
+
+pub fn execute(args: AudioSpectrogramArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    // The format follows the output file extension unless given explicitly
+    let output_format = match output.extension() {
+        Some(extension) => Some(
+            format_from_extension(&extension.to_string_lossy())
+                .with_context(|| format!("Unsupported output extension: {:?}", extension))?,
+        ),
+        None => None,
+    };
+    let format = match (args.format, output_format) {
+        (Some(format), Some(output_format)) if format != output_format => {
+            return Err(anyhow::Error::msg(
+                "Output extension does not match the requested format",
+            ))
+        }
+        (Some(format), _) => format,
+        (None, Some(output_format)) => output_format,
+        (None, None) => FeatureFormat::Npy,
+    };
+
+    let options = Options {
+        kind: args.kind,
+        format,
+        window: args.window,
+        hop: args.hop,
+        n_mels: args.n_mels,
+        n_mfcc: args.n_mfcc,
+    };
+
+    // Validate the analysis parameters
+    if options.window == 0 || options.hop == 0 {
+        return Err(anyhow::Error::msg("Window and hop must be positive"));
+    }
+    if options.kind != SpectrogramKind::Stft && options.n_mels == 0 {
+        return Err(anyhow::Error::msg("Number of mel bands must be positive"));
+    }
+    if options.kind == SpectrogramKind::Mfcc && !(1..=options.n_mels).contains(&options.n_mfcc) {
+        return Err(anyhow::Error::msg(
+            "Number of MFCCs must be between 1 and the number of mel bands",
+        ));
+    }
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, output, options).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(input: &Path, output: &Path, options: Options) -> Result<()> {
+    let extension = format_extension(options.format);
+
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+
+        // Write next to the other features when the output is a directory
+        let file_output = match output.extension() {
+            Some(_) => output.to_path_buf(),
+            None => output.join(
+                input
+                    .with_extension(extension)
+                    .file_name()
+                    .with_context(|| format!("Failed to extract file name from: {:?}", input))?,
+            ),
+        };
+
+        process_file(input, &file_output, options)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output path, with the extension of the format
+            let file_output = output.join(relative_path).with_extension(extension);
+
+            // Ensure the output directory exists
+            if let Some(parent) = file_output.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+            }
+
+            // Process the file
+            process_file(file, &file_output, options)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// Process a single file
+fn process_file(input: &Path, output: &Path, options: Options) -> Result<()> {
+    // Read samples
+    let (spec, samples) = read_wav(input)?;
+
+    // Features are computed on the average of all channels
+    let n_frames = samples.first().map_or(0, |channel| channel.len());
+    let mono: Vec<f64> = (0..n_frames)
+        .map(|i| samples.iter().map(|channel| channel[i]).sum::<f64>() / samples.len() as f64)
+        .collect();
+
+    let features = compute_features(&mono, spec.sample_rate, options)?;
+
+    // Write to file
+    match options.format {
+        FeatureFormat::Png => write_png(&features, options.kind, output),
+        FeatureFormat::Npy => write_npy(&features, output),
+        FeatureFormat::Parquet => write_parquet(&features, options, spec.sample_rate, output),
+    }
+}
+
+// Compute the requested features as a (frames, bins) matrix
+fn compute_features(signal: &[f64], sample_rate: u32, options: Options) -> Result<Vec<Vec<f32>>> {
+    let power = stft_power(signal, options.window, options.hop)?;
+
+    let features = match options.kind {
+        // Magnitude is the square root of the power
+        SpectrogramKind::Stft => power
+            .iter()
+            .map(|frame| frame.iter().map(|&p| p.sqrt() as f32).collect())
+            .collect(),
+        SpectrogramKind::Mel | SpectrogramKind::Mfcc => {
+            let filterbank = mel_filterbank(options.n_mels, options.window, sample_rate);
+
+            // Log-power mel spectrogram in dB
+            let log_mel: Vec<Vec<f64>> = power
+                .iter()
+                .map(|frame| {
+                    filterbank
+                        .iter()
+                        .map(|filter| {
+                            let energy: f64 = filter.iter().zip(frame).map(|(w, p)| w * p).sum();
+                            10.0 * energy.max(POWER_FLOOR).log10()
+                        })
+                        .collect()
+                })
+                .collect();
+
+            match options.kind {
+                SpectrogramKind::Mfcc => log_mel
+                    .iter()
+                    .map(|frame| dct(frame, options.n_mfcc))
+                    .collect(),
+                _ => log_mel
+                    .iter()
+                    .map(|frame| frame.iter().map(|&v| v as f32).collect())
+                    .collect(),
+            }
+        }
+    };
+
+    Ok(features)
+}
+
+/// Power spectrum of Hann-windowed frames
+///
+/// The signal is zero-padded at the end so that every sample belongs to a frame, and a
+/// signal shorter than the window still gives one frame.
+fn stft_power(signal: &[f64], window: usize, hop: usize) -> Result<Vec<Vec<f64>>> {
+    let mut planner = RealFftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(window);
+
+    // Periodic Hann window
+    let hann: Vec<f64> = (0..window)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / window as f64).cos())
+        .collect();
+
+    let n_frames = if signal.len() <= window {
+        1
+    } else {
+        1 + (signal.len() - window).div_ceil(hop)
+    };
+
+    let mut frame = fft.make_input_vec();
+    let mut spectrum = fft.make_output_vec();
+
+    (0..n_frames)
+        .map(|f| {
+            let start = f * hop;
+            for (i, sample) in frame.iter_mut().enumerate() {
+                *sample = signal.get(start + i).copied().unwrap_or(0.0) * hann[i];
+            }
+            fft.process(&mut frame, &mut spectrum)
+                .with_context(|| "Failed to compute FFT")?;
+            Ok(spectrum.iter().map(|bin| bin.norm_sqr()).collect())
+        })
+        .collect()
+}
+
+/// Triangular filters evenly spaced on the HTK mel scale, from 0 Hz to Nyquist
+fn mel_filterbank(n_mels: usize, window: usize, sample_rate: u32) -> Vec<Vec<f64>> {
+    let hz_to_mel = |hz: f64| 2595.0 * (1.0 + hz / 700.0).log10();
+    let mel_to_hz = |mel: f64| 700.0 * (10_f64.powf(mel / 2595.0) - 1.0);
+
+    // Band edges, each filter spans three consecutive edges
+    let max_mel = hz_to_mel(sample_rate as f64 / 2.0);
+    let edges: Vec<f64> = (0..n_mels + 2)
+        .map(|i| mel_to_hz(max_mel * i as f64 / (n_mels + 1) as f64))
+        .collect();
+
+    let n_bins = window / 2 + 1;
+    (0..n_mels)
+        .map(|m| {
+            let (low, center, high) = (edges[m], edges[m + 1], edges[m + 2]);
+            (0..n_bins)
+                .map(|k| {
+                    let hz = k as f64 * sample_rate as f64 / window as f64;
+                    if hz <= low || hz >= high {
+                        0.0
+                    } else if hz <= center {
+                        (hz - low) / (center - low)
+                    } else {
+                        (high - hz) / (high - center)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Orthonormal DCT-II, keeping the first `n_coefficients`
+fn dct(values: &[f64], n_coefficients: usize) -> Vec<f32> {
+    let n = values.len() as f64;
+    (0..n_coefficients)
+        .map(|k| {
+            let sum: f64 = values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| v * (PI * k as f64 * (2.0 * i as f64 + 1.0) / (2.0 * n)).cos())
+                .sum();
+            let scale = if k == 0 {
+                (1.0 / n).sqrt()
+            } else {
+                (2.0 / n).sqrt()
+            };
+            (sum * scale) as f32
+        })
+        .collect()
+}
+
+// Render features as a grayscale image, time along x and low frequencies at the bottom
+fn write_png(features: &[Vec<f32>], kind: SpectrogramKind, output: &Path) -> Result<()> {
+    // Magnitudes are shown in dB
+    let values: Vec<Vec<f32>> = match kind {
+        SpectrogramKind::Stft => features
+            .iter()
+            .map(|frame| frame.iter().map(|&v| 20.0 * v.max(1e-5).log10()).collect())
+            .collect(),
+        _ => features.to_vec(),
+    };
+
+    // Map the value range to [0, 255]
+    let max = values.iter().flatten().fold(f32::MIN, |a, &b| a.max(b));
+    let min = match kind {
+        SpectrogramKind::Mfcc => values.iter().flatten().fold(f32::MAX, |a, &b| a.min(b)),
+        _ => max - TOP_DB,
+    };
+    let range = (max - min).max(f32::EPSILON);
+
+    let n_bins = values[0].len();
+    let image = GrayImage::from_fn(values.len() as u32, n_bins as u32, |x, y| {
+        let value = values[x as usize][n_bins - 1 - y as usize];
+        Luma([(((value - min) / range).clamp(0.0, 1.0) * 255.0).round() as u8])
+    });
+
+    image
+        .save(output)
+        .with_context(|| format!("Failed to save image: {:?}", output))
+}
+
+// Write features as a little-endian float32 array of shape (frames, bins)
+fn write_npy(features: &[Vec<f32>], output: &Path) -> Result<()> {
+    let n_bins = features.first().map_or(0, |frame| frame.len());
+
+    // Header dict, padded with spaces so the data starts on a 64 bytes boundary
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+        features.len(),
+        n_bins
+    );
+    let padding = (64 - (10 + header.len() + 1) % 64) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    let file = File::create(output).with_context(|| format!("Couldn't write to {:?}", output))?;
+    let mut writer = BufWriter::new(file);
+
+    // Magic string and version 1.0
+    writer.write_all(b"\x93NUMPY\x01\x00")?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    for value in features.iter().flatten() {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+// Write features as a table with one row per frame and one column per bin
+fn write_parquet(
+    features: &[Vec<f32>],
+    options: Options,
+    sample_rate: u32,
+    output: &Path,
+) -> Result<()> {
+    let prefix = match options.kind {
+        SpectrogramKind::Stft => "stft",
+        SpectrogramKind::Mel => "mel",
+        SpectrogramKind::Mfcc => "mfcc",
+    };
+    let n_bins = features.first().map_or(0, |frame| frame.len());
+
+    // Start time of each frame in seconds
+    let times: Vec<f64> = (0..features.len())
+        .map(|f| (f * options.hop) as f64 / sample_rate as f64)
+        .collect();
+
+    let mut columns = vec![Series::new("time".into(), times)];
+    for bin in 0..n_bins {
+        let name = format!("{}_{}", prefix, bin);
+        let values: Vec<f32> = features.iter().map(|frame| frame[bin]).collect();
+        columns.push(Series::new(name.as_str().into(), values));
+    }
+
+    let mut df = DataFrame::new(columns).with_context(|| "Failed to build feature table")?;
+    write_table(&mut df, output)
+}
+
+// Format matching a file extension
+fn format_from_extension(extension: &str) -> Option<FeatureFormat> {
+    match extension.to_lowercase().as_str() {
+        "png" => Some(FeatureFormat::Png),
+        "npy" => Some(FeatureFormat::Npy),
+        "parquet" => Some(FeatureFormat::Parquet),
+        _ => None,
+    }
+}
+
+// File extension of a format
+fn format_extension(format: FeatureFormat) -> &'static str {
+    match format {
+        FeatureFormat::Png => "png",
+        FeatureFormat::Npy => "npy",
+        FeatureFormat::Parquet => "parquet",
+    }
+}
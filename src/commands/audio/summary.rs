@@ -1,16 +1,40 @@
 use anyhow::{Context, Result};
-use lofty::{AudioFile, Probe};
+use hound::{SampleFormat, WavReader};
+use lofty::{Accessor, AudioFile, Probe, TaggedFileExt};
+use polars::prelude::*;
 use rayon::prelude::*;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-use crate::utils::file_has_right_extension;
+use crate::commands::audio::audio_utils::read_wav;
+use crate::commands::audio::loudness::{measure, Loudness};
+use crate::utils::{file_has_right_extension, write_table};
 use crate::AudioSummaryArgs;
 
 // Admissible extensions for this command
 const EXTENSIONS: [&str; 6] = ["mp3", "wav", "ogg", "flac", "aac", "m4a"];
 
+// Extensions that can be decoded for the sample format and loudness report
+const WAV_EXTENSIONS: [&str; 1] = ["wav"];
+
+/// Properties and common tags of a single audio file
+struct AudioInfo {
+    path: String,
+    codec: String,
+    /// Duration in nanoseconds
+    duration: u128,
+    /// Overall bitrate in kbps
+    bitrate: Option<u32>,
+    sample_rate: Option<u32>,
+    channels: Option<u8>,
+    bit_depth: Option<u8>,
+    sample_format: Option<&'static str>,
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+}
+
 pub fn execute(args: AudioSummaryArgs) -> Result<()> {
     // Parse the arguments
     let target = Path::new(&args.target);
@@ -45,25 +69,35 @@ pub fn execute(args: AudioSummaryArgs) -> Result<()> {
     }
 
     // Process files
-    let info: Vec<(u128, u32, u8, u8)> = files
-        .into_par_iter()
-        .filter_map(|file| process_audio(&file).ok())
+    let info: Vec<AudioInfo> = files
+        .par_iter()
+        .filter_map(|file| process_audio(file).ok())
         .collect();
 
+    // Per-file table printed as CSV, alone so that it can be piped
+    if args.per_file && args.output.is_none() {
+        let mut df = info_to_table(&info)?;
+        CsvWriter::new(std::io::stdout())
+            .finish(&mut df)
+            .with_context(|| "Failed to print table")?;
+        return Ok(());
+    }
+
     // Calculate total number of files
     let n_files = info.len();
 
     // Compute duration in seconds
     let total_duration_seconds: u64 = info
         .iter()
-        .map(|(duration, _, _, _)| (duration / 1_000_000_000) as u64)
+        .map(|i| (i.duration / 1_000_000_000) as u64)
         .sum();
 
     // Get unique values
-    let unique_sample_rates: HashSet<_> = info.iter().map(|(_, sr, _, _)| sr).collect();
-    let unique_channels: HashSet<_> = info.iter().map(|(_, _, channels, _)| channels).collect();
-    let unique_bit_depths: HashSet<_> = info.iter().map(|(_, _, _, depth)| depth).collect();
-    let unique_durations: HashSet<_> = info.iter().map(|(duration, _, _, _)| duration).collect();
+    let unique_sample_rates: HashSet<_> = info.iter().filter_map(|i| i.sample_rate).collect();
+    let unique_channels: HashSet<_> = info.iter().filter_map(|i| i.channels).collect();
+    let unique_bit_depths: HashSet<_> = info.iter().filter_map(|i| i.bit_depth).collect();
+    let unique_durations: HashSet<_> = info.iter().map(|i| i.duration).collect();
+    let unique_sample_formats: HashSet<_> = info.iter().filter_map(|i| i.sample_format).collect();
 
     // Format duration
     let hours = total_duration_seconds / 3600;
@@ -81,18 +115,100 @@ pub fn execute(args: AudioSummaryArgs) -> Result<()> {
     println!("Sample Rates: {:?} Hz", unique_sample_rates);
     println!("Channels: {:?}", unique_channels);
     println!("Bit Depths: {:?}", unique_bit_depths);
+    if !unique_sample_formats.is_empty() {
+        println!("Sample Formats (WAV): {:?}", unique_sample_formats);
+    }
     println!("Unique durations: {}", unique_durations.len());
 
     if let (Some(min), Some(max)) = (unique_durations.iter().min(), unique_durations.iter().max()) {
-        println!("Min duration: {:} s", (**min as f64 / 1_000_000_000_f64));
-        println!("Max duration: {:} s", (**max as f64 / 1_000_000_000_f64));
+        println!("Min duration: {:} s", (*min as f64 / 1_000_000_000_f64));
+        println!("Max duration: {:} s", (*max as f64 / 1_000_000_000_f64));
+    }
+
+    // Loudness report
+    if args.loudness {
+        print_loudness(&files);
+    }
+
+    // Per-file table written to a file
+    if let Some(output) = &args.output {
+        let mut df = info_to_table(&info)?;
+        write_table(&mut df, Path::new(output))
+            .with_context(|| format!("Failed to write table to {:?}", output))?;
     }
 
     Ok(())
 }
 
+// Build a table with one row per file
+fn info_to_table(info: &[AudioInfo]) -> Result<DataFrame> {
+    df!(
+        "path" => info.iter().map(|i| i.path.clone()).collect::<Vec<_>>(),
+        "codec" => info.iter().map(|i| i.codec.clone()).collect::<Vec<_>>(),
+        "duration" => info.iter().map(|i| i.duration as f64 / 1_000_000_000_f64).collect::<Vec<_>>(),
+        "bitrate" => info.iter().map(|i| i.bitrate).collect::<Vec<_>>(),
+        "sample_rate" => info.iter().map(|i| i.sample_rate).collect::<Vec<_>>(),
+        "channels" => info.iter().map(|i| i.channels.map(u32::from)).collect::<Vec<_>>(),
+        "bit_depth" => info.iter().map(|i| i.bit_depth.map(u32::from)).collect::<Vec<_>>(),
+        "sample_format" => info.iter().map(|i| i.sample_format).collect::<Vec<_>>(),
+        "title" => info.iter().map(|i| i.title.clone()).collect::<Vec<_>>(),
+        "artist" => info.iter().map(|i| i.artist.clone()).collect::<Vec<_>>(),
+        "album" => info.iter().map(|i| i.album.clone()).collect::<Vec<_>>(),
+    )
+    .with_context(|| "Failed to build summary table")
+}
+
+// Decode the files that support it and print loudness statistics
+fn print_loudness(files: &[PathBuf]) {
+    // Measure loudness of decodable files
+    let loudness: Vec<Loudness> = files
+        .par_iter()
+        .filter(|file| file_has_right_extension(file, &WAV_EXTENSIONS).is_ok())
+        .filter_map(|file| {
+            read_wav(file)
+                .map(|(spec, samples)| measure(&samples, spec.sample_rate))
+                .ok()
+        })
+        .collect();
+
+    println!("Loudness measured files: {}", loudness.len());
+
+    if loudness.is_empty() {
+        return;
+    }
+
+    // Silent files have no integrated loudness and are left out of the range
+    let integrated: Vec<f64> = loudness
+        .iter()
+        .map(|l| l.integrated)
+        .filter(|l| l.is_finite())
+        .collect();
+    if !integrated.is_empty() {
+        println!(
+            "Integrated loudness: min {:.1} LUFS, max {:.1} LUFS",
+            integrated.iter().cloned().fold(f64::INFINITY, f64::min),
+            integrated.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+        );
+    }
+    println!(
+        "Max true peak: {:.1} dBTP",
+        loudness
+            .iter()
+            .map(|l| l.true_peak)
+            .fold(f64::NEG_INFINITY, f64::max)
+    );
+    println!(
+        "RMS: min {:.1} dBFS, max {:.1} dBFS",
+        loudness.iter().map(|l| l.rms).fold(f64::INFINITY, f64::min),
+        loudness
+            .iter()
+            .map(|l| l.rms)
+            .fold(f64::NEG_INFINITY, f64::max)
+    );
+}
+
 // Function for getting relevant info of an audio file by just probing it
-fn process_audio(file: &Path) -> Result<(u128, u32, u8, u8)> {
+fn process_audio(file: &Path) -> Result<AudioInfo> {
     // Probe the audio file
     let audio_file = Probe::open(file)
         .with_context(|| format!("Failed to open audio file: {:?}", file))?
@@ -102,23 +218,31 @@ fn process_audio(file: &Path) -> Result<(u128, u32, u8, u8)> {
     // Read all audio file properties
     let properties = audio_file.properties();
 
-    // Get the number of channels
-    let channels = properties
-        .channels()
-        .with_context(|| "Failed to read channels")?;
-
-    // Get the duration in seconds
-    let duration = properties.duration().as_nanos();
-
-    // Get the sample rate
-    let sample_rate = properties
-        .sample_rate()
-        .with_context(|| "Failed to read sample rate")?;
-
-    // Get the bit depth
-    let bit_depth = properties
-        .bit_depth()
-        .with_context(|| "Failed to read bit depth")?;
+    // Sample format is only stored in WAV headers
+    let sample_format = match file_has_right_extension(file, &WAV_EXTENSIONS) {
+        Ok(()) => WavReader::open(file)
+            .ok()
+            .map(|reader| match reader.spec().sample_format {
+                SampleFormat::Int => "Int",
+                SampleFormat::Float => "Float",
+            }),
+        Err(_) => None,
+    };
 
-    Ok((duration, sample_rate, channels, bit_depth))
+    // Common tags, if any
+    let tag = audio_file.primary_tag().or_else(|| audio_file.first_tag());
+
+    Ok(AudioInfo {
+        path: file.to_string_lossy().to_string(),
+        codec: format!("{:?}", audio_file.file_type()),
+        duration: properties.duration().as_nanos(),
+        bitrate: properties.overall_bitrate(),
+        sample_rate: properties.sample_rate(),
+        channels: properties.channels(),
+        bit_depth: properties.bit_depth(),
+        sample_format,
+        title: tag.and_then(|t| t.title()).map(|t| t.to_string()),
+        artist: tag.and_then(|t| t.artist()).map(|t| t.to_string()),
+        album: tag.and_then(|t| t.album()).map(|t| t.to_string()),
+    })
 }
@@ -0,0 +1,317 @@
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::f64::consts::PI;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use walkdir::WalkDir;
+
+use crate::commands::audio::audio_utils::{read_wav, resample_samples, write_samples};
+use crate::commands::audio::loudness::from_db;
+use crate::utils::{file_has_right_extension, perform_io_sanity_check};
+use crate::AudioTransformArgs;
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 1] = ["wav"];
+
+// Length of the segments overlapped when time-stretching, in seconds
+const STRETCH_WINDOW: f64 = 0.04;
+
+/// A single step of the effects chain
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operation {
+    /// Linear fade in over the given seconds
+    FadeIn(f64),
+    /// Linear fade out over the given seconds
+    FadeOut(f64),
+    /// Append silence up to the given length in seconds
+    Pad(f64),
+    /// Gain in dB
+    Gain(f64),
+    /// Play faster by the given factor, changing the pitch as well
+    Speed(f64),
+    /// Play faster by the given factor, keeping the pitch
+    Tempo(f64),
+    /// Shift the pitch by the given semitones, keeping the duration
+    Pitch(f64),
+}
+
+impl FromStr for Operation {
+    type Err = anyhow::Error;
+
+    // Parse `name=value`
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, value) = s
+            .split_once('=')
+            .with_context(|| format!("Operation must be given as name=value: {:?}", s))?;
+        let value: f64 = value
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid value for operation: {:?}", s))?;
+
+        if !value.is_finite() {
+            return Err(anyhow::Error::msg(format!(
+                "Invalid value for operation: {:?}",
+                s
+            )));
+        }
+
+        let operation = match name.trim() {
+            "fade-in" => Operation::FadeIn(value),
+            "fade-out" => Operation::FadeOut(value),
+            "pad" => Operation::Pad(value),
+            "gain" => Operation::Gain(value),
+            "speed" => Operation::Speed(value),
+            "tempo" => Operation::Tempo(value),
+            "pitch" => Operation::Pitch(value),
+            _ => return Err(anyhow::Error::msg(format!("Unknown operation: {:?}", name))),
+        };
+
+        // Durations can't be negative and factors must be positive
+        match operation {
+            Operation::FadeIn(v) | Operation::FadeOut(v) | Operation::Pad(v) if v < 0.0 => Err(
+                anyhow::Error::msg(format!("Duration must not be negative: {:?}", s)),
+            ),
+            Operation::Speed(v) | Operation::Tempo(v) if v <= 0.0 => Err(anyhow::Error::msg(
+                format!("Factor must be positive: {:?}", s),
+            )),
+            _ => Ok(operation),
+        }
+    }
+}
+
+pub fn execute(args: AudioTransformArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    let operations: Vec<Operation> = args
+        .operations
+        .iter()
+        .map(|operation| operation.parse())
+        .collect::<Result<_>>()?;
+
+    let overwrite: bool = args.overwrite;
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, &operations, output, overwrite).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+/// Process all the content (single file or directory of files)
+fn process(input: &Path, operations: &[Operation], output: &Path, overwrite: bool) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+        process_file(input, operations, output, overwrite)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output path
+            let file_output = output.join(relative_path);
+
+            // Ensure the output directory exists
+            if let Some(parent) = file_output.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+            }
+
+            // Process the file
+            process_file(file, operations, &file_output, overwrite)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+/// Process a single file
+fn process_file(
+    input: &Path,
+    operations: &[Operation],
+    output: &Path,
+    overwrite: bool,
+) -> Result<()> {
+    // Check that we can overwrite
+    if input == output && !overwrite {
+        return Err(anyhow::Error::msg("Can't overwrite files"));
+    }
+
+    // Read samples
+    let (spec, mut samples) = read_wav(input)?;
+    let sample_rate = spec.sample_rate;
+
+    // Apply the operations in the given order
+    for operation in operations {
+        let n_frames = samples.first().map_or(0, |channel| channel.len());
+        let to_frames = |seconds: f64| (seconds * sample_rate as f64).round() as usize;
+
+        match *operation {
+            Operation::FadeIn(seconds) => {
+                let length = to_frames(seconds).min(n_frames);
+                samples.iter_mut().for_each(|channel| {
+                    channel[..length]
+                        .iter_mut()
+                        .enumerate()
+                        .for_each(|(i, sample)| *sample *= i as f64 / length as f64)
+                });
+            }
+            Operation::FadeOut(seconds) => {
+                let length = to_frames(seconds).min(n_frames);
+                samples.iter_mut().for_each(|channel| {
+                    channel[n_frames - length..]
+                        .iter_mut()
+                        .enumerate()
+                        .for_each(|(i, sample)| *sample *= 1.0 - (i + 1) as f64 / length as f64)
+                });
+            }
+            Operation::Pad(seconds) => {
+                let length = to_frames(seconds).max(n_frames);
+                samples
+                    .iter_mut()
+                    .for_each(|channel| channel.resize(length, 0.0));
+            }
+            Operation::Gain(db) => {
+                let gain = from_db(db);
+                samples
+                    .iter_mut()
+                    .flatten()
+                    .for_each(|sample| *sample *= gain);
+            }
+            Operation::Speed(factor) => {
+                samples = change_speed(&samples, factor, sample_rate)?;
+            }
+            Operation::Tempo(factor) => {
+                samples = time_stretch(&samples, factor, sample_rate);
+            }
+            Operation::Pitch(semitones) => {
+                // Stretch then speed up by the same ratio, so only the pitch changes
+                let ratio = 2_f64.powf(semitones / 12.0);
+                samples = time_stretch(&samples, 1.0 / ratio, sample_rate);
+                samples = change_speed(&samples, ratio, sample_rate)?;
+                samples
+                    .iter_mut()
+                    .for_each(|channel| channel.resize(n_frames, 0.0));
+            }
+        }
+    }
+
+    // Write to file
+    write_samples(output, spec, &samples)?;
+
+    Ok(())
+}
+
+/// Play samples faster by resampling them as if they had been recorded at a higher rate
+fn change_speed(samples: &[Vec<f64>], factor: f64, sample_rate: u32) -> Result<Vec<Vec<f64>>> {
+    let original_sr = (sample_rate as f64 * factor).round() as u32;
+    if original_sr == sample_rate || samples.first().map_or(0, |channel| channel.len()) == 0 {
+        return Ok(samples.to_vec());
+    }
+    resample_samples(samples, original_sr, sample_rate)
+}
+
+/// Change the duration of samples without changing their pitch (WSOLA)
+///
+/// Hann-windowed segments are read from the input at `tempo` times the rate they are written
+/// to the output with 50% overlap. Each segment is shifted by up to a quarter of a window to
+/// best match the continuation of the previous one, which avoids phase cancellations. The
+/// shift is chosen on the average of all channels and applied to each of them.
+fn time_stretch(samples: &[Vec<f64>], tempo: f64, sample_rate: u32) -> Vec<Vec<f64>> {
+    let n_frames = samples.first().map_or(0, |channel| channel.len());
+    if n_frames == 0 || tempo == 1.0 {
+        return samples.to_vec();
+    }
+
+    // Even window, so that periodic Hann windows at half overlap sum to one
+    let window = (((STRETCH_WINDOW * sample_rate as f64) as usize) / 2 * 2).max(4);
+    let hop = window / 2;
+    let tolerance = (hop / 2) as isize;
+    let hann: Vec<f64> = (0..window)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / window as f64).cos())
+        .collect();
+
+    // Samples outside the input are silent
+    let at = |channel: &[f64], index: isize| -> f64 {
+        if index < 0 {
+            0.0
+        } else {
+            channel.get(index as usize).copied().unwrap_or(0.0)
+        }
+    };
+
+    // Average of all channels, used for the alignment
+    let mono: Vec<f64> = (0..n_frames)
+        .map(|i| samples.iter().map(|channel| channel[i]).sum::<f64>() / samples.len() as f64)
+        .collect();
+
+    let output_len = (n_frames as f64 / tempo).round() as usize;
+    let mut output = vec![vec![0.0; output_len]; samples.len()];
+
+    // Segments start half a window early so the first output samples get full weight
+    let hop_isize = hop as isize;
+    let mut previous: isize = -hop_isize;
+    let mut k = 0;
+    while k * hop < output_len + hop {
+        let output_start = (k * hop) as isize - hop_isize;
+        let nominal = (k as f64 * hop as f64 * tempo).round() as isize - hop_isize;
+
+        // Look for the shift that best continues the previous segment
+        let position = if k == 0 {
+            nominal
+        } else {
+            let target = previous + hop_isize;
+            (nominal - tolerance..=nominal + tolerance)
+                .map(|candidate| {
+                    let (correlation, energy) =
+                        (0..hop_isize).fold((0.0, 0.0), |(correlation, energy), i| {
+                            let sample = at(&mono, candidate + i);
+                            (
+                                correlation + at(&mono, target + i) * sample,
+                                energy + sample * sample,
+                            )
+                        });
+                    (candidate, correlation / (energy + 1e-12).sqrt())
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or(nominal, |(candidate, _)| candidate)
+        };
+
+        // Overlap-add the segment
+        for (channel, output_channel) in samples.iter().zip(output.iter_mut()) {
+            for (i, weight) in hann.iter().enumerate() {
+                let index = output_start + i as isize;
+                if index >= 0 && (index as usize) < output_len {
+                    output_channel[index as usize] += at(channel, position + i as isize) * weight;
+                }
+            }
+        }
+
+        previous = position;
+        k += 1;
+    }
+
+    output
+}
@@ -0,0 +1,270 @@
+use anyhow::{Context, Result};
+use polars::prelude::*;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::audio::audio_utils::read_audio;
+use crate::commands::audio::loudness::{rms, to_db};
+use crate::utils::{file_has_right_extension, write_table};
+use crate::AudioVadArgs;
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 6] = ["mp3", "wav", "ogg", "flac", "aac", "m4a"];
+
+// Percentile of frame energies taken as the noise floor
+const NOISE_FLOOR_PERCENTILE: f64 = 0.1;
+
+/// Parameters of the detector, with durations in seconds
+#[derive(Debug, Clone, Copy)]
+struct Detector {
+    frame: f64,
+    threshold: f64,
+    silence_threshold: f64,
+    max_zcr: f64,
+    min_speech: f64,
+    min_silence: f64,
+    padding: f64,
+}
+
+/// Speech segments found in a single file
+struct VadReport {
+    path: String,
+    duration: f64,
+    segments: Vec<(f64, f64)>,
+}
+
+impl VadReport {
+    fn speech_duration(&self) -> f64 {
+        self.segments.iter().map(|(start, end)| end - start).sum()
+    }
+
+    fn speech_ratio(&self) -> f64 {
+        match self.duration > 0.0 {
+            true => self.speech_duration() / self.duration,
+            false => 0.0,
+        }
+    }
+}
+
+pub fn execute(args: AudioVadArgs) -> Result<()> {
+    // Parse the arguments
+    let target = Path::new(&args.target);
+
+    let detector = Detector {
+        frame: args.frame,
+        threshold: args.threshold,
+        silence_threshold: args.silence_threshold,
+        max_zcr: args.max_zcr,
+        min_speech: args.min_speech,
+        min_silence: args.min_silence,
+        padding: args.padding,
+    };
+    if detector.frame <= 0.0 {
+        return Err(anyhow::Error::msg("Frame duration must be positive"));
+    }
+
+    // Error if it does not exist at all
+    if !target.exists() {
+        return Err(anyhow::Error::msg(
+            "Target file or directory does not exist",
+        ));
+    }
+
+    // Find all admissible files
+    let files: Vec<PathBuf> = match target.is_file() {
+        true => {
+            if file_has_right_extension(target, &EXTENSIONS).is_ok() {
+                vec![target.to_path_buf()]
+            } else {
+                vec![]
+            }
+        }
+        false => WalkDir::new(target)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect(),
+    };
+
+    // Raise error if no files are admissible
+    if files.is_empty() {
+        return Err(anyhow::Error::msg("No admissible audio files detected"));
+    }
+
+    // Detect speech in parallel, skipping unreadable files
+    let mut reports: Vec<VadReport> = files
+        .par_iter()
+        .filter_map(|file| match detect_file(file, detector) {
+            Ok(report) => Some(report),
+            Err(err) => {
+                eprintln!("Skipping file {:?} due to error: {:#}", file, err);
+                None
+            }
+        })
+        .collect();
+    reports.sort_by(|a, b| a.path.cmp(&b.path));
+
+    if reports.is_empty() {
+        return Err(anyhow::Error::msg("No audio files could be read"));
+    }
+
+    // Print speech statistics
+    let total_duration: f64 = reports.iter().map(|r| r.duration).sum();
+    let speech_duration: f64 = reports.iter().map(|r| r.speech_duration()).sum();
+    let n_segments: usize = reports.iter().map(|r| r.segments.len()).sum();
+    let mut ratios: Vec<f64> = reports.iter().map(|r| r.speech_ratio()).collect();
+    ratios.sort_by(f64::total_cmp);
+
+    println!("Files: {}", reports.len());
+    println!("Speech segments: {}", n_segments);
+    println!("Total duration: {:.2} s", total_duration);
+    println!("Speech duration: {:.2} s", speech_duration);
+    if total_duration > 0.0 {
+        println!("Speech ratio: {:.3}", speech_duration / total_duration);
+    }
+    println!(
+        "Speech ratio per file: min {:.3}, median {:.3}, max {:.3}",
+        ratios[0],
+        ratios[ratios.len() / 2],
+        ratios[ratios.len() - 1]
+    );
+    println!(
+        "Files without speech: {}",
+        reports.iter().filter(|r| r.segments.is_empty()).count()
+    );
+
+    // Write the segments
+    if let Some(output) = &args.output {
+        let mut df = reports_to_table(&reports)?;
+        write_table(&mut df, Path::new(output))
+            .with_context(|| format!("Failed to write table to {:?}", output))?;
+    }
+
+    Ok(())
+}
+
+// Build a table with one row per speech segment
+fn reports_to_table(reports: &[VadReport]) -> Result<DataFrame> {
+    let rows = reports
+        .iter()
+        .flat_map(|r| r.segments.iter().map(move |segment| (&r.path, segment)));
+    let (files, (starts, ends)): (Vec<String>, (Vec<f64>, Vec<f64>)) = rows
+        .map(|(path, &(start, end))| (path.clone(), (start, end)))
+        .unzip();
+
+    df!(
+        "file" => files,
+        "start" => starts,
+        "end" => ends,
+    )
+    .with_context(|| "Failed to build segment table")
+}
+
+// Find the speech segments of a file
+fn detect_file(file: &Path, detector: Detector) -> Result<VadReport> {
+    let (sample_rate, samples) = read_audio(file)?;
+    let n_frames = samples.first().map_or(0, |channel| channel.len());
+    let duration = n_frames as f64 / sample_rate as f64;
+
+    // Mix down to mono
+    let mono: Vec<f64> = (0..n_frames)
+        .map(|i| samples.iter().map(|channel| channel[i]).sum::<f64>() / samples.len() as f64)
+        .collect();
+
+    // Classify frames and turn runs of speech frames into segments
+    let frame_length = ((detector.frame * sample_rate as f64).round() as usize).max(1);
+    let is_speech = classify_frames(&mono, frame_length, detector);
+    let frame_duration = frame_length as f64 / sample_rate as f64;
+
+    let mut segments: Vec<(f64, f64)> = Vec::new();
+    let mut start = None;
+    for (i, &speech) in is_speech.iter().chain([&false]).enumerate() {
+        match (speech, start) {
+            (true, None) => start = Some(i),
+            (false, Some(first)) => {
+                segments.push((first as f64 * frame_duration, i as f64 * frame_duration));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    let segments = smooth_segments(segments, duration, detector);
+
+    Ok(VadReport {
+        path: file.to_string_lossy().to_string(),
+        duration,
+        segments,
+    })
+}
+
+// Speech decision for each frame from its energy and zero-crossing rate
+fn classify_frames(mono: &[f64], frame_length: usize, detector: Detector) -> Vec<bool> {
+    let frames: Vec<&[f64]> = mono.chunks(frame_length).collect();
+
+    // Energy in dBFS of each frame
+    let energies: Vec<f64> = frames
+        .iter()
+        .map(|frame| to_db(rms(&[frame.to_vec()])))
+        .collect();
+    if energies.is_empty() {
+        return Vec::new();
+    }
+
+    // Threshold above the noise floor, lowered for files with little silence
+    let mut sorted = energies.clone();
+    sorted.sort_by(f64::total_cmp);
+    let noise_floor = sorted[((sorted.len() - 1) as f64 * NOISE_FLOOR_PERCENTILE) as usize];
+    let peak = sorted[sorted.len() - 1];
+    let threshold = (noise_floor + detector.threshold)
+        .min(peak - detector.threshold)
+        .max(detector.silence_threshold);
+
+    // Loud frames are speech unless they cross zero as often as broadband noise
+    frames
+        .iter()
+        .zip(&energies)
+        .map(|(frame, &energy)| {
+            let crossings = frame
+                .windows(2)
+                .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+                .count();
+            let zcr = crossings as f64 / frame.len().max(2).saturating_sub(1) as f64;
+            energy > threshold && zcr <= detector.max_zcr
+        })
+        .collect()
+}
+
+// Bridge short pauses, drop short bursts and pad what is left
+fn smooth_segments(
+    segments: Vec<(f64, f64)>,
+    duration: f64,
+    detector: Detector,
+) -> Vec<(f64, f64)> {
+    // Bridge pauses shorter than the minimum silence
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    for (start, end) in segments {
+        match merged.last_mut() {
+            Some(last) if start - last.1 < detector.min_silence => last.1 = end,
+            _ => merged.push((start, end)),
+        }
+    }
+
+    // Drop bursts shorter than the minimum speech, then pad the rest
+    let mut padded: Vec<(f64, f64)> = Vec::new();
+    for (start, end) in merged {
+        if end - start < detector.min_speech {
+            continue;
+        }
+        let start = (start - detector.padding).max(0.0);
+        let end = (end + detector.padding).min(duration);
+        match padded.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => padded.push((start, end)),
+        }
+    }
+
+    padded
+}
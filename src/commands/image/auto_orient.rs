@@ -0,0 +1,99 @@
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::image::image_utils::{open_image, write_metadata};
+use crate::utils::{file_has_right_extension, perform_io_sanity_check};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "bmp", "gif", "tiff"];
+
+use crate::{ImageAutoOrientArgs, MetadataMode};
+
+// Execute the auto-orient command
+pub fn execute(args: ImageAutoOrientArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    let metadata: MetadataMode = args.metadata;
+    let overwrite: bool = args.overwrite;
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, output, metadata, overwrite).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(input: &Path, output: &Path, metadata: MetadataMode, overwrite: bool) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+        process_file(input, output, metadata, overwrite)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output path
+            let file_output = output.join(relative_path);
+
+            // Ensure the output directory exists
+            if let Some(parent) = file_output.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+            }
+
+            // Process the file
+            process_file(file, &file_output, metadata, overwrite)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// Process a single file
+fn process_file(
+    input: &Path,
+    output: &Path,
+    metadata: MetadataMode,
+    overwrite: bool,
+) -> Result<()> {
+    // Check that we can overwrite
+    if input == output && !overwrite {
+        return Err(anyhow::Error::msg("Can't overwrite files"));
+    }
+
+    // Read image, upright
+    let (output_img, exif) = open_image(input, true)?;
+
+    // Save image
+    output_img
+        .save(output)
+        .with_context(|| format!("Couldn't save image to {:?}", output))?;
+    write_metadata(output, exif.as_ref(), metadata, true)?;
+
+    Ok(())
+}
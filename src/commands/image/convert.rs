@@ -0,0 +1,300 @@
+use anyhow::{Context, Result};
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::{ColorType, DynamicImage, ImageEncoder, ImageFormat, ImageOutputFormat};
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::image::image_utils::{open_image, write_metadata};
+use crate::utils::{check_distinct_outputs, file_has_right_extension, perform_io_sanity_check};
+use crate::{ImageColor, ImageConvertArgs, ImageFileFormat, MetadataMode, PngCompression};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "png", "bmp", "gif", "tiff", "webp"];
+
+/// Encoder settings shared by all the files processed
+#[derive(Debug, Clone, Copy)]
+struct Options {
+    format: ImageFileFormat,
+    quality: u8,
+    compression: PngCompression,
+    color: Option<ImageColor>,
+    overwrite: bool,
+    auto_orient: bool,
+    metadata: MetadataMode,
+}
+
+pub fn execute(args: ImageConvertArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    // An output file decides the format unless it is given
+    let format = match (args.format, output.extension().and_then(|e| e.to_str())) {
+        (Some(format), _) => format,
+        (None, Some(extension)) => format_from_extension(extension)?,
+        (None, None) => {
+            return Err(anyhow::Error::msg(
+                "Output format is required when writing to a directory",
+            ))
+        }
+    };
+
+    let options = Options {
+        format,
+        quality: args.quality,
+        compression: args.compression,
+        color: args.color,
+        overwrite: args.overwrite,
+        auto_orient: args.auto_orient,
+        metadata: args.metadata,
+    };
+
+    // Validate the requested encoding
+    if options.quality == 0 || options.quality > 100 {
+        return Err(anyhow::Error::msg("JPEG quality must be between 1 and 100"));
+    }
+    if let Some(color) = options.color {
+        if !supports_color(format, color) {
+            return Err(anyhow::Error::msg(format!(
+                "{:?} can't store {:?} images",
+                format, color
+            )));
+        }
+    }
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, output, options).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(input: &Path, output: &Path, options: Options) -> Result<()> {
+    let extension = extension(options.format);
+
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+
+        // Write next to the other outputs when given a directory
+        let file_output = match output.extension() {
+            Some(_) => output.to_path_buf(),
+            None => {
+                let file_name = input
+                    .file_name()
+                    .with_context(|| format!("Failed to extract file name from: {:?}", input))?;
+                output.join(file_name).with_extension(extension)
+            }
+        };
+        process_file(input, &file_output, options)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files along with their nested output path, with the extension of the format
+        let files: Vec<(PathBuf, PathBuf)> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| -> Result<(PathBuf, PathBuf)> {
+                let file = e.path().to_path_buf();
+                let relative_path = file
+                    .strip_prefix(input)
+                    .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+                let file_output = output.join(relative_path).with_extension(extension);
+                Ok((file, file_output))
+            })
+            .collect::<Result<_>>()?;
+
+        // photo.jpg and photo.png would both become photo.webp
+        check_distinct_outputs(&files)?;
+
+        // Parallel loop over entries
+        files
+            .par_iter()
+            .try_for_each(|(file, file_output)| -> Result<()> {
+                // Ensure the output directory exists
+                if let Some(parent) = file_output.parent() {
+                    std::fs::create_dir_all(parent).with_context(|| {
+                        format!("Failed to create output directory: {:?}", parent)
+                    })?;
+                }
+
+                // Process the file
+                process_file(file, file_output, options)
+                    .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+                Ok(())
+            })?;
+    }
+    Ok(())
+}
+
+// Process a single file
+fn process_file(input: &Path, output: &Path, options: Options) -> Result<()> {
+    // Check that we can overwrite
+    if input == output && !options.overwrite {
+        return Err(anyhow::Error::msg("Can't overwrite files"));
+    }
+
+    // Read image
+    let (input_img, exif) = open_image(input, options.auto_orient)?;
+
+    // Requested color type, or the closest one the format can store
+    let output_img = match options.color {
+        Some(color) => convert_color(input_img, color),
+        None => {
+            let color = closest_color(options.format, input_img.color());
+            convert_color(input_img, color)
+        }
+    };
+
+    // Encode with the requested settings
+    encode(&output_img, output, options)?;
+    write_metadata(output, exif.as_ref(), options.metadata, options.auto_orient)?;
+
+    Ok(())
+}
+
+// Write an image with the encoder settings of the format
+fn encode(output_img: &DynamicImage, output: &Path, options: Options) -> Result<()> {
+    let file =
+        File::create(output).with_context(|| format!("Couldn't create file: {:?}", output))?;
+    let mut writer = BufWriter::new(file);
+    match options.format {
+        ImageFileFormat::Png => {
+            let compression = match options.compression {
+                PngCompression::Fast => CompressionType::Fast,
+                PngCompression::Default => CompressionType::Default,
+                PngCompression::Best => CompressionType::Best,
+            };
+            PngEncoder::new_with_quality(&mut writer, compression, FilterType::Adaptive)
+                .write_image(
+                    output_img.as_bytes(),
+                    output_img.width(),
+                    output_img.height(),
+                    output_img.color(),
+                )
+        }
+        ImageFileFormat::Jpeg => {
+            output_img.write_to(&mut writer, ImageOutputFormat::Jpeg(options.quality))
+        }
+        ImageFileFormat::Webp => output_img.write_to(&mut writer, ImageFormat::WebP),
+        ImageFileFormat::Bmp => output_img.write_to(&mut writer, ImageFormat::Bmp),
+        ImageFileFormat::Tiff => output_img.write_to(&mut writer, ImageFormat::Tiff),
+        ImageFileFormat::Gif => output_img.write_to(&mut writer, ImageFormat::Gif),
+    }
+    .with_context(|| format!("Couldn't save image to {:?}", output))?;
+    writer
+        .flush()
+        .with_context(|| format!("Couldn't save image to {:?}", output))?;
+
+    Ok(())
+}
+
+// Format matching a file extension
+fn format_from_extension(extension: &str) -> Result<ImageFileFormat> {
+    match extension.to_lowercase().as_str() {
+        "png" => Ok(ImageFileFormat::Png),
+        "jpg" | "jpeg" => Ok(ImageFileFormat::Jpeg),
+        "webp" => Ok(ImageFileFormat::Webp),
+        "bmp" => Ok(ImageFileFormat::Bmp),
+        "tif" | "tiff" => Ok(ImageFileFormat::Tiff),
+        "gif" => Ok(ImageFileFormat::Gif),
+        _ => Err(anyhow::Error::msg(format!(
+            "Unsupported output extension: {}",
+            extension
+        ))),
+    }
+}
+
+// Extension of the files written in a format
+fn extension(format: ImageFileFormat) -> &'static str {
+    match format {
+        ImageFileFormat::Png => "png",
+        ImageFileFormat::Jpeg => "jpg",
+        ImageFileFormat::Webp => "webp",
+        ImageFileFormat::Bmp => "bmp",
+        ImageFileFormat::Tiff => "tiff",
+        ImageFileFormat::Gif => "gif",
+    }
+}
+
+// Whether a format can store a color type as is
+fn supports_color(format: ImageFileFormat, color: ImageColor) -> bool {
+    match format {
+        ImageFileFormat::Png => true,
+        // The TIFF encoder has no gray with alpha
+        ImageFileFormat::Tiff => !matches!(color, ImageColor::GrayAlpha | ImageColor::GrayAlpha16),
+        ImageFileFormat::Jpeg => matches!(color, ImageColor::Rgb | ImageColor::Gray),
+        ImageFileFormat::Webp | ImageFileFormat::Bmp => matches!(
+            color,
+            ImageColor::Rgb | ImageColor::Rgba | ImageColor::Gray | ImageColor::GrayAlpha
+        ),
+        ImageFileFormat::Gif => matches!(color, ImageColor::Rgba),
+    }
+}
+
+// Color type of a decoded image, or the closest one a format can store
+fn closest_color(format: ImageFileFormat, color: ColorType) -> ImageColor {
+    let color = match color {
+        ColorType::L8 => ImageColor::Gray,
+        ColorType::La8 => ImageColor::GrayAlpha,
+        ColorType::Rgb8 => ImageColor::Rgb,
+        ColorType::L16 => ImageColor::Gray16,
+        ColorType::La16 => ImageColor::GrayAlpha16,
+        ColorType::Rgb16 => ImageColor::Rgb16,
+        ColorType::Rgba16 => ImageColor::Rgba16,
+        ColorType::Rgb32F => ImageColor::Rgb16,
+        ColorType::Rgba32F => ImageColor::Rgba16,
+        _ => ImageColor::Rgba,
+    };
+    if supports_color(format, color) {
+        return color;
+    }
+
+    // Drop the alpha channel for JPEG, the bit depth for 8-bit formats, and the gray for TIFF
+    match (format, color) {
+        (
+            ImageFileFormat::Jpeg,
+            ImageColor::Gray | ImageColor::GrayAlpha | ImageColor::Gray16 | ImageColor::GrayAlpha16,
+        ) => ImageColor::Gray,
+        (ImageFileFormat::Jpeg, _) => ImageColor::Rgb,
+        (ImageFileFormat::Gif, _) => ImageColor::Rgba,
+        (ImageFileFormat::Tiff, ImageColor::GrayAlpha) => ImageColor::Rgba,
+        (ImageFileFormat::Tiff, ImageColor::GrayAlpha16) => ImageColor::Rgba16,
+        (_, ImageColor::Gray16) => ImageColor::Gray,
+        (_, ImageColor::GrayAlpha16) => ImageColor::GrayAlpha,
+        (_, ImageColor::Rgb16) => ImageColor::Rgb,
+        (_, _) => ImageColor::Rgba,
+    }
+}
+
+// Convert an image to a color type, keeping it when it already matches
+fn convert_color(image: DynamicImage, color: ImageColor) -> DynamicImage {
+    match (color, image.color()) {
+        (ImageColor::Gray, ColorType::L8)
+        | (ImageColor::GrayAlpha, ColorType::La8)
+        | (ImageColor::Rgb, ColorType::Rgb8)
+        | (ImageColor::Rgba, ColorType::Rgba8)
+        | (ImageColor::Gray16, ColorType::L16)
+        | (ImageColor::GrayAlpha16, ColorType::La16)
+        | (ImageColor::Rgb16, ColorType::Rgb16)
+        | (ImageColor::Rgba16, ColorType::Rgba16) => image,
+        (ImageColor::Gray, _) => DynamicImage::ImageLuma8(image.to_luma8()),
+        (ImageColor::GrayAlpha, _) => DynamicImage::ImageLumaA8(image.to_luma_alpha8()),
+        (ImageColor::Rgb, _) => DynamicImage::ImageRgb8(image.to_rgb8()),
+        (ImageColor::Rgba, _) => DynamicImage::ImageRgba8(image.to_rgba8()),
+        (ImageColor::Gray16, _) => DynamicImage::ImageLuma16(image.to_luma16()),
+        (ImageColor::GrayAlpha16, _) => DynamicImage::ImageLumaA16(image.to_luma_alpha16()),
+        (ImageColor::Rgb16, _) => DynamicImage::ImageRgb16(image.to_rgb16()),
+        (ImageColor::Rgba16, _) => DynamicImage::ImageRgba16(image.to_rgba16()),
+    }
+}
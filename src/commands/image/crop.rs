@@ -0,0 +1,403 @@
+use anyhow::{Context, Result};
+use exif::Exif;
+use image::DynamicImage;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::image::image_utils::{open_image, write_metadata};
+use crate::utils::{
+    file_has_right_extension, float_column, perform_io_sanity_check, read_table,
+    relative_directory, sanitize, seed_from_path, string_column,
+};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "bmp", "gif", "tiff"];
+
+// Extensions of manifests
+const MANIFEST_EXTENSIONS: [&str; 2] = ["csv", "parquet"];
+
+use crate::{CropMode, ImageCropArgs, MetadataMode};
+
+/// How every image is cropped
+#[derive(Debug, Clone, Copy)]
+struct CropOptions {
+    mode: CropMode,
+    x: u32,
+    y: u32,
+    width: Option<u32>,
+    height: Option<u32>,
+    aspect: Option<f64>,
+    seed: u64,
+    overwrite: bool,
+    auto_orient: bool,
+    metadata: MetadataMode,
+}
+
+/// A box of the manifest, in pixels
+#[derive(Debug, Clone)]
+struct CropBox {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    output: PathBuf,
+}
+
+// Execute the crop command
+pub fn execute(args: ImageCropArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    let options = CropOptions {
+        mode: args.mode,
+        x: args.x,
+        y: args.y,
+        width: args.width,
+        height: args.height,
+        aspect: args.aspect.as_deref().map(parse_aspect).transpose()?,
+        seed: args.seed,
+        overwrite: args.overwrite,
+        auto_orient: args.auto_orient,
+        metadata: args.metadata,
+    };
+
+    // The size of the crop must be consistent
+    if options.width == Some(0) || options.height == Some(0) {
+        return Err(anyhow::Error::msg("Width and height must be positive"));
+    }
+    if options.width.is_some() && options.height.is_some() && options.aspect.is_some() {
+        return Err(anyhow::Error::msg(
+            "Give at most two of width, height and aspect ratio",
+        ));
+    }
+
+    match args.manifest.as_deref().map(Path::new) {
+        // Boxes of the manifest
+        Some(manifest) => {
+            // Sanity checks on I/O
+            file_has_right_extension(manifest, &MANIFEST_EXTENSIONS)
+                .with_context(|| "Manifest must be a CSV or Parquet file")?;
+            perform_io_sanity_check(input, output, false, false)
+                .with_context(|| "Sanity check failed")?;
+            if !input.is_dir() {
+                return Err(anyhow::Error::msg(
+                    "Input must be a directory when a manifest is given",
+                ));
+            }
+
+            // Read boxes grouped by source file
+            let boxes = read_boxes(&args, manifest, input, output)?;
+
+            // Process files
+            boxes
+                .par_iter()
+                .try_for_each(|(file, boxes)| -> Result<()> {
+                    process_manifest_file(file, boxes, options)
+                        .with_context(|| format!("Failed to process file: {:?}", file))
+                })
+                .with_context(|| "Processing failed")?;
+        }
+        // The same crop for every image
+        None => {
+            // Sanity checks on I/O
+            perform_io_sanity_check(input, output, false, true)
+                .with_context(|| "Sanity check failed")?;
+
+            // Process files
+            process(input, output, options).with_context(|| "Processing failed")?;
+        }
+    }
+
+    Ok(())
+}
+
+// Parse an aspect ratio given as `W:H`
+fn parse_aspect(aspect: &str) -> Result<f64> {
+    let ratio = aspect.split_once(':').and_then(|(width, height)| {
+        Some((
+            width.trim().parse::<f64>().ok()?,
+            height.trim().parse::<f64>().ok()?,
+        ))
+    });
+    match ratio {
+        Some((width, height)) if width > 0.0 && height > 0.0 => Ok(width / height),
+        _ => Err(anyhow::Error::msg(format!(
+            "Invalid aspect ratio {:?}, expected W:H",
+            aspect
+        ))),
+    }
+}
+
+// Process all the content (single file or directory of files)
+fn process(input: &Path, output: &Path, options: CropOptions) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+        let relative_path = Path::new(input.file_name().unwrap_or_default());
+        process_file(input, relative_path, output, options)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output path
+            let file_output = output.join(relative_path);
+
+            // Ensure the output directory exists
+            if let Some(parent) = file_output.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+            }
+
+            // Process the file
+            process_file(file, relative_path, &file_output, options)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// Process a single file
+fn process_file(
+    input: &Path,
+    relative_path: &Path,
+    output: &Path,
+    options: CropOptions,
+) -> Result<()> {
+    // Check that we can overwrite
+    if input == output && !options.overwrite {
+        return Err(anyhow::Error::msg("Can't overwrite files"));
+    }
+
+    // Read image
+    let (input_img, exif) = open_image(input, options.auto_orient)?;
+
+    // Crop image
+    let (x, y, width, height) = crop_region(
+        relative_path,
+        input_img.width(),
+        input_img.height(),
+        options,
+    )?;
+    let output_img = input_img.crop_imm(x, y, width, height);
+
+    // Save image
+    save_crop(&output_img, output, exif.as_ref(), options)
+}
+
+// Region of an image to crop, as x, y, width and height
+fn crop_region(
+    relative_path: &Path,
+    image_width: u32,
+    image_height: u32,
+    options: CropOptions,
+) -> Result<(u32, u32, u32, u32)> {
+    // Room left for the crop, boxes extend to the border of the image at most
+    let (room_width, room_height) = match options.mode {
+        CropMode::Box => {
+            if options.x >= image_width || options.y >= image_height {
+                return Err(anyhow::Error::msg(format!(
+                    "Box starts at ({}, {}), outside of the {}x{} image",
+                    options.x, options.y, image_width, image_height
+                )));
+            }
+            (image_width - options.x, image_height - options.y)
+        }
+        CropMode::Center | CropMode::Random => (image_width, image_height),
+    };
+    let (width, height) = crop_size(room_width, room_height, options);
+
+    let (x, y) = match options.mode {
+        CropMode::Box => (options.x, options.y),
+        CropMode::Center => ((image_width - width) / 2, (image_height - height) / 2),
+        CropMode::Random => {
+            // Seeded per file, so that reruns give the same crops
+            let mut rng = StdRng::seed_from_u64(options.seed ^ seed_from_path(relative_path));
+            (
+                rng.gen_range(0..=image_width - width),
+                rng.gen_range(0..=image_height - height),
+            )
+        }
+    };
+
+    Ok((x, y, width, height))
+}
+
+// Size of the crop, clamped to the room available
+fn crop_size(room_width: u32, room_height: u32, options: CropOptions) -> (u32, u32) {
+    let (width, height) = match (options.width, options.height, options.aspect) {
+        (Some(width), None, Some(aspect)) => (width, (width as f64 / aspect).round() as u32),
+        (None, Some(height), Some(aspect)) => ((height as f64 * aspect).round() as u32, height),
+        (None, None, Some(aspect)) => {
+            // Largest crop with that aspect ratio
+            let width = room_width.min((room_height as f64 * aspect).round() as u32);
+            (width, (width as f64 / aspect).round() as u32)
+        }
+        (width, height, _) => (width.unwrap_or(room_width), height.unwrap_or(room_height)),
+    };
+    (width.clamp(1, room_width), height.clamp(1, room_height))
+}
+
+// Read the manifest into boxes grouped by source file, in manifest order
+fn read_boxes(
+    args: &ImageCropArgs,
+    manifest: &Path,
+    root: &Path,
+    output: &Path,
+) -> Result<BTreeMap<PathBuf, Vec<CropBox>>> {
+    let df = read_table(manifest)?
+        .collect()
+        .with_context(|| format!("Failed to read manifest: {:?}", manifest))?;
+
+    // Required columns
+    let files = string_column(&df, &args.file_column)?
+        .with_context(|| format!("Missing column: {:?}", args.file_column))?;
+    let xs = float_column(&df, &args.x_column)?;
+    let ys = float_column(&df, &args.y_column)?;
+    let widths = float_column(&df, &args.width_column)?;
+    let heights = float_column(&df, &args.height_column)?;
+
+    // Optional columns, used when present
+    let labels = string_column(&df, &args.label_column)?;
+    let names = string_column(&df, &args.name_column)?;
+
+    let mut boxes: BTreeMap<PathBuf, Vec<CropBox>> = BTreeMap::new();
+    let mut outputs = HashSet::new();
+
+    for row in 0..df.height() {
+        let row_error = |message: &str| anyhow::Error::msg(format!("Row {}: {}", row, message));
+
+        let file = files[row]
+            .as_deref()
+            .ok_or_else(|| row_error("missing file"))?;
+        let (x, y, width, height) = match (xs[row], ys[row], widths[row], heights[row]) {
+            (Some(x), Some(y), Some(width), Some(height)) => (x, y, width, height),
+            _ => return Err(row_error("missing x, y, width or height")),
+        };
+        if width <= 0.0 || height <= 0.0 {
+            return Err(row_error("width and height must be positive"));
+        }
+
+        // Relative paths are taken from the input directory
+        let file = root.join(file);
+        let extension = file
+            .extension()
+            .with_context(|| format!("Failed to extract extension from: {:?}", file))?
+            .to_string_lossy()
+            .to_string();
+
+        // Organised by label when there is one
+        let directory = match labels.as_ref().and_then(|labels| labels[row].as_deref()) {
+            Some(label) => output.join(sanitize(label)),
+            None => output.to_path_buf(),
+        };
+
+        // Boxes are numbered per source file, mirroring its directory, unless named
+        let index = boxes.get(&file).map_or(0, |boxes| boxes.len());
+        let box_output = match names.as_ref().and_then(|names| names[row].as_deref()) {
+            Some(name) => directory.join(format!("{}.{}", sanitize(name), extension)),
+            None => {
+                let stem = file
+                    .file_stem()
+                    .with_context(|| format!("Failed to extract stem from: {:?}", file))?
+                    .to_string_lossy()
+                    .to_string();
+                directory
+                    .join(relative_directory(&file, root))
+                    .join(format!("{}@{}.{}", stem, index, extension))
+            }
+        };
+
+        // Two rows must not write to the same file
+        if !outputs.insert(box_output.clone()) {
+            return Err(row_error(&format!(
+                "output {:?} is already used by another row",
+                box_output
+            )));
+        }
+
+        boxes.entry(file).or_default().push(CropBox {
+            x,
+            y,
+            width,
+            height,
+            output: box_output,
+        });
+    }
+
+    Ok(boxes)
+}
+
+// Crop all the boxes of a file
+fn process_manifest_file(input: &Path, boxes: &[CropBox], options: CropOptions) -> Result<()> {
+    // Read image
+    let (input_img, exif) = open_image(input, options.auto_orient)?;
+    let image_width = input_img.width() as f64;
+    let image_height = input_img.height() as f64;
+
+    for crop in boxes {
+        // Check that we can overwrite
+        if crop.output == input && !options.overwrite {
+            return Err(anyhow::Error::msg("Can't overwrite files"));
+        }
+
+        // Boxes are clamped to the image, as annotations often spill over its border
+        let x0 = crop.x.round().clamp(0.0, image_width) as u32;
+        let y0 = crop.y.round().clamp(0.0, image_height) as u32;
+        let x1 = (crop.x + crop.width).round().clamp(0.0, image_width) as u32;
+        let y1 = (crop.y + crop.height).round().clamp(0.0, image_height) as u32;
+        if x0 >= x1 || y0 >= y1 {
+            return Err(anyhow::Error::msg(format!(
+                "Box ({}, {}, {}, {}) is outside of the image",
+                crop.x, crop.y, crop.width, crop.height
+            )));
+        }
+
+        // Ensure the output directory exists
+        if let Some(parent) = crop.output.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+        }
+
+        let output_img = input_img.crop_imm(x0, y0, x1 - x0, y1 - y0);
+        save_crop(&output_img, &crop.output, exif.as_ref(), options)?;
+    }
+
+    Ok(())
+}
+
+// Save a crop along with the metadata of its image
+fn save_crop(
+    image: &DynamicImage,
+    output: &Path,
+    exif: Option<&Exif>,
+    options: CropOptions,
+) -> Result<()> {
+    image
+        .save(output)
+        .with_context(|| format!("Couldn't save image to {:?}", output))?;
+    write_metadata(output, exif, options.metadata, options.auto_orient)
+}
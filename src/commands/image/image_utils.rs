@@ -0,0 +1,191 @@
+use anyhow::{Context, Result};
+use exif::experimental::Writer;
+use exif::{Exif, Field, In, Tag, Value};
+use image::io::Reader as ImageReader;
+use image::{image_dimensions, DynamicImage};
+use std::fs::{self, File};
+use std::io::{BufReader, Cursor};
+use std::path::Path;
+
+use crate::MetadataMode;
+
+/// Read the EXIF metadata of a file, `None` when it has none or it can't be parsed
+pub fn read_exif(path: &Path) -> Option<Exif> {
+    let file = File::open(path).ok()?;
+    exif::Reader::new()
+        .read_from_container(&mut BufReader::new(file))
+        .ok()
+}
+
+/// EXIF orientation of the primary image, 1 when missing or invalid
+pub fn orientation(exif: Option<&Exif>) -> u32 {
+    exif.and_then(|exif| exif.get_field(Tag::Orientation, In::PRIMARY))
+        .and_then(|field| field.value.get_uint(0))
+        .filter(|orientation| (1..=8).contains(orientation))
+        .unwrap_or(1)
+}
+
+/// Rotate and flip an image so that it displays upright for an EXIF orientation
+pub fn apply_orientation(image: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+/// Open and decode an image along with its EXIF metadata, applying the orientation if requested
+pub fn open_image(path: &Path, auto_orient: bool) -> Result<(DynamicImage, Option<Exif>)> {
+    let image = ImageReader::open(path)
+        .with_context(|| "Can't open image")?
+        .with_guessed_format()
+        .with_context(|| "Can't guess image format")?
+        .decode()
+        .with_context(|| "Can't decode image")?;
+    let exif = read_exif(path);
+
+    let image = match auto_orient {
+        true => apply_orientation(image, orientation(exif.as_ref())),
+        false => image,
+    };
+    Ok((image, exif))
+}
+
+/// Write the EXIF metadata of the input into a freshly encoded JPEG or PNG file
+///
+/// Encoders never write metadata, so `MetadataMode::Strip` leaves the file untouched. When the
+/// orientation was applied to the pixels, the Orientation tag is reset so that viewers don't
+/// rotate the image twice. Other formats are left without metadata.
+pub fn write_metadata(
+    path: &Path,
+    exif: Option<&Exif>,
+    mode: MetadataMode,
+    oriented: bool,
+) -> Result<()> {
+    let exif = match (mode, exif) {
+        (MetadataMode::Strip, _) | (_, None) => return Ok(()),
+        (_, Some(exif)) => exif,
+    };
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    if !["jpg", "jpeg", "png"].contains(&extension.as_str()) {
+        return Ok(());
+    }
+
+    // Fields describing the written image rather than the original one
+    let (width, height) = image_dimensions(path)?;
+    let replaced = |field: &Field| -> Option<Value> {
+        match field.tag {
+            Tag::Orientation if oriented => Some(Value::Short(vec![1])),
+            Tag::PixelXDimension => Some(Value::Long(vec![width])),
+            Tag::PixelYDimension => Some(Value::Long(vec![height])),
+            _ => None,
+        }
+    };
+
+    // Primary image fields only, the thumbnail is not carried over
+    let fields: Vec<Field> = exif
+        .fields()
+        .filter(|field| field.ifd_num == In::PRIMARY)
+        .filter(|field| {
+            mode != MetadataMode::KeepNoGps || field.tag.context() != exif::Context::Gps
+        })
+        .map(|field| Field {
+            tag: field.tag,
+            ifd_num: field.ifd_num,
+            value: replaced(field).unwrap_or_else(|| field.value.clone()),
+        })
+        .collect();
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    // Encode the fields as TIFF data
+    let mut writer = Writer::new();
+    fields.iter().for_each(|field| writer.push_field(field));
+    let mut tiff = Cursor::new(Vec::new());
+    writer
+        .write(&mut tiff, exif.little_endian())
+        .with_context(|| "Failed to encode EXIF metadata")?;
+    let tiff = tiff.into_inner();
+
+    // Insert it in the container
+    let data = fs::read(path).with_context(|| format!("Failed to read file: {:?}", path))?;
+    let data = match extension.as_str() {
+        "png" => insert_png_exif(&data, &tiff)?,
+        _ => insert_jpeg_exif(&data, &tiff)?,
+    };
+    fs::write(path, data).with_context(|| format!("Failed to write file: {:?}", path))?;
+
+    Ok(())
+}
+
+// Insert an APP1 segment after the SOI marker and any JFIF APP0 segment
+fn insert_jpeg_exif(data: &[u8], tiff: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < 4 || data[..2] != [0xFF, 0xD8] {
+        return Err(anyhow::Error::msg("Not a JPEG file"));
+    }
+    let segment_length = tiff.len() + 8;
+    if segment_length > u16::MAX as usize {
+        return Err(anyhow::Error::msg("EXIF metadata too large for JPEG"));
+    }
+
+    let mut position = 2;
+    if data[2..4] == [0xFF, 0xE0] && data.len() >= 6 {
+        position += 2 + u16::from_be_bytes([data[4], data[5]]) as usize;
+    }
+
+    let mut output = Vec::with_capacity(data.len() + segment_length + 2);
+    output.extend_from_slice(&data[..position]);
+    output.extend_from_slice(&[0xFF, 0xE1]);
+    output.extend_from_slice(&(segment_length as u16).to_be_bytes());
+    output.extend_from_slice(b"Exif\0\0");
+    output.extend_from_slice(tiff);
+    output.extend_from_slice(&data[position..]);
+    Ok(output)
+}
+
+// Insert an eXIf chunk right after the IHDR chunk
+fn insert_png_exif(data: &[u8], tiff: &[u8]) -> Result<Vec<u8>> {
+    // Signature, then the IHDR chunk with its 13 bytes of data
+    const IHDR_END: usize = 8 + 4 + 4 + 13 + 4;
+    if data.len() < IHDR_END || &data[12..16] != b"IHDR" {
+        return Err(anyhow::Error::msg("Not a PNG file"));
+    }
+
+    let mut chunk = Vec::with_capacity(tiff.len() + 4);
+    chunk.extend_from_slice(b"eXIf");
+    chunk.extend_from_slice(tiff);
+
+    let mut output = Vec::with_capacity(data.len() + chunk.len() + 8);
+    output.extend_from_slice(&data[..IHDR_END]);
+    output.extend_from_slice(&(tiff.len() as u32).to_be_bytes());
+    output.extend_from_slice(&chunk);
+    output.extend_from_slice(&crc32(&chunk).to_be_bytes());
+    output.extend_from_slice(&data[IHDR_END..]);
+    Ok(output)
+}
+
+// CRC-32 of PNG chunks
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFF_u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
@@ -1,3 +1,7 @@
+pub mod auto_orient;
+pub mod convert;
+pub mod crop;
+pub mod image_utils;
 pub mod resize;
 pub mod summary;
 pub mod tessellate;
@@ -1,16 +1,34 @@
 use anyhow::{Context, Result};
-use image::imageops::{resize, FilterType};
-use image::io::Reader as ImageReader;
+use image::imageops::{overlay, FilterType};
+use image::{DynamicImage, Rgba, RgbaImage};
 use rayon::prelude::*;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::commands::image::image_utils::{open_image, write_metadata};
 use crate::utils::{file_has_right_extension, perform_io_sanity_check};
 
 // Admissible extensions for this command
 const EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "bmp", "gif", "tiff"];
 
-use crate::ImageResizeArgs;
+use crate::{ImageResizeArgs, MetadataMode, ResizeFilter, ResizeMode};
+
+/// How every image is resized
+#[derive(Debug, Clone, Copy)]
+struct ResizeOptions {
+    /// Requested sides, 0 when left free
+    height: u32,
+    width: u32,
+    mode: ResizeMode,
+    /// Side length of shortest and longest modes
+    size: u32,
+    /// Factor of scale mode
+    factor: f64,
+    filter: FilterType,
+    pad_color: [u8; 3],
+    auto_orient: bool,
+    metadata: MetadataMode,
+}
 
 // Execute the resize command
 pub fn execute(args: ImageResizeArgs) -> Result<()> {
@@ -18,8 +36,66 @@ pub fn execute(args: ImageResizeArgs) -> Result<()> {
     let input = Path::new(&args.input);
     let output = Path::new(&args.output);
 
-    let height: u32 = args.height;
-    let width: u32 = args.width;
+    // Shortest and longest take a size and scale a factor, the other modes neither
+    let (size, factor) = match (args.mode, args.size, args.factor) {
+        (ResizeMode::Shortest | ResizeMode::Longest, Some(size), None) if size > 0 => (size, 1.0),
+        (ResizeMode::Scale, None, Some(factor)) if factor > 0.0 => (0, factor),
+        (ResizeMode::Shortest | ResizeMode::Longest, _, _) => {
+            return Err(anyhow::Error::msg(format!(
+                "{:?} mode requires a positive --size and no --factor",
+                args.mode
+            )))
+        }
+        (ResizeMode::Scale, _, _) => {
+            return Err(anyhow::Error::msg(
+                "Scale mode requires a positive --factor and no --size",
+            ))
+        }
+        (_, None, None) => (0, 1.0),
+        (mode, _, _) => {
+            return Err(anyhow::Error::msg(format!(
+                "--size and --factor can't be used in {:?} mode",
+                mode
+            )))
+        }
+    };
+
+    if args.height == Some(0) || args.width == Some(0) {
+        return Err(anyhow::Error::msg("Height and width must be positive"));
+    }
+
+    // Only stretch and fit can leave a side free, and modes sized otherwise use neither
+    let valid = match args.mode {
+        ResizeMode::Stretch | ResizeMode::Fit => args.height.is_some() || args.width.is_some(),
+        ResizeMode::Fill | ResizeMode::Pad => args.height.is_some() && args.width.is_some(),
+        ResizeMode::Shortest | ResizeMode::Longest | ResizeMode::Scale => {
+            args.height.is_none() && args.width.is_none()
+        }
+    };
+    if !valid {
+        return Err(anyhow::Error::msg(format!(
+            "Invalid --height and --width for {:?} mode",
+            args.mode
+        )));
+    }
+
+    let options = ResizeOptions {
+        height: args.height.unwrap_or(0),
+        width: args.width.unwrap_or(0),
+        mode: args.mode,
+        size,
+        factor,
+        filter: match args.filter {
+            ResizeFilter::Nearest => FilterType::Nearest,
+            ResizeFilter::Triangle => FilterType::Triangle,
+            ResizeFilter::CatmullRom => FilterType::CatmullRom,
+            ResizeFilter::Gaussian => FilterType::Gaussian,
+            ResizeFilter::Lanczos3 => FilterType::Lanczos3,
+        },
+        pad_color: parse_color(&args.pad_color)?,
+        auto_orient: args.auto_orient,
+        metadata: args.metadata,
+    };
 
     let overwrite: bool = args.overwrite;
 
@@ -27,18 +103,18 @@ pub fn execute(args: ImageResizeArgs) -> Result<()> {
     perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
 
     // Process files
-    process(input, height, width, output, overwrite).with_context(|| "Processing failed")?;
+    process(input, options, output, overwrite).with_context(|| "Processing failed")?;
 
     Ok(())
 }
 
 // Process all the content (single file or directory of files)
-fn process(input: &Path, height: u32, width: u32, output: &Path, overwrite: bool) -> Result<()> {
+fn process(input: &Path, options: ResizeOptions, output: &Path, overwrite: bool) -> Result<()> {
     // Case of single input file
     if input.is_file() {
         // Check if the file has the right extension and process it
         file_has_right_extension(input, &EXTENSIONS)?;
-        process_file(input, height, width, output, overwrite)
+        process_file(input, options, output, overwrite)
             .with_context(|| format!("Failed to process file: {:?}", input))?;
     }
     // Case of input being a directory
@@ -68,7 +144,7 @@ fn process(input: &Path, height: u32, width: u32, output: &Path, overwrite: bool
             }
 
             // Process the file
-            process_file(file, height, width, &file_output, overwrite)
+            process_file(file, options, &file_output, overwrite)
                 .with_context(|| format!("Failed to process file: {:?}", file))?;
 
             Ok(())
@@ -80,8 +156,7 @@ fn process(input: &Path, height: u32, width: u32, output: &Path, overwrite: bool
 // Process a single file
 fn process_file(
     input: &Path,
-    height: u32,
-    width: u32,
+    options: ResizeOptions,
     output: &Path,
     overwrite: bool,
 ) -> Result<()> {
@@ -91,18 +166,115 @@ fn process_file(
     }
 
     // Read image
-    let input_img = ImageReader::open(input)
-        .with_context(|| "Can't open image")?
-        .decode()
-        .with_context(|| "Can't decode image")?;
+    let (input_img, exif) = open_image(input, options.auto_orient)?;
 
     // Resize image
-    let output_img = resize(&input_img, width, height, FilterType::Lanczos3);
+    let output_img = resize_image(&input_img, options);
 
     // Save image
     output_img
-        .save(&output)
+        .save(output)
         .with_context(|| format!("Couldn't save image to {:?}", output))?;
+    write_metadata(output, exif.as_ref(), options.metadata, options.auto_orient)?;
 
     Ok(())
 }
+
+// Resize an image according to the mode, keeping its color type except when padding
+fn resize_image(image: &DynamicImage, options: ResizeOptions) -> DynamicImage {
+    let (width, height) = (image.width() as f64, image.height() as f64);
+    let (target_width, target_height) = (options.width as f64, options.height as f64);
+
+    // Scale factors to match the requested width and height, a side of 0 is free
+    let scale_x = if options.width > 0 {
+        target_width / width
+    } else {
+        f64::INFINITY
+    };
+    let scale_y = if options.height > 0 {
+        target_height / height
+    } else {
+        f64::INFINITY
+    };
+    let scaled = |scale: f64| {
+        (
+            ((width * scale).round() as u32).max(1),
+            ((height * scale).round() as u32).max(1),
+        )
+    };
+
+    match options.mode {
+        ResizeMode::Stretch => {
+            let (new_width, new_height) = match (options.width, options.height) {
+                (0, _) => scaled(scale_y),
+                (_, 0) => scaled(scale_x),
+                (new_width, new_height) => (new_width, new_height),
+            };
+            image.resize_exact(new_width, new_height, options.filter)
+        }
+        ResizeMode::Fit => {
+            let (new_width, new_height) = scaled(scale_x.min(scale_y));
+            image.resize_exact(new_width, new_height, options.filter)
+        }
+        ResizeMode::Fill => {
+            let (new_width, new_height) = scaled(scale_x.max(scale_y));
+            let resized = image.resize_exact(
+                new_width.max(options.width),
+                new_height.max(options.height),
+                options.filter,
+            );
+            resized.crop_imm(
+                (resized.width() - options.width) / 2,
+                (resized.height() - options.height) / 2,
+                options.width,
+                options.height,
+            )
+        }
+        ResizeMode::Pad => {
+            let (new_width, new_height) = scaled(scale_x.min(scale_y));
+            let resized = image
+                .resize_exact(new_width, new_height, options.filter)
+                .to_rgba8();
+            let [r, g, b] = options.pad_color;
+            let mut canvas =
+                RgbaImage::from_pixel(options.width, options.height, Rgba([r, g, b, 255]));
+            overlay(
+                &mut canvas,
+                &resized,
+                ((options.width - new_width.min(options.width)) / 2) as i64,
+                ((options.height - new_height.min(options.height)) / 2) as i64,
+            );
+
+            // Keep the alpha channel only for images that had one
+            match image.color().has_alpha() {
+                true => DynamicImage::ImageRgba8(canvas),
+                false => DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(canvas).to_rgb8()),
+            }
+        }
+        ResizeMode::Shortest => {
+            let (new_width, new_height) = scaled(options.size as f64 / width.min(height));
+            image.resize_exact(new_width, new_height, options.filter)
+        }
+        ResizeMode::Longest => {
+            let (new_width, new_height) = scaled(options.size as f64 / width.max(height));
+            image.resize_exact(new_width, new_height, options.filter)
+        }
+        ResizeMode::Scale => {
+            let (new_width, new_height) = scaled(options.factor);
+            image.resize_exact(new_width, new_height, options.filter)
+        }
+    }
+}
+
+// Parse a color given as RRGGBB hex, with or without a leading #
+fn parse_color(color: &str) -> Result<[u8; 3]> {
+    let hex = color.trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow::Error::msg(format!(
+            "Invalid color, expected RRGGBB hex: {}",
+            color
+        )));
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
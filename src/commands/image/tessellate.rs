@@ -1,16 +1,16 @@
 use anyhow::{Context, Result};
-use image::io::Reader as ImageReader;
 use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::commands::image::image_utils::{open_image, write_metadata};
 use crate::utils::{file_has_right_extension, perform_io_sanity_check};
 
 // Admissible extensions for this command
 const EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "bmp", "gif", "tiff"];
 
-use crate::ImageTessellateArgs;
+use crate::{ImageTessellateArgs, MetadataMode};
 
 // Execute the resize command
 pub fn execute(args: ImageTessellateArgs) -> Result<()> {
@@ -23,12 +23,23 @@ pub fn execute(args: ImageTessellateArgs) -> Result<()> {
 
     let delete_original: bool = args.delete_original;
 
+    let auto_orient: bool = args.auto_orient;
+    let metadata: MetadataMode = args.metadata;
+
     // Sanity checks on I/O
     perform_io_sanity_check(input, output, false, false).with_context(|| "Sanity check failed")?;
 
     // Process files
-    process(input, n_vertical, n_horizontal, output, delete_original)
-        .with_context(|| "Processing failed")?;
+    process(
+        input,
+        n_vertical,
+        n_horizontal,
+        output,
+        delete_original,
+        auto_orient,
+        metadata,
+    )
+    .with_context(|| "Processing failed")?;
 
     Ok(())
 }
@@ -40,13 +51,22 @@ fn process(
     n_horizontal: u32,
     output: &Path,
     delete_original: bool,
+    auto_orient: bool,
+    metadata: MetadataMode,
 ) -> Result<()> {
     // Case of single input file
     if input.is_file() {
         // Check if the file has the right extension and process it
         file_has_right_extension(input, &EXTENSIONS)?;
-        process_file(input, n_vertical, n_horizontal, output)
-            .with_context(|| format!("Failed to process file: {:?}", input))?;
+        process_file(
+            input,
+            n_vertical,
+            n_horizontal,
+            output,
+            auto_orient,
+            metadata,
+        )
+        .with_context(|| format!("Failed to process file: {:?}", input))?;
         if delete_original {
             fs::remove_file(input)
                 .with_context(|| format!("Failed to delete file: {:?}", input))?;
@@ -80,8 +100,15 @@ fn process(
             })?;
 
             // Process the file
-            process_file(file, n_vertical, n_horizontal, output_directory)
-                .with_context(|| format!("Failed to process file: {:?}", file))?;
+            process_file(
+                file,
+                n_vertical,
+                n_horizontal,
+                output_directory,
+                auto_orient,
+                metadata,
+            )
+            .with_context(|| format!("Failed to process file: {:?}", file))?;
 
             if delete_original {
                 fs::remove_file(file)
@@ -95,12 +122,16 @@ fn process(
 }
 
 // Process one file
-fn process_file(input: &Path, n_vertical: u32, n_horizontal: u32, output: &Path) -> Result<()> {
+fn process_file(
+    input: &Path,
+    n_vertical: u32,
+    n_horizontal: u32,
+    output: &Path,
+    auto_orient: bool,
+    metadata: MetadataMode,
+) -> Result<()> {
     // Read image
-    let input_img = ImageReader::open(input)
-        .with_context(|| "Can't open image")?
-        .decode()
-        .with_context(|| "Can't decode image")?;
+    let (input_img, exif) = open_image(input, auto_orient)?;
 
     // Get image dimensions
     let image_width = input_img.width();
@@ -217,6 +248,7 @@ fn process_file(input: &Path, n_vertical: u32, n_horizontal: u32, output: &Path)
         patch
             .save(&output_path)
             .with_context(|| format!("Couldn't save image to {:?}", output_path))?;
+        write_metadata(&output_path, exif.as_ref(), metadata, auto_orient)?;
     }
 
     Ok(())
@@ -1,17 +1,15 @@
 use anyhow::{Context, Result};
-use image::image_dimensions;
-use image::imageops::rotate90;
-use image::io::Reader as ImageReader;
 use rayon::prelude::*;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::commands::image::image_utils::{open_image, write_metadata};
 use crate::utils::{file_has_right_extension, perform_io_sanity_check};
 
 // Admissible extensions for this command
 const EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "bmp", "gif", "tiff"];
 
-use crate::ImageToPortraitArgs;
+use crate::{ImageToPortraitArgs, MetadataMode};
 
 // Execute the resize command
 pub fn execute(args: ImageToPortraitArgs) -> Result<()> {
@@ -21,22 +19,32 @@ pub fn execute(args: ImageToPortraitArgs) -> Result<()> {
 
     let overwrite: bool = args.overwrite;
 
+    let auto_orient: bool = args.auto_orient;
+    let metadata: MetadataMode = args.metadata;
+
     // Sanity checks on I/O
     perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
 
     // Process files
-    process(input, output, overwrite).with_context(|| "Processing failed")?;
+    process(input, output, overwrite, auto_orient, metadata)
+        .with_context(|| "Processing failed")?;
 
     Ok(())
 }
 
 // Process all the content (single file or directory of files)
-fn process(input: &Path, output: &Path, overwrite: bool) -> Result<()> {
+fn process(
+    input: &Path,
+    output: &Path,
+    overwrite: bool,
+    auto_orient: bool,
+    metadata: MetadataMode,
+) -> Result<()> {
     // Case of single input file
     if input.is_file() {
         // Check if the file has the right extension and process it
         file_has_right_extension(input, &EXTENSIONS)?;
-        process_file(input, output, overwrite)
+        process_file(input, output, overwrite, auto_orient, metadata)
             .with_context(|| format!("Failed to process file: {:?}", input))?;
     }
     // Case of input being a directory
@@ -47,11 +55,6 @@ fn process(input: &Path, output: &Path, overwrite: bool) -> Result<()> {
             .filter_map(|e| e.ok())
             .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
             .map(|e| e.path().to_path_buf())
-            .filter(|path| {
-                image_dimensions(path)
-                    .map(|(width, height)| width > height)
-                    .is_ok()
-            })
             .collect();
 
         // Parallel loop over entries
@@ -71,7 +74,7 @@ fn process(input: &Path, output: &Path, overwrite: bool) -> Result<()> {
             }
 
             // Process the file
-            process_file(file, &file_output, overwrite)
+            process_file(file, &file_output, overwrite, auto_orient, metadata)
                 .with_context(|| format!("Failed to process file: {:?}", file))?;
 
             Ok(())
@@ -81,25 +84,32 @@ fn process(input: &Path, output: &Path, overwrite: bool) -> Result<()> {
 }
 
 // Process a single file
-fn process_file(input: &Path, output: &Path, overwrite: bool) -> Result<()> {
+fn process_file(
+    input: &Path,
+    output: &Path,
+    overwrite: bool,
+    auto_orient: bool,
+    metadata: MetadataMode,
+) -> Result<()> {
     // Check that we can overwrite
     if input == output && !overwrite {
         return Err(anyhow::Error::msg("Can't overwrite files"));
     }
 
     // Read image
-    let input_img = ImageReader::open(input)
-        .with_context(|| "Can't open image")?
-        .decode()
-        .with_context(|| "Can't decode image")?;
+    let (input_img, exif) = open_image(input, auto_orient)?;
 
-    // Resize image
-    let output_img = rotate90(&input_img);
+    // Rotate image, unless it already has the right orientation
+    let output_img = match input_img.width() > input_img.height() {
+        true => input_img.rotate90(),
+        false => input_img,
+    };
 
     // Save image
     output_img
-        .save(&output)
+        .save(output)
         .with_context(|| format!("Couldn't save image to {:?}", output))?;
+    write_metadata(output, exif.as_ref(), metadata, auto_orient)?;
 
     Ok(())
 }
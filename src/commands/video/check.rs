@@ -0,0 +1,362 @@
+extern crate ffmpeg_next as ffmpeg;
+
+use anyhow::{Context, Result};
+use ffmpeg::media::Type;
+use polars::prelude::*;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::utils::{file_has_right_extension, write_table};
+use crate::VideoCheckArgs;
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 4] = ["ts", "mp4", "mkv", "mov"];
+
+/// Decoding state and findings for a single video or audio stream
+struct StreamCheck {
+    index: usize,
+    medium: Type,
+    decoder: ffmpeg::decoder::Opened,
+    time_base: f64,
+    /// Duration announced by the stream header, in seconds
+    header_duration: Option<f64>,
+    packets: u64,
+    frames: u64,
+    /// Smallest presentation time and largest end time of the packets read, in time base units
+    extent: Option<(i64, i64)>,
+    last_dts: Option<i64>,
+    timestamp_errors: usize,
+    decode_errors: usize,
+    first_decode_error: Option<String>,
+}
+
+impl StreamCheck {
+    // Seconds covered by the packets read so far
+    fn read_duration(&self) -> f64 {
+        self.extent
+            .map_or(0.0, |(start, end)| (end - start) as f64 * self.time_base)
+    }
+
+    // Record a decode error, keeping the first message
+    fn decode_error(&mut self, message: String) {
+        self.decode_errors += 1;
+        self.first_decode_error.get_or_insert(message);
+    }
+
+    // Count the frames the decoder has ready, including those it flags as corrupt,
+    // into the buffer matching the stream type
+    fn receive_frames(
+        &mut self,
+        video: &mut ffmpeg::frame::Video,
+        audio: &mut ffmpeg::frame::Audio,
+    ) {
+        let frame: &mut ffmpeg::Frame = match self.medium {
+            Type::Video => video,
+            _ => audio,
+        };
+        while self.decoder.receive_frame(frame).is_ok() {
+            self.frames += 1;
+            if unsafe { (*frame.as_ptr()).decode_error_flags } != 0 {
+                self.decode_error(format!("corrupt frame {}", self.frames - 1));
+            }
+        }
+    }
+}
+
+/// Thresholds used to flag issues
+#[derive(Debug, Clone, Copy)]
+struct Thresholds {
+    duration_tolerance: f64,
+}
+
+/// Result of checking a single file
+struct CheckReport {
+    path: String,
+    container_duration: Option<f64>,
+    decoded_duration: f64,
+    video_frames: u64,
+    audio_frames: u64,
+    decode_errors: usize,
+    timestamp_errors: usize,
+    issues: Vec<String>,
+}
+
+pub fn execute(args: VideoCheckArgs) -> Result<()> {
+    // Parse the arguments
+    let target = Path::new(&args.target);
+
+    let thresholds = Thresholds {
+        duration_tolerance: args.duration_tolerance,
+    };
+
+    // Error if it does not exist at all
+    if !target.exists() {
+        return Err(anyhow::Error::msg(
+            "Target file or directory does not exist",
+        ));
+    }
+
+    // Find all admissible files
+    let files: Vec<PathBuf> = match target.is_file() {
+        true => {
+            if file_has_right_extension(target, &EXTENSIONS).is_ok() {
+                vec![target.to_path_buf()]
+            } else {
+                vec![]
+            }
+        }
+        false => WalkDir::new(target)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect(),
+    };
+
+    // Raise error if no files are admissible
+    if files.is_empty() {
+        return Err(anyhow::Error::msg("No admissible video files detected"));
+    }
+
+    // Check files, unreadable files are reported rather than skipped
+    let mut reports: Vec<CheckReport> = files
+        .par_iter()
+        .map(|file| check_file(file, thresholds))
+        .collect();
+    reports.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // Print files with issues
+    let flagged: Vec<&CheckReport> = reports.iter().filter(|r| !r.issues.is_empty()).collect();
+    for report in &flagged {
+        println!("{}: {}", report.path, report.issues.join("; "));
+    }
+
+    println!("Checked files: {}", reports.len());
+    println!("Files with issues: {}", flagged.len());
+
+    // Write the full report
+    if let Some(output) = &args.output {
+        let mut df = reports_to_table(&reports)?;
+        write_table(&mut df, Path::new(output))
+            .with_context(|| format!("Failed to write table to {:?}", output))?;
+    }
+
+    // Fail so that scripts can rely on the exit code
+    if !flagged.is_empty() {
+        return Err(anyhow::Error::msg(format!(
+            "{} of {} files have issues",
+            flagged.len(),
+            reports.len()
+        )));
+    }
+
+    Ok(())
+}
+
+// Build a table with one row per file
+fn reports_to_table(reports: &[CheckReport]) -> Result<DataFrame> {
+    df!(
+        "path" => reports.iter().map(|r| r.path.clone()).collect::<Vec<_>>(),
+        "container_duration" => reports.iter().map(|r| r.container_duration).collect::<Vec<_>>(),
+        "decoded_duration" => reports.iter().map(|r| r.decoded_duration).collect::<Vec<_>>(),
+        "video_frames" => reports.iter().map(|r| r.video_frames).collect::<Vec<_>>(),
+        "audio_frames" => reports.iter().map(|r| r.audio_frames).collect::<Vec<_>>(),
+        "decode_errors" => reports.iter().map(|r| r.decode_errors as u64).collect::<Vec<_>>(),
+        "timestamp_errors" => reports.iter().map(|r| r.timestamp_errors as u64).collect::<Vec<_>>(),
+        "issues" => reports.iter().map(|r| r.issues.join("; ")).collect::<Vec<_>>(),
+    )
+    .with_context(|| "Failed to build check table")
+}
+
+// Decode every stream of a file and run all the checks on it
+fn check_file(file: &Path, thresholds: Thresholds) -> CheckReport {
+    let mut report = CheckReport {
+        path: file.to_string_lossy().to_string(),
+        container_duration: None,
+        decoded_duration: 0.0,
+        video_frames: 0,
+        audio_frames: 0,
+        decode_errors: 0,
+        timestamp_errors: 0,
+        issues: Vec::new(),
+    };
+
+    // Read context
+    let mut context = match ffmpeg::format::input(&file) {
+        Ok(context) => context,
+        Err(e) => {
+            report.issues.push(format!("unreadable header: {}", e));
+            return report;
+        }
+    };
+    report.container_duration = match context.duration() {
+        duration if duration > 0 => Some(duration as f64 / ffmpeg::ffi::AV_TIME_BASE as f64),
+        _ => None,
+    };
+
+    // Open a decoder for every video and audio stream
+    let mut streams: Vec<Option<StreamCheck>> = Vec::new();
+    for stream in context.streams() {
+        let medium = stream.parameters().medium();
+        let check = match medium {
+            Type::Video | Type::Audio => match open_stream(&stream) {
+                Ok(check) => Some(check),
+                Err(e) => {
+                    report
+                        .issues
+                        .push(format!("undecodable stream {}: {:#}", stream.index(), e));
+                    None
+                }
+            },
+            _ => None,
+        };
+        streams.push(check);
+    }
+    if context.streams().best(Type::Video).is_none() {
+        report.issues.push("no video stream".to_string());
+    }
+
+    // Read packets until the end of the file or the first read error
+    let mut video_frame = ffmpeg::frame::Video::empty();
+    let mut audio_frame = ffmpeg::frame::Audio::empty();
+    loop {
+        let mut packet = ffmpeg::Packet::empty();
+        match packet.read(&mut context) {
+            Ok(()) => {}
+            Err(ffmpeg::Error::Eof) => break,
+            Err(e) => {
+                report.issues.push(format!("truncated file: {}", e));
+                break;
+            }
+        }
+        let Some(stream) = streams.get_mut(packet.stream()).and_then(|s| s.as_mut()) else {
+            continue;
+        };
+        stream.packets += 1;
+
+        // Decoding timestamps must strictly increase
+        if let Some(dts) = packet.dts() {
+            if stream.last_dts.is_some_and(|last| dts <= last) {
+                stream.timestamp_errors += 1;
+            }
+            stream.last_dts = Some(dts);
+        }
+
+        // Extent of the presentation times
+        if let Some(pts) = packet.pts().or(packet.dts()) {
+            let end = pts + packet.duration().max(0);
+            stream.extent = Some(match stream.extent {
+                Some((start, last_end)) => (start.min(pts), last_end.max(end)),
+                None => (pts, end),
+            });
+        }
+
+        if let Err(e) = stream.decoder.send_packet(&packet) {
+            stream.decode_error(e.to_string());
+            continue;
+        }
+        stream.receive_frames(&mut video_frame, &mut audio_frame);
+    }
+
+    // Drain the decoders
+    for stream in streams.iter_mut().flatten() {
+        if stream.decoder.send_eof().is_ok() {
+            stream.receive_frames(&mut video_frame, &mut audio_frame);
+        }
+    }
+
+    // Per-stream findings
+    for stream in streams.iter().flatten() {
+        let kind = match stream.medium {
+            Type::Video => "video",
+            _ => "audio",
+        };
+        match stream.medium {
+            Type::Video => report.video_frames += stream.frames,
+            _ => report.audio_frames += stream.frames,
+        }
+        report.decode_errors += stream.decode_errors;
+        report.timestamp_errors += stream.timestamp_errors;
+        report.decoded_duration = report.decoded_duration.max(stream.read_duration());
+
+        if stream.packets == 0 || stream.frames == 0 {
+            report
+                .issues
+                .push(format!("empty {} stream {}", kind, stream.index));
+        }
+        if stream.decode_errors > 0 {
+            report.issues.push(format!(
+                "decode errors in {} stream {}: {} ({})",
+                kind,
+                stream.index,
+                stream.decode_errors,
+                stream.first_decode_error.as_deref().unwrap_or_default()
+            ));
+        }
+        if stream.timestamp_errors > 0 {
+            report.issues.push(format!(
+                "non-monotonic timestamps in {} stream {}: {} packets",
+                kind, stream.index, stream.timestamp_errors
+            ));
+        }
+        if let Some(header_duration) = stream.header_duration {
+            if stream.packets > 0
+                && (header_duration - stream.read_duration()).abs() > thresholds.duration_tolerance
+            {
+                report.issues.push(format!(
+                    "duration mismatch in {} stream {}: header {:.3} s, decoded {:.3} s",
+                    kind,
+                    stream.index,
+                    header_duration,
+                    stream.read_duration()
+                ));
+            }
+        }
+    }
+
+    // The longest stream should last as long as the container announces
+    if let Some(container_duration) = report.container_duration {
+        if report.decoded_duration > 0.0
+            && container_duration - report.decoded_duration > thresholds.duration_tolerance
+        {
+            report.issues.push(format!(
+                "truncated file: container {:.3} s, decoded {:.3} s",
+                container_duration, report.decoded_duration
+            ));
+        }
+    }
+
+    report
+}
+
+// Open a decoder for a stream
+fn open_stream(stream: &ffmpeg::format::stream::Stream) -> Result<StreamCheck> {
+    let parameters = stream.parameters();
+    let codec = ffmpeg::decoder::find(parameters.id())
+        .with_context(|| format!("No decoder for codec {:?}", parameters.id()))?;
+    let decoder = ffmpeg::codec::context::Context::from_parameters(parameters.clone())
+        .with_context(|| "Failed to create decoder context from stream parameters")?
+        .decoder()
+        .open_as(codec)
+        .with_context(|| "Failed to open decoder")?;
+
+    let time_base = f64::from(stream.time_base());
+    Ok(StreamCheck {
+        index: stream.index(),
+        medium: parameters.medium(),
+        decoder,
+        time_base,
+        header_duration: match stream.duration() {
+            duration if duration > 0 => Some(duration as f64 * time_base),
+            _ => None,
+        },
+        packets: 0,
+        frames: 0,
+        extent: None,
+        last_dts: None,
+        timestamp_errors: 0,
+        decode_errors: 0,
+        first_decode_error: None,
+    })
+}
@@ -0,0 +1,148 @@
+extern crate ffmpeg_next as ffmpeg;
+
+use anyhow::{Context, Result};
+use ffmpeg::format::Pixel;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::video::video_utils::{check_even_size, convert_video, Conversion};
+use crate::utils::{check_distinct_outputs, file_has_right_extension, perform_io_sanity_check};
+use crate::VideoConvertArgs;
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 4] = ["ts", "mp4", "mkv", "mov"];
+
+pub fn execute(args: VideoConvertArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    let pixel_format = match &args.pixel_format {
+        Some(name) => Some(
+            name.parse::<Pixel>()
+                .map_err(|_| anyhow::Error::msg(format!("Unknown pixel format: {}", name)))?,
+        ),
+        None => None,
+    };
+    let conversion = Conversion {
+        codec: args.codec,
+        width: args.width,
+        height: args.height,
+        fps: args.fps,
+        pixel_format,
+        crf: args.crf,
+    };
+
+    // Validate the requested encoding
+    if conversion.width == Some(0) || conversion.height == Some(0) {
+        return Err(anyhow::Error::msg("Width and height must be positive"));
+    }
+    check_even_size(conversion.codec, conversion.width, conversion.height)?;
+    if conversion.fps.is_some_and(|fps| fps <= 0.0) {
+        return Err(anyhow::Error::msg("Frame rate must be positive"));
+    }
+    if let Some(extension) = &args.extension {
+        if !EXTENSIONS.contains(&extension.as_str()) {
+            return Err(anyhow::Error::msg(format!(
+                "Unsupported output extension: {}",
+                extension
+            )));
+        }
+    }
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, output, conversion, args.extension.as_deref())
+        .with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(
+    input: &Path,
+    output: &Path,
+    conversion: Conversion,
+    extension: Option<&str>,
+) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+
+        // Keep the file name when given a directory
+        let file_output =
+            match output.extension() {
+                Some(_) => output.to_path_buf(),
+                None => output_path(
+                    &output.join(input.file_name().with_context(|| {
+                        format!("Failed to extract file name from: {:?}", input)
+                    })?),
+                    extension,
+                ),
+            };
+
+        process_file(input, &file_output, conversion)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files along with their nested output path
+        let files: Vec<(PathBuf, PathBuf)> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| -> Result<(PathBuf, PathBuf)> {
+                let file = e.path().to_path_buf();
+                let relative_path = file
+                    .strip_prefix(input)
+                    .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+                let file_output = output_path(&output.join(relative_path), extension);
+                Ok((file, file_output))
+            })
+            .collect::<Result<_>>()?;
+
+        // A new container can map clip.mkv and clip.mp4 to the same file
+        check_distinct_outputs(&files)?;
+
+        // Parallel loop over entries
+        files
+            .par_iter()
+            .try_for_each(|(file, file_output)| -> Result<()> {
+                // Ensure the output directory exists
+                if let Some(parent) = file_output.parent() {
+                    std::fs::create_dir_all(parent).with_context(|| {
+                        format!("Failed to create output directory: {:?}", parent)
+                    })?;
+                }
+
+                // Process the file
+                process_file(file, file_output, conversion)
+                    .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+                Ok(())
+            })?;
+    }
+    Ok(())
+}
+
+// Output path with the requested container, if any
+fn output_path(path: &Path, extension: Option<&str>) -> PathBuf {
+    match extension {
+        Some(extension) => path.with_extension(extension),
+        None => path.to_path_buf(),
+    }
+}
+
+// Process a single file
+fn process_file(input: &Path, output: &Path, conversion: Conversion) -> Result<()> {
+    // Videos are read while the new one is written
+    if input == output {
+        return Err(anyhow::Error::msg("Can't overwrite files"));
+    }
+
+    convert_video(input, output, conversion)
+}
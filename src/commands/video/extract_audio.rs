@@ -0,0 +1,150 @@
+use anyhow::{Context, Result};
+use hound::{SampleFormat, WavSpec};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::audio::audio_utils::{conform_samples, decode_audio, write_samples};
+use crate::utils::{file_has_right_extension, perform_io_sanity_check};
+use crate::{VideoExtractAudioArgs, WavSampleFormat};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 4] = ["ts", "mp4", "mkv", "mov"];
+
+/// Target WAV layout shared by all the files processed, `None` keeps the original
+#[derive(Debug, Clone, Copy)]
+struct Options {
+    sr: Option<u32>,
+    channels: Option<usize>,
+    bits: u16,
+    format: WavSampleFormat,
+}
+
+pub fn execute(args: VideoExtractAudioArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    let options = Options {
+        sr: args.sr,
+        channels: args.channels,
+        bits: args.bits,
+        format: args.format,
+    };
+
+    // Validate the requested layout
+    match (options.format, options.bits) {
+        (WavSampleFormat::Int, bits) if ![8, 16, 24, 32].contains(&bits) => {
+            return Err(anyhow::Error::msg(format!(
+                "Unsupported bits per sample: {}",
+                bits
+            )))
+        }
+        (WavSampleFormat::Float, bits) if bits != 32 => {
+            return Err(anyhow::Error::msg("Float samples must be 32 bits"))
+        }
+        _ => {}
+    }
+    if options.sr == Some(0) || options.channels == Some(0) {
+        return Err(anyhow::Error::msg(
+            "Sample rate and channels must be positive",
+        ));
+    }
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, output, options).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(input: &Path, output: &Path, options: Options) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+
+        // Write next to the other outputs when given a directory
+        let file_output = match output.extension() {
+            Some(_) => output.to_path_buf(),
+            None => {
+                let file_name = input
+                    .file_name()
+                    .with_context(|| format!("Failed to extract file name from: {:?}", input))?;
+                output.join(format!("{}.wav", file_name.to_string_lossy()))
+            }
+        };
+
+        process_file(input, &file_output, options)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output path named after the whole file name, so that clip.mp4 and
+            // clip.mkv don't collide
+            let mut file_output = output.join(relative_path).into_os_string();
+            file_output.push(".wav");
+            let file_output = PathBuf::from(file_output);
+
+            // Ensure the output directory exists
+            if let Some(parent) = file_output.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+            }
+
+            // Process the file
+            process_file(file, &file_output, options)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// Decode the audio stream of a video and write it as WAV
+fn process_file(input: &Path, output: &Path, options: Options) -> Result<()> {
+    // Decode the best audio stream
+    let decoded = decode_audio(input)?;
+    if let Some(error) = decoded.error {
+        return Err(anyhow::Error::msg(error));
+    }
+
+    // Downmix and resample if requested
+    let channels = options.channels.unwrap_or(decoded.samples.len());
+    let sample_rate = options.sr.unwrap_or(decoded.sample_rate);
+    let samples = conform_samples(decoded.samples, decoded.sample_rate, channels, sample_rate)?;
+
+    let spec = WavSpec {
+        channels: channels as u16,
+        sample_rate,
+        bits_per_sample: options.bits,
+        sample_format: match options.format {
+            WavSampleFormat::Int => SampleFormat::Int,
+            WavSampleFormat::Float => SampleFormat::Float,
+        },
+    };
+
+    // Write to file
+    write_samples(output, spec, &samples)?;
+
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use anyhow::{Context, Result};
+use image::imageops::{resize, FilterType};
+use image::RgbImage;
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::video::video_utils::{decode_frames, save_frame};
+use crate::utils::{file_has_right_extension, perform_io_sanity_check};
+use crate::{FrameFormat, VideoFramesArgs};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 4] = ["ts", "mp4", "mkv", "mov"];
+
+/// Which frames to extract
+#[derive(Debug, Clone)]
+enum Selection {
+    Every(usize),
+    Fps(f64),
+    Keyframes,
+    Timestamps(Vec<f64>),
+}
+
+/// How to write the extracted frames
+#[derive(Debug, Clone, Copy)]
+struct FrameOutput {
+    format: FrameFormat,
+    quality: u8,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+pub fn execute(args: VideoFramesArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    let selection = match (
+        args.every,
+        args.fps,
+        args.keyframes,
+        args.timestamps.is_empty(),
+    ) {
+        (Some(0), _, _, _) => return Err(anyhow::Error::msg("--every must be positive")),
+        (Some(every), _, _, _) => Selection::Every(every),
+        (_, Some(fps), _, _) if fps <= 0.0 => {
+            return Err(anyhow::Error::msg("--fps must be positive"))
+        }
+        (_, Some(fps), _, _) => Selection::Fps(fps),
+        (_, _, true, _) => Selection::Keyframes,
+        (_, _, _, false) => {
+            let mut timestamps = args.timestamps.clone();
+            timestamps.sort_by(f64::total_cmp);
+            Selection::Timestamps(timestamps)
+        }
+        _ => Selection::Every(1),
+    };
+
+    let frame_output = FrameOutput {
+        format: args.format,
+        quality: args.quality,
+        width: args.width,
+        height: args.height,
+    };
+    if frame_output.quality == 0 || frame_output.quality > 100 {
+        return Err(anyhow::Error::msg("JPEG quality must be between 1 and 100"));
+    }
+
+    // Sanity checks on I/O, frames always go to a directory
+    perform_io_sanity_check(input, output, false, false).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, output, &selection, frame_output).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(
+    input: &Path,
+    output: &Path,
+    selection: &Selection,
+    frame_output: FrameOutput,
+) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+        process_file(input, output, selection, frame_output)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output directory
+            let file_output = match relative_path.parent() {
+                Some(parent) => output.join(parent),
+                None => output.to_path_buf(),
+            };
+
+            // Process the file
+            process_file(file, &file_output, selection, frame_output)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// Extract the selected frames of a file into a directory
+fn process_file(
+    input: &Path,
+    output: &Path,
+    selection: &Selection,
+    frame_output: FrameOutput,
+) -> Result<()> {
+    // Ensure the output directory exists
+    fs::create_dir_all(output)
+        .with_context(|| format!("Failed to create output directory: {:?}", output))?;
+
+    // Named after the whole file name, so that clip.mp4 and clip.mkv don't collide
+    let name = input
+        .file_name()
+        .with_context(|| format!("Failed to extract file name from: {:?}", input))?
+        .to_string_lossy()
+        .to_string();
+    let extension = match frame_output.format {
+        FrameFormat::Png => "png",
+        FrameFormat::Jpeg => "jpg",
+    };
+
+    // Time of the next frame for a target fps, or position in the requested timestamps
+    let mut next_time = 0.0;
+    let mut next_timestamp = 0;
+
+    decode_frames(input, |mut frame| {
+        let selected = match selection {
+            Selection::Every(every) => frame.index % every == 0,
+            Selection::Fps(fps) => {
+                // Tolerate timestamps rounded by the container
+                let selected = frame.timestamp >= next_time - 1e-6;
+                while next_time <= frame.timestamp + 1e-6 {
+                    next_time += 1.0 / fps;
+                }
+                selected
+            }
+            Selection::Keyframes => frame.key,
+            Selection::Timestamps(timestamps) => {
+                // First frame at or after each requested timestamp
+                let mut selected = false;
+                while next_timestamp < timestamps.len()
+                    && timestamps[next_timestamp] <= frame.timestamp + 1e-6
+                {
+                    selected = true;
+                    next_timestamp += 1;
+                }
+                selected
+            }
+        };
+
+        if selected {
+            let image = resize_frame(frame.to_rgb()?, frame_output);
+            let path = output.join(format!("{}@{:06}.{}", name, frame.index, extension));
+            save_frame(&image, &path, frame_output.format, frame_output.quality)?;
+        }
+
+        // Stop decoding once all the requested timestamps are found
+        Ok(match selection {
+            Selection::Timestamps(timestamps) => next_timestamp < timestamps.len(),
+            _ => true,
+        })
+    })?;
+
+    // Requested timestamps past the last frame
+    if let Selection::Timestamps(timestamps) = selection {
+        if next_timestamp < timestamps.len() {
+            return Err(anyhow::Error::msg(format!(
+                "Timestamps past the end of the video: {:?}",
+                &timestamps[next_timestamp..]
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// Resize with Lanczos like `image resize`, keeping the aspect ratio if a side is missing
+fn resize_frame(image: RgbImage, frame_output: FrameOutput) -> RgbImage {
+    let (width, height) = image.dimensions();
+    let (new_width, new_height) = match (frame_output.width, frame_output.height) {
+        (None, None) => return image,
+        (Some(new_width), Some(new_height)) => (new_width, new_height),
+        (Some(new_width), None) => {
+            let new_height = (height as f64 * new_width as f64 / width as f64).round() as u32;
+            (new_width, new_height.max(1))
+        }
+        (None, Some(new_height)) => {
+            let new_width = (width as f64 * new_height as f64 / height as f64).round() as u32;
+            (new_width.max(1), new_height)
+        }
+    };
+    resize(&image, new_width, new_height, FilterType::Lanczos3)
+}
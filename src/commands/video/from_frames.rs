@@ -0,0 +1,205 @@
+use anyhow::{Context, Result};
+use image::imageops::{overlay, resize, FilterType};
+use image::io::Reader as ImageReader;
+use image::{Rgb, RgbImage};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::video::video_utils::{check_even_size, encode_images, even};
+use crate::utils::file_has_right_extension;
+use crate::VideoFromFramesArgs;
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "bmp", "gif", "tiff"];
+
+pub fn execute(args: VideoFromFramesArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    if args.fps <= 0.0 {
+        return Err(anyhow::Error::msg("Frame rate must be positive"));
+    }
+    if args.width == Some(0) || args.height == Some(0) {
+        return Err(anyhow::Error::msg("Width and height must be positive"));
+    }
+    check_even_size(args.codec, args.width, args.height)?;
+
+    // Sanity checks on I/O, frames come from a directory and go to a single video
+    if !input.is_dir() {
+        return Err(anyhow::Error::msg("Input directory does not exist"));
+    }
+    if output.extension().is_none() {
+        return Err(anyhow::Error::msg("Output must be a video file"));
+    }
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+    }
+
+    // Find the frames in order
+    let frames = match &args.pattern {
+        Some(pattern) => match_pattern(input, pattern)?,
+        None => natural_sorted(input)?,
+    };
+    if frames.is_empty() {
+        return Err(anyhow::Error::msg("No admissible image files detected"));
+    }
+
+    // Size of the video, from the first frame unless requested
+    let first = read_image(&frames[0])?;
+    let (first_width, first_height) = first.dimensions();
+    let size = match (args.width, args.height) {
+        (Some(width), Some(height)) => (width, height),
+        (Some(width), None) => (
+            width,
+            even(first_height as f64 * width as f64 / first_width as f64),
+        ),
+        (None, Some(height)) => (
+            even(first_width as f64 * height as f64 / first_height as f64),
+            height,
+        ),
+        (None, None) => (even(first_width as f64), even(first_height as f64)),
+    };
+
+    // Encode the frames, fitted to the size of the video
+    let images = frames
+        .iter()
+        .map(|frame| read_image(frame).map(|image| fit(image, size)));
+    let n_frames = encode_images(output, images, size, args.fps, args.codec, args.crf)
+        .with_context(|| format!("Failed to encode {:?}", output))?;
+
+    println!(
+        "Encoded {} frames of size {}x{} into {:?}",
+        n_frames, size.0, size.1, output
+    );
+
+    Ok(())
+}
+
+// Images matching a pattern with a single `%d` or `%0Nd` placeholder, sorted by their number
+fn match_pattern(input: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    // Split the pattern around its placeholder
+    let start = pattern
+        .find('%')
+        .with_context(|| format!("Pattern {:?} has no %d placeholder", pattern))?;
+    let end = pattern[start..]
+        .find('d')
+        .map(|offset| start + offset)
+        .with_context(|| format!("Pattern {:?} has no %d placeholder", pattern))?;
+    let width = match &pattern[start + 1..end] {
+        "" => 1,
+        width => width
+            .parse::<usize>()
+            .with_context(|| format!("Invalid placeholder in pattern {:?}", pattern))?,
+    };
+    let (prefix, suffix) = (&pattern[..start], &pattern[end + 1..]);
+
+    let mut frames: Vec<(u64, PathBuf)> = Vec::new();
+    for entry in fs::read_dir(input).with_context(|| format!("Can't read {:?}", input))? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let number = name
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(suffix))
+            .filter(|digits| digits.len() >= width && digits.bytes().all(|b| b.is_ascii_digit()));
+        if let Some(number) = number.and_then(|digits| digits.parse::<u64>().ok()) {
+            frames.push((number, path));
+        }
+    }
+    frames.sort();
+
+    Ok(frames.into_iter().map(|(_, path)| path).collect())
+}
+
+// Images of a directory in natural order, so that `frame2` comes before `frame10`
+fn natural_sorted(input: &Path) -> Result<Vec<PathBuf>> {
+    let mut frames: Vec<PathBuf> = fs::read_dir(input)
+        .with_context(|| format!("Can't read {:?}", input))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.is_file() && file_has_right_extension(path, &EXTENSIONS).is_ok())
+        .collect();
+    frames.sort_by(|a, b| {
+        natural_cmp(
+            &a.file_name().unwrap_or_default().to_string_lossy(),
+            &b.file_name().unwrap_or_default().to_string_lossy(),
+        )
+    });
+
+    Ok(frames)
+}
+
+// Compare strings with runs of digits compared by value
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let a_end = a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len());
+                let b_end = b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len());
+                let (a_digits, b_digits) = (
+                    a[..a_end].trim_start_matches('0'),
+                    b[..b_end].trim_start_matches('0'),
+                );
+                let ordering = a_digits
+                    .len()
+                    .cmp(&b_digits.len())
+                    .then_with(|| a_digits.cmp(b_digits))
+                    .then_with(|| a_end.cmp(&b_end));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                (a, b) = (&a[a_end..], &b[b_end..]);
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+            }
+        }
+    }
+}
+
+// Read an image as RGB
+fn read_image(path: &Path) -> Result<RgbImage> {
+    let image = ImageReader::open(path)
+        .with_context(|| format!("Can't open image {:?}", path))?
+        .decode()
+        .with_context(|| format!("Can't decode image {:?}", path))?;
+    Ok(image.to_rgb8())
+}
+
+// Resize with Lanczos to fit in the size, keeping the aspect ratio, and pad with black
+fn fit(image: RgbImage, (width, height): (u32, u32)) -> RgbImage {
+    let (image_width, image_height) = image.dimensions();
+    if (image_width, image_height) == (width, height) {
+        return image;
+    }
+
+    let scale = f64::min(
+        width as f64 / image_width as f64,
+        height as f64 / image_height as f64,
+    );
+    let new_width = ((image_width as f64 * scale).round() as u32).clamp(1, width);
+    let new_height = ((image_height as f64 * scale).round() as u32).clamp(1, height);
+    let resized = resize(&image, new_width, new_height, FilterType::Lanczos3);
+
+    let mut canvas = RgbImage::from_pixel(width, height, Rgb([0, 0, 0]));
+    overlay(
+        &mut canvas,
+        &resized,
+        ((width - new_width) / 2) as i64,
+        ((height - new_height) / 2) as i64,
+    );
+    canvas
+}
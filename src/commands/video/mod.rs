@@ -1 +1,11 @@
+pub mod check;
+pub mod convert;
+pub mod extract_audio;
+pub mod frames;
+pub mod from_frames;
+pub mod scenes;
+pub mod split;
 pub mod summary;
+pub mod thumbnails;
+pub mod trim;
+pub mod video_utils;
@@ -0,0 +1,299 @@
+use anyhow::{Context, Result};
+use image::imageops::{resize, FilterType};
+use image::RgbImage;
+use polars::prelude::*;
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::video::video_utils::{decode_frames, save_frame};
+use crate::utils::{file_has_right_extension, write_table};
+use crate::{FrameFormat, SceneMethod, VideoScenesArgs};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 4] = ["ts", "mp4", "mkv", "mov"];
+
+// Width frames are downscaled to before comparing them
+const ANALYSIS_WIDTH: u32 = 160;
+
+// Bins of the histogram of each channel
+const HISTOGRAM_BINS: usize = 16;
+
+/// Parameters of the detector, with durations in seconds
+#[derive(Debug, Clone, Copy)]
+struct Detector {
+    method: SceneMethod,
+    threshold: f64,
+    min_shot: f64,
+}
+
+/// Where to write one keyframe per shot
+#[derive(Debug, Clone)]
+struct Keyframes {
+    output: PathBuf,
+    format: FrameFormat,
+}
+
+/// Shots found in a single file
+struct ScenesReport {
+    path: String,
+    shots: Vec<(f64, f64)>,
+}
+
+pub fn execute(args: VideoScenesArgs) -> Result<()> {
+    // Parse the arguments
+    let target = Path::new(&args.target);
+
+    let detector = Detector {
+        method: args.method,
+        threshold: args.threshold.unwrap_or(match args.method {
+            SceneMethod::Histogram => 0.3,
+            SceneMethod::Pixel => 0.15,
+        }),
+        min_shot: args.min_shot,
+    };
+    if !(0.0..=1.0).contains(&detector.threshold) {
+        return Err(anyhow::Error::msg("Threshold must be between 0 and 1"));
+    }
+
+    let keyframes = args.keyframes.as_ref().map(|output| Keyframes {
+        output: PathBuf::from(output),
+        format: args.format,
+    });
+
+    // Error if it does not exist at all
+    if !target.exists() {
+        return Err(anyhow::Error::msg(
+            "Target file or directory does not exist",
+        ));
+    }
+
+    // Find all admissible files
+    let files: Vec<PathBuf> = match target.is_file() {
+        true => {
+            if file_has_right_extension(target, &EXTENSIONS).is_ok() {
+                vec![target.to_path_buf()]
+            } else {
+                vec![]
+            }
+        }
+        false => WalkDir::new(target)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect(),
+    };
+
+    // Raise error if no files are admissible
+    if files.is_empty() {
+        return Err(anyhow::Error::msg("No admissible video files detected"));
+    }
+
+    // Detect shots in parallel, skipping unreadable files
+    let mut reports: Vec<ScenesReport> = files
+        .par_iter()
+        .filter_map(|file| {
+            let report = detect_file(file, detector).and_then(|report| {
+                if let Some(keyframes) = &keyframes {
+                    write_keyframes(file, target, &report, keyframes)?;
+                }
+                Ok(report)
+            });
+            match report {
+                Ok(report) => Some(report),
+                Err(err) => {
+                    eprintln!("Skipping file {:?} due to error: {:#}", file, err);
+                    None
+                }
+            }
+        })
+        .collect();
+    reports.sort_by(|a, b| a.path.cmp(&b.path));
+
+    if reports.is_empty() {
+        return Err(anyhow::Error::msg("No video files could be read"));
+    }
+
+    // Print shot statistics
+    let mut shots_per_file: Vec<usize> = reports.iter().map(|r| r.shots.len()).collect();
+    shots_per_file.sort();
+    let mut durations: Vec<f64> = reports
+        .iter()
+        .flat_map(|r| r.shots.iter().map(|(start, end)| end - start))
+        .collect();
+    durations.sort_by(f64::total_cmp);
+
+    println!("Files: {}", reports.len());
+    println!("Shots: {}", durations.len());
+    println!(
+        "Shots per file: min {}, median {}, max {}",
+        shots_per_file[0],
+        shots_per_file[shots_per_file.len() / 2],
+        shots_per_file[shots_per_file.len() - 1]
+    );
+    if !durations.is_empty() {
+        println!(
+            "Shot duration: min {:.2} s, median {:.2} s, max {:.2} s",
+            durations[0],
+            durations[durations.len() / 2],
+            durations[durations.len() - 1]
+        );
+    }
+
+    // Write the shots
+    if let Some(output) = &args.output {
+        let mut df = reports_to_table(&reports)?;
+        write_table(&mut df, Path::new(output))
+            .with_context(|| format!("Failed to write table to {:?}", output))?;
+    }
+
+    Ok(())
+}
+
+// Build a table with one row per shot
+fn reports_to_table(reports: &[ScenesReport]) -> Result<DataFrame> {
+    let rows: Vec<(&String, u32, f64, f64)> = reports
+        .iter()
+        .flat_map(|r| {
+            r.shots
+                .iter()
+                .enumerate()
+                .map(move |(shot, &(start, end))| (&r.path, shot as u32, start, end))
+        })
+        .collect();
+
+    df!(
+        "file" => rows.iter().map(|row| row.0.clone()).collect::<Vec<_>>(),
+        "shot" => rows.iter().map(|row| row.1).collect::<Vec<_>>(),
+        "start" => rows.iter().map(|row| row.2).collect::<Vec<_>>(),
+        "end" => rows.iter().map(|row| row.3).collect::<Vec<_>>(),
+    )
+    .with_context(|| "Failed to build shot table")
+}
+
+// Find the shots of a file from the differences between consecutive frames
+fn detect_file(file: &Path, detector: Detector) -> Result<ScenesReport> {
+    let mut starts: Vec<f64> = Vec::new();
+    let mut previous: Option<RgbImage> = None;
+    let mut last_times = (0.0, 0.0);
+
+    decode_frames(file, |mut frame| {
+        let rgb = frame.to_rgb()?;
+        let (width, height) = rgb.dimensions();
+        let analysis_height =
+            ((height as f64 * ANALYSIS_WIDTH as f64 / width as f64).round() as u32).max(1);
+        let small = resize(&rgb, ANALYSIS_WIDTH, analysis_height, FilterType::Triangle);
+
+        match &previous {
+            None => starts.push(frame.timestamp),
+            Some(previous) => {
+                let difference = match detector.method {
+                    SceneMethod::Histogram => histogram_difference(previous, &small),
+                    SceneMethod::Pixel => pixel_difference(previous, &small),
+                };
+                let since_cut = frame.timestamp - starts.last().copied().unwrap_or(0.0);
+                if difference > detector.threshold && since_cut >= detector.min_shot {
+                    starts.push(frame.timestamp);
+                }
+            }
+        }
+
+        previous = Some(small);
+        last_times = (last_times.1, frame.timestamp);
+        Ok(true)
+    })?;
+
+    // The last shot lasts until the end of its last frame
+    let end = last_times.1 + (last_times.1 - last_times.0).max(0.0);
+    let shots = starts
+        .iter()
+        .zip(starts.iter().skip(1).chain([&end]))
+        .map(|(&start, &end)| (start, end))
+        .collect();
+
+    Ok(ScenesReport {
+        path: file.to_string_lossy().to_string(),
+        shots,
+    })
+}
+
+// Total variation distance between the per-channel histograms of two frames, in [0, 1]
+fn histogram_difference(a: &RgbImage, b: &RgbImage) -> f64 {
+    let histogram = |image: &RgbImage| {
+        let mut bins = [[0.0; HISTOGRAM_BINS]; 3];
+        for pixel in image.pixels() {
+            for (channel, &value) in pixel.0.iter().enumerate() {
+                bins[channel][value as usize * HISTOGRAM_BINS / 256] += 1.0;
+            }
+        }
+        let n_pixels = (image.width() * image.height()).max(1) as f64;
+        bins.map(|channel| channel.map(|count| count / n_pixels))
+    };
+    let (a, b) = (histogram(a), histogram(b));
+
+    a.iter()
+        .zip(&b)
+        .map(|(a, b)| a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum::<f64>() / 2.0)
+        .sum::<f64>()
+        / 3.0
+}
+
+// Mean absolute difference between the pixels of two frames, in [0, 1]
+fn pixel_difference(a: &RgbImage, b: &RgbImage) -> f64 {
+    let total: u64 = a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .map(|(&x, &y)| x.abs_diff(y) as u64)
+        .sum();
+    total as f64 / (a.as_raw().len().max(1) as f64 * 255.0)
+}
+
+// Write the middle frame of each shot, named after the shot index
+fn write_keyframes(
+    file: &Path,
+    target: &Path,
+    report: &ScenesReport,
+    keyframes: &Keyframes,
+) -> Result<()> {
+    // Nested output directory
+    let output = match file.strip_prefix(target).ok().and_then(|p| p.parent()) {
+        Some(parent) => keyframes.output.join(parent),
+        None => keyframes.output.clone(),
+    };
+    fs::create_dir_all(&output)
+        .with_context(|| format!("Failed to create output directory: {:?}", output))?;
+
+    // Named after the whole file name, so that clip.mp4 and clip.mkv don't collide
+    let name = file
+        .file_name()
+        .with_context(|| format!("Failed to extract file name from: {:?}", file))?
+        .to_string_lossy()
+        .to_string();
+    let extension = match keyframes.format {
+        FrameFormat::Png => "png",
+        FrameFormat::Jpeg => "jpg",
+    };
+
+    // First frame at or after the middle of each shot
+    let middles: Vec<f64> = report
+        .shots
+        .iter()
+        .map(|(start, end)| (start + end) / 2.0)
+        .collect();
+    let mut next_shot = 0;
+    decode_frames(file, |mut frame| {
+        if next_shot < middles.len() && frame.timestamp >= middles[next_shot] - 1e-6 {
+            let path = output.join(format!("{}@{:04}.{}", name, next_shot, extension));
+            save_frame(&frame.to_rgb()?, &path, keyframes.format, 90)?;
+            while next_shot < middles.len() && frame.timestamp >= middles[next_shot] - 1e-6 {
+                next_shot += 1;
+            }
+        }
+        Ok(next_shot < middles.len())
+    })?;
+
+    Ok(())
+}
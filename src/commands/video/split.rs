@@ -0,0 +1,145 @@
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::video::video_utils::{cut_video, video_duration};
+use crate::utils::{file_has_right_extension, perform_io_sanity_check};
+use crate::{VideoCutMode, VideoSplitArgs};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 4] = ["ts", "mp4", "mkv", "mov"];
+
+// Remainders shorter than this are not written as a chunk of their own
+const MIN_CHUNK_DURATION: f64 = 1e-3;
+
+pub fn execute(args: VideoSplitArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    let chunk_duration: f64 = args.chunk_duration;
+    if chunk_duration <= 0.0 {
+        return Err(anyhow::Error::msg("Chunk duration must be positive"));
+    }
+
+    let delete_original: bool = args.delete_original;
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, false).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, chunk_duration, output, args.mode, delete_original)
+        .with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(
+    input: &Path,
+    chunk_duration: f64,
+    output: &Path,
+    mode: VideoCutMode,
+    delete_original: bool,
+) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+        process_file(input, chunk_duration, output, mode)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+        if delete_original {
+            fs::remove_file(input)
+                .with_context(|| format!("Failed to delete file: {:?}", input))?;
+        }
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            let joined_path = output.join(relative_path);
+            let output_directory = joined_path.parent().with_context(|| {
+                format!("Failed to get parent directory of: {:?}", relative_path)
+            })?;
+
+            // Create output directory
+            fs::create_dir_all(output_directory).with_context(|| {
+                format!("Failed to create output directory: {:?}", output_directory)
+            })?;
+
+            // Process the file
+            process_file(file, chunk_duration, output_directory, mode)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            if delete_original {
+                fs::remove_file(file)
+                    .with_context(|| format!("Failed to delete file: {:?}", file))?;
+            }
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// Process a single file
+fn process_file(
+    input: &Path,
+    chunk_duration: f64,
+    output: &Path,
+    mode: VideoCutMode,
+) -> Result<()> {
+    // Calculate the number of chunks the file will be split into, the last one may be shorter
+    let duration = video_duration(input)?;
+    let num_chunks = ((duration - MIN_CHUNK_DURATION) / chunk_duration)
+        .ceil()
+        .max(1.0) as usize;
+
+    // Calculate the number of digits needed when padding the name with 0's
+    let padding_width = format!("{}", num_chunks - 1).len();
+
+    // Calculate the stem and keep the container
+    let stem = input
+        .file_stem()
+        .with_context(|| format!("Failed to extract stem from: {:?}", input))?
+        .to_str()
+        .with_context(|| format!("Failed to convert stem to string for: {:?}", input))?;
+    let extension = input
+        .extension()
+        .with_context(|| format!("Failed to extract extension from: {:?}", input))?
+        .to_string_lossy();
+
+    for i in 0..num_chunks {
+        // Define the output path for the chunk
+        let output_path = output.join(format!(
+            "{}@{:0width$}.{}",
+            stem,
+            i,
+            extension,
+            width = padding_width
+        ));
+
+        // Cut the chunk out of the original file
+        let start = i as f64 * chunk_duration;
+        let end = (start + chunk_duration).min(duration);
+        cut_video(input, &output_path, start, end, mode)
+            .with_context(|| format!("Failed to write chunk {:?}", output_path))?;
+    }
+
+    Ok(())
+}
@@ -1,18 +1,47 @@
 extern crate ffmpeg_next as ffmpeg;
 
 use anyhow::{Context, Result};
+use ffmpeg::media::Type;
+use polars::prelude::*;
 use rayon::prelude::*;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-use crate::utils::file_has_right_extension;
+use crate::utils::{file_has_right_extension, write_table};
 
 use crate::VideoSummaryArgs;
 
 // Admissible extensions for this command
 const EXTENSIONS: [&str; 4] = ["ts", "mp4", "mkv", "mov"];
 
+/// Container, stream and codec properties of a single video file
+struct VideoInfo {
+    path: String,
+    container: String,
+    /// Duration in seconds
+    duration: f64,
+    /// Overall bitrate in kbps
+    bitrate: Option<u64>,
+    video_codec: String,
+    pixel_format: String,
+    width: u32,
+    height: u32,
+    fps_numerator: u32,
+    fps_denominator: u32,
+    /// Bitrate of the video stream in kbps
+    video_bitrate: Option<u64>,
+    /// Frame count stored in the container, if any
+    frames: Option<u64>,
+    /// Clockwise rotation in degrees to apply for display
+    rotation: i32,
+    audio_streams: usize,
+    audio_codec: Option<String>,
+    sample_rate: Option<u32>,
+    channels: Option<u16>,
+    subtitle_streams: usize,
+}
+
 pub fn execute(args: VideoSummaryArgs) -> Result<()> {
     // Parse the arguments
     let target = Path::new(&args.target);
@@ -43,74 +72,232 @@ pub fn execute(args: VideoSummaryArgs) -> Result<()> {
 
     // Raise error if no files are admissible
     if files.is_empty() {
-        return Err(anyhow::Error::msg("No admissible image files detected"));
+        return Err(anyhow::Error::msg("No admissible video files detected"));
     }
 
-    // Process files
-    let info: Vec<(f64, u32, u32, u32, u32)> = files
-        .into_par_iter()
-        .filter_map(|file| process_video(&file).ok())
+    // Process files, skipping unreadable ones
+    let mut info: Vec<VideoInfo> = files
+        .par_iter()
+        .filter_map(|file| match process_video(file) {
+            Ok(info) => Some(info),
+            Err(err) => {
+                eprintln!("Skipping file {:?} due to error: {:#}", file, err);
+                None
+            }
+        })
         .collect();
+    info.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // Per-file table printed as CSV, alone so that it can be piped
+    if args.per_file && args.output.is_none() {
+        let mut df = info_to_table(&info)?;
+        CsvWriter::new(std::io::stdout())
+            .finish(&mut df)
+            .with_context(|| "Failed to print table")?;
+        return Ok(());
+    }
 
     // Calculate total number of files
     let n_files = info.len();
 
     // Sum all durations
-    let total_duration: f64 = info.iter().map(|(duration, _, _, _, _)| duration).sum();
+    let total_duration: f64 = info.iter().map(|i| i.duration).sum();
 
     // Get unique values
-    let unique_durations: HashSet<_> = info
-        .iter()
-        .map(|(duration, _, _, _, _)| *duration as u64)
-        .collect();
+    let unique_durations: HashSet<_> = info.iter().map(|i| i.duration as u64).collect();
     let unique_fps: HashSet<_> = info
         .iter()
-        .map(|(_, fps_num, fps_den, _, _)| (fps_num, fps_den))
+        .map(|i| (i.fps_numerator, i.fps_denominator))
         .collect();
-    let unique_shapes: HashSet<_> = info.iter().map(|(_, _, _, h, w)| (h, w)).collect();
+    let unique_shapes: HashSet<_> = info.iter().map(|i| (i.height, i.width)).collect();
 
     // Print results
     println!("Total files: {}", n_files);
     println!("Total duration: {}", total_duration);
     println!("Unique durations: {:?}", unique_durations);
     println!("Unique (height, width) pairs: {:?}", unique_shapes);
-    println!("Unique  FPS: {:?}", unique_fps);
+    println!("Unique FPS: {:?}", unique_fps);
+
+    // Number of files per property, to spot the odd file in a dataset
+    println!(
+        "Containers: {:?}",
+        count(info.iter().map(|i| i.container.as_str()))
+    );
+    println!(
+        "Video codecs: {:?}",
+        count(info.iter().map(|i| i.video_codec.as_str()))
+    );
+    println!(
+        "Pixel formats: {:?}",
+        count(info.iter().map(|i| i.pixel_format.as_str()))
+    );
+    println!("Rotations: {:?}", count(info.iter().map(|i| i.rotation)));
+    println!(
+        "Audio codecs: {:?}",
+        count(info.iter().filter_map(|i| i.audio_codec.as_deref()))
+    );
+    println!(
+        "Audio sample rates: {:?}",
+        count(info.iter().filter_map(|i| i.sample_rate))
+    );
+    println!(
+        "Audio channels: {:?}",
+        count(info.iter().filter_map(|i| i.channels))
+    );
+    println!(
+        "Files without audio: {}",
+        info.iter().filter(|i| i.audio_streams == 0).count()
+    );
+    println!(
+        "Files with subtitles: {}",
+        info.iter().filter(|i| i.subtitle_streams > 0).count()
+    );
+
+    // Per-file table written to a file
+    if let Some(output) = &args.output {
+        let mut df = info_to_table(&info)?;
+        write_table(&mut df, Path::new(output))
+            .with_context(|| format!("Failed to write table to {:?}", output))?;
+    }
 
     Ok(())
 }
 
-// Function for getting relevant info of an image file by just probing it
-fn process_video(path: &Path) -> Result<(f64, u32, u32, u32, u32)> {
+// Number of occurrences of each value
+fn count<T: Ord>(values: impl Iterator<Item = T>) -> BTreeMap<T, usize> {
+    values.fold(BTreeMap::new(), |mut counts, value| {
+        *counts.entry(value).or_insert(0) += 1;
+        counts
+    })
+}
+
+// Build a table with one row per file
+fn info_to_table(info: &[VideoInfo]) -> Result<DataFrame> {
+    df!(
+        "path" => info.iter().map(|i| i.path.clone()).collect::<Vec<_>>(),
+        "container" => info.iter().map(|i| i.container.clone()).collect::<Vec<_>>(),
+        "duration" => info.iter().map(|i| i.duration).collect::<Vec<_>>(),
+        "bitrate" => info.iter().map(|i| i.bitrate).collect::<Vec<_>>(),
+        "video_codec" => info.iter().map(|i| i.video_codec.clone()).collect::<Vec<_>>(),
+        "pixel_format" => info.iter().map(|i| i.pixel_format.clone()).collect::<Vec<_>>(),
+        "width" => info.iter().map(|i| i.width).collect::<Vec<_>>(),
+        "height" => info.iter().map(|i| i.height).collect::<Vec<_>>(),
+        "fps" => info.iter().map(|i| format!("{}/{}", i.fps_numerator, i.fps_denominator)).collect::<Vec<_>>(),
+        "video_bitrate" => info.iter().map(|i| i.video_bitrate).collect::<Vec<_>>(),
+        "frames" => info.iter().map(|i| i.frames).collect::<Vec<_>>(),
+        "rotation" => info.iter().map(|i| i.rotation).collect::<Vec<_>>(),
+        "audio_streams" => info.iter().map(|i| i.audio_streams as u32).collect::<Vec<_>>(),
+        "audio_codec" => info.iter().map(|i| i.audio_codec.clone()).collect::<Vec<_>>(),
+        "sample_rate" => info.iter().map(|i| i.sample_rate).collect::<Vec<_>>(),
+        "channels" => info.iter().map(|i| i.channels.map(u32::from)).collect::<Vec<_>>(),
+        "subtitle_streams" => info.iter().map(|i| i.subtitle_streams as u32).collect::<Vec<_>>(),
+    )
+    .with_context(|| "Failed to build summary table")
+}
+
+// Function for getting relevant info of a video file by just probing it
+fn process_video(path: &Path) -> Result<VideoInfo> {
     // Read context
     let context = ffmpeg::format::input(&path).with_context(|| "Couldn't read video")?;
 
-    // Only select the video stream, throw away audio, subtitles etc
-    if let Some(video_stream) = context
+    // Main video and audio streams, as ffmpeg would pick them
+    let video_stream = context
         .streams()
-        .find(|s| s.parameters().medium() == ffmpeg::media::Type::Video)
-    {
-        // Extract duration
-        let duration = video_stream.duration() as f64 * f64::from(video_stream.time_base());
-
-        // Extract FPS
-        let fps_numerator = video_stream.rate().numerator() as u32;
-        let fps_denominator = video_stream.rate().denominator() as u32;
-
-        // Create decoder
-        let context_decoder =
-            ffmpeg::codec::context::Context::from_parameters(video_stream.parameters())
-                .with_context(|| "Failed to create decoder context from video stream parameters")?;
-        let decoder = context_decoder
-            .decoder()
-            .video()
-            .with_context(|| "Failed to create video decoder from decoder context")?;
-
-        // Extract width and height from codec parameters directly
-        let width = decoder.width();
-        let height = decoder.height();
-
-        Ok((duration, fps_numerator, fps_denominator, height, width))
-    } else {
-        Err(anyhow::Error::msg("No video stream found in file"))
+        .best(Type::Video)
+        .with_context(|| "No video stream found in file")?;
+    let audio_stream = context.streams().best(Type::Audio);
+
+    // Extract duration, from the container when the stream does not store it
+    let duration = match video_stream.duration() {
+        duration if duration > 0 => duration as f64 * f64::from(video_stream.time_base()),
+        _ => context.duration().max(0) as f64 / ffmpeg::ffi::AV_TIME_BASE as f64,
+    };
+
+    // Extract FPS
+    let fps_numerator = video_stream.rate().numerator() as u32;
+    let fps_denominator = video_stream.rate().denominator() as u32;
+
+    // Create decoder
+    let context_decoder =
+        ffmpeg::codec::context::Context::from_parameters(video_stream.parameters())
+            .with_context(|| "Failed to create decoder context from video stream parameters")?;
+    let decoder = context_decoder
+        .decoder()
+        .video()
+        .with_context(|| "Failed to create video decoder from decoder context")?;
+
+    // Audio properties from the decoder of the main audio stream
+    let (audio_codec, sample_rate, channels) = match audio_stream {
+        Some(stream) => {
+            let decoder = ffmpeg::codec::context::Context::from_parameters(stream.parameters())
+                .with_context(|| "Failed to create decoder context from audio stream parameters")?
+                .decoder()
+                .audio()
+                .with_context(|| "Failed to create audio decoder from decoder context")?;
+            (
+                Some(stream.parameters().id().name().to_string()),
+                Some(decoder.rate()),
+                Some(decoder.channels()),
+            )
+        }
+        None => (None, None, None),
+    };
+
+    // Count streams by type
+    let count_streams = |medium: Type| {
+        context
+            .streams()
+            .filter(|s| s.parameters().medium() == medium)
+            .count()
+    };
+
+    Ok(VideoInfo {
+        path: path.to_string_lossy().to_string(),
+        container: context.format().name().to_string(),
+        duration,
+        bitrate: to_kbps(context.bit_rate()),
+        video_codec: video_stream.parameters().id().name().to_string(),
+        pixel_format: format!("{:?}", decoder.format()).to_lowercase(),
+        width: decoder.width(),
+        height: decoder.height(),
+        fps_numerator,
+        fps_denominator,
+        video_bitrate: to_kbps(decoder.bit_rate() as i64),
+        frames: match video_stream.frames() {
+            frames if frames > 0 => Some(frames as u64),
+            _ => None,
+        },
+        rotation: rotation(&video_stream),
+        audio_streams: count_streams(Type::Audio),
+        audio_codec,
+        sample_rate,
+        channels,
+        subtitle_streams: count_streams(Type::Subtitle),
+    })
+}
+
+// Bitrate in kbps, if known
+fn to_kbps(bitrate: i64) -> Option<u64> {
+    match bitrate {
+        bitrate if bitrate > 0 => Some(bitrate as u64 / 1000),
+        _ => None,
     }
 }
+
+// Clockwise display rotation from the `rotate` tag or the display matrix of a stream
+fn rotation(stream: &ffmpeg::format::stream::Stream) -> i32 {
+    let degrees = match stream.metadata().get("rotate") {
+        Some(rotate) => rotate.parse::<f64>().unwrap_or(0.0),
+        None => stream
+            .side_data()
+            .find(|data| data.kind() == ffmpeg::codec::packet::side_data::Type::DisplayMatrix)
+            .filter(|data| data.data().len() >= 9 * std::mem::size_of::<i32>())
+            .map(|data| {
+                // The matrix holds the counterclockwise rotation
+                let matrix = data.data().as_ptr() as *const i32;
+                -unsafe { ffmpeg::ffi::av_display_rotation_get(matrix) }
+            })
+            .unwrap_or(0.0),
+    };
+    (degrees.round() as i32).rem_euclid(360)
+}
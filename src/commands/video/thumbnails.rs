@@ -0,0 +1,262 @@
+use anyhow::{Context, Result};
+use image::imageops::{overlay, resize, FilterType};
+use image::{Rgb, RgbImage};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::video::video_utils::{decode_frames, save_frame, video_duration};
+use crate::utils::{file_has_right_extension, perform_io_sanity_check};
+use crate::{FrameFormat, VideoThumbnailsArgs};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 4] = ["ts", "mp4", "mkv", "mov"];
+
+// Pixels between tiles and around the sheet
+const SPACING: u32 = 4;
+
+// 5x7 glyphs of the characters used in timestamps, one row per byte
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+const GLYPHS: [(char, [u8; 7]); 12] = [
+    ('0', [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E]),
+    ('1', [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ('2', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F]),
+    ('3', [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E]),
+    ('4', [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02]),
+    ('5', [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E]),
+    ('6', [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E]),
+    ('7', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08]),
+    ('8', [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E]),
+    ('9', [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C]),
+    (':', [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00]),
+    ('.', [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C]),
+];
+
+/// Layout of the contact sheets
+#[derive(Debug, Clone, Copy)]
+struct Sheet {
+    count: usize,
+    columns: usize,
+    width: u32,
+    timestamps: bool,
+    format: FrameFormat,
+    quality: u8,
+}
+
+pub fn execute(args: VideoThumbnailsArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    if args.count == 0 || args.width == 0 || args.columns == Some(0) {
+        return Err(anyhow::Error::msg(
+            "Count, columns and width must be positive",
+        ));
+    }
+    if args.quality == 0 || args.quality > 100 {
+        return Err(anyhow::Error::msg("JPEG quality must be between 1 and 100"));
+    }
+
+    // An output file decides the image format
+    let format = match output.extension().and_then(|e| e.to_str()) {
+        Some(extension) => match extension.to_lowercase().as_str() {
+            "png" => FrameFormat::Png,
+            "jpg" | "jpeg" => FrameFormat::Jpeg,
+            _ => {
+                return Err(anyhow::Error::msg(format!(
+                    "Unsupported output extension: {}",
+                    extension
+                )))
+            }
+        },
+        None => args.format,
+    };
+
+    let sheet = Sheet {
+        count: args.count,
+        columns: args
+            .columns
+            .unwrap_or((args.count as f64).sqrt().ceil() as usize),
+        width: args.width,
+        timestamps: !args.no_timestamps,
+        format,
+        quality: args.quality,
+    };
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, output, sheet).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(input: &Path, output: &Path, sheet: Sheet) -> Result<()> {
+    let extension = match sheet.format {
+        FrameFormat::Png => "png",
+        FrameFormat::Jpeg => "jpg",
+    };
+
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+
+        // Write next to the other outputs when given a directory
+        let file_output = match output.extension() {
+            Some(_) => output.to_path_buf(),
+            None => {
+                let file_name = input
+                    .file_name()
+                    .with_context(|| format!("Failed to extract file name from: {:?}", input))?;
+                output.join(format!("{}.{}", file_name.to_string_lossy(), extension))
+            }
+        };
+        process_file(input, &file_output, sheet)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output path with the image extension appended, so that
+            // clip.mp4 and clip.mkv get separate sheets
+            let mut file_output = output.join(relative_path).into_os_string();
+            file_output.push(format!(".{}", extension));
+            let file_output = PathBuf::from(file_output);
+
+            // Ensure the output directory exists
+            if let Some(parent) = file_output.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+            }
+
+            // Process the file
+            process_file(file, &file_output, sheet)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// Sample evenly spaced frames of a video and write them as a grid
+fn process_file(input: &Path, output: &Path, sheet: Sheet) -> Result<()> {
+    // Middle of each of `count` equal parts of the video
+    let duration = video_duration(input)?;
+    let times: Vec<f64> = (0..sheet.count)
+        .map(|i| duration * (i as f64 + 0.5) / sheet.count as f64)
+        .collect();
+
+    // First frame at or after each time
+    let mut thumbnails: Vec<(f64, RgbImage)> = Vec::with_capacity(sheet.count);
+    decode_frames(input, |mut frame| {
+        if frame.timestamp >= times[thumbnails.len()] - 1e-6 {
+            let image = frame.to_rgb()?;
+            while thumbnails.len() < times.len()
+                && frame.timestamp >= times[thumbnails.len()] - 1e-6
+            {
+                thumbnails.push((frame.timestamp, image.clone()));
+            }
+        }
+        Ok(thumbnails.len() < times.len())
+    })?;
+    let (_, first) = thumbnails
+        .first()
+        .with_context(|| "No frames decoded from video")?;
+
+    // Tiles keep the aspect ratio of the video
+    let tile_width = sheet.width;
+    let tile_height =
+        ((first.height() as f64 * tile_width as f64 / first.width() as f64).round() as u32).max(1);
+    let columns = sheet.columns as u32;
+    let rows = sheet.count.div_ceil(sheet.columns) as u32;
+
+    let mut grid = RgbImage::new(
+        columns * (tile_width + SPACING) + SPACING,
+        rows * (tile_height + SPACING) + SPACING,
+    );
+    for (i, (timestamp, image)) in thumbnails.iter().enumerate() {
+        let mut tile = resize(image, tile_width, tile_height, FilterType::Lanczos3);
+        if sheet.timestamps {
+            draw_label(&mut tile, &format_timestamp(*timestamp));
+        }
+        let (column, row) = (i as u32 % columns, i as u32 / columns);
+        overlay(
+            &mut grid,
+            &tile,
+            (SPACING + column * (tile_width + SPACING)) as i64,
+            (SPACING + row * (tile_height + SPACING)) as i64,
+        );
+    }
+
+    save_frame(&grid, output, sheet.format, sheet.quality)
+}
+
+// Timestamp as minutes and seconds, with hours for long videos
+fn format_timestamp(seconds: f64) -> String {
+    let tenths = (seconds * 10.0).round() as u64;
+    let (hours, minutes, seconds) = (tenths / 36000, tenths / 600 % 60, tenths % 600);
+    match hours {
+        0 => format!("{:02}:{:02}.{}", minutes, seconds / 10, seconds % 10),
+        _ => format!("{}:{:02}:{:02}", hours, minutes, seconds / 10),
+    }
+}
+
+// Write white text on a black box in the bottom left corner of an image
+fn draw_label(image: &mut RgbImage, text: &str) {
+    // Text grows with the tile so that it stays readable
+    let scale = (image.width() / 160).max(1);
+    let margin = scale * 2;
+    let box_width = text.chars().count() as u32 * (GLYPH_WIDTH + 1) * scale + margin * 2 - scale;
+    let box_height = GLYPH_HEIGHT * scale + margin * 2;
+    if box_width > image.width() || box_height > image.height() {
+        return;
+    }
+
+    let top = image.height() - box_height;
+    for y in top..image.height() {
+        for x in 0..box_width {
+            image.put_pixel(x, y, Rgb([0, 0, 0]));
+        }
+    }
+
+    for (i, character) in text.chars().enumerate() {
+        let Some((_, rows)) = GLYPHS.iter().find(|(c, _)| *c == character) else {
+            continue;
+        };
+        let left = margin + i as u32 * (GLYPH_WIDTH + 1) * scale;
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits >> (GLYPH_WIDTH - 1 - column) & 1 == 1 {
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            image.put_pixel(
+                                left + column * scale + dx,
+                                top + margin + row as u32 * scale + dy,
+                                Rgb([255, 255, 255]),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
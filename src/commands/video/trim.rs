@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::commands::video::video_utils::{cut_video, video_duration};
+use crate::utils::{file_has_right_extension, perform_io_sanity_check};
+use crate::{VideoCutMode, VideoTrimArgs};
+
+// Admissible extensions for this command
+const EXTENSIONS: [&str; 4] = ["ts", "mp4", "mkv", "mov"];
+
+// Tolerance on the container duration, which is rounded
+const DURATION_TOLERANCE: f64 = 1e-3;
+
+pub fn execute(args: VideoTrimArgs) -> Result<()> {
+    // Parse the arguments
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+
+    let offset: f64 = args.offset;
+    let length: f64 = args.length;
+
+    if offset < 0.0 || length <= 0.0 {
+        return Err(anyhow::Error::msg(
+            "Offset must be non-negative and length positive",
+        ));
+    }
+
+    // Sanity checks on I/O
+    perform_io_sanity_check(input, output, false, true).with_context(|| "Sanity check failed")?;
+
+    // Process files
+    process(input, offset, length, output, args.mode).with_context(|| "Processing failed")?;
+
+    Ok(())
+}
+
+// Process all the content (single file or directory of files)
+fn process(
+    input: &Path,
+    offset: f64,
+    length: f64,
+    output: &Path,
+    mode: VideoCutMode,
+) -> Result<()> {
+    // Case of single input file
+    if input.is_file() {
+        // Check if the file has the right extension and process it
+        file_has_right_extension(input, &EXTENSIONS)?;
+
+        // Keep the file name when given a directory
+        let file_output = match output.extension() {
+            Some(_) => output.to_path_buf(),
+            None => output.join(
+                input
+                    .file_name()
+                    .with_context(|| format!("Failed to extract file name from: {:?}", input))?,
+            ),
+        };
+
+        process_file(input, offset, length, &file_output, mode)
+            .with_context(|| format!("Failed to process file: {:?}", input))?;
+    }
+    // Case of input being a directory
+    else {
+        // Find all files
+        let files: Vec<PathBuf> = WalkDir::new(input)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| file_has_right_extension(e.path(), &EXTENSIONS).is_ok())
+            .map(|e| e.path().to_path_buf())
+            .collect();
+
+        // Parallel loop over entries
+        files.par_iter().try_for_each(|file| -> Result<()> {
+            // Relative path wrt input directory
+            let relative_path = file
+                .strip_prefix(input)
+                .with_context(|| format!("Failed to strip prefix from path: {:?}", file))?;
+
+            // Nested output path
+            let file_output = output.join(relative_path);
+
+            // Ensure the output directory exists
+            if let Some(parent) = file_output.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+            }
+
+            // Process the file
+            process_file(file, offset, length, &file_output, mode)
+                .with_context(|| format!("Failed to process file: {:?}", file))?;
+
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+// Process a single file
+fn process_file(
+    input: &Path,
+    offset: f64,
+    length: f64,
+    output: &Path,
+    mode: VideoCutMode,
+) -> Result<()> {
+    // Videos are read while the clip is written
+    if input == output {
+        return Err(anyhow::Error::msg("Can't overwrite files"));
+    }
+
+    // Raise error if the requested range goes past the end of the file
+    let duration = video_duration(input)?;
+    if offset > duration {
+        return Err(anyhow::Error::msg(
+            "Requested offset larger than file length",
+        ));
+    } else if offset + length > duration + DURATION_TOLERANCE {
+        return Err(anyhow::Error::msg(
+            "Requested length larger than file length",
+        ));
+    }
+
+    cut_video(input, output, offset, offset + length, mode)
+}
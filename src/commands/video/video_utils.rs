@@ -0,0 +1,1028 @@
+extern crate ffmpeg_next as ffmpeg;
+
+use anyhow::{Context, Result};
+use ffmpeg::format::Pixel;
+use ffmpeg::media::Type;
+use ffmpeg::software::scaling::{context::Context as Scaler, flag::Flags};
+use ffmpeg::Rational;
+use image::codecs::jpeg::JpegEncoder;
+use image::RgbImage;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::{FrameFormat, VideoCodec, VideoCutMode};
+
+// Seconds past the end of a clip read before stopping
+const INTERLEAVING_MARGIN: f64 = 1.0;
+
+// Tolerance on frame times rounded by the container
+const TIME_TOLERANCE: f64 = 1e-6;
+
+/// A decoded video frame, converted to RGB on demand
+pub struct Frame<'a> {
+    /// Position of the frame in presentation order
+    pub index: usize,
+    /// Presentation time in seconds from the start of the stream
+    pub timestamp: f64,
+    /// Whether the frame is a keyframe
+    pub key: bool,
+    frame: &'a ffmpeg::frame::Video,
+    scaler: &'a mut Option<Scaler>,
+}
+
+impl Frame<'_> {
+    /// Convert the frame to an RGB image
+    pub fn to_rgb(&mut self) -> Result<RgbImage> {
+        let (width, height, format) =
+            (self.frame.width(), self.frame.height(), self.frame.format());
+
+        // The scaler is reused across frames unless their format or size changes
+        let outdated = match self.scaler.as_ref() {
+            Some(scaler) => {
+                scaler.input().format != format
+                    || scaler.input().width != width
+                    || scaler.input().height != height
+            }
+            None => true,
+        };
+        if outdated {
+            *self.scaler = Some(
+                Scaler::get(
+                    format,
+                    width,
+                    height,
+                    Pixel::RGB24,
+                    width,
+                    height,
+                    Flags::BILINEAR,
+                )
+                .with_context(|| "Failed to create RGB converter")?,
+            );
+        }
+        let scaler = self
+            .scaler
+            .as_mut()
+            .with_context(|| "Missing RGB converter")?;
+
+        let mut rgb = ffmpeg::frame::Video::empty();
+        scaler
+            .run(self.frame, &mut rgb)
+            .with_context(|| "Failed to convert frame to RGB")?;
+
+        // Rows of the converted frame may be padded
+        let stride = rgb.stride(0);
+        let row_length = width as usize * 3;
+        let data = rgb.data(0);
+        let mut buffer = Vec::with_capacity(row_length * height as usize);
+        for row in 0..height as usize {
+            buffer.extend_from_slice(&data[row * stride..row * stride + row_length]);
+        }
+
+        RgbImage::from_raw(width, height, buffer)
+            .with_context(|| "Failed to build image from frame")
+    }
+}
+
+/// Decode the best video stream of a file, calling `visit` on every frame until it returns false
+pub fn decode_frames(input: &Path, mut visit: impl FnMut(Frame) -> Result<bool>) -> Result<()> {
+    // Read context
+    let mut context = ffmpeg::format::input(&input).with_context(|| "Couldn't read video")?;
+
+    // Only select the video stream, throw away audio, subtitles etc
+    let (stream_index, parameters, time_base, start_time, frame_rate) = {
+        let stream = context
+            .streams()
+            .best(ffmpeg::media::Type::Video)
+            .with_context(|| "No video stream found in file")?;
+        (
+            stream.index(),
+            stream.parameters(),
+            f64::from(stream.time_base()),
+            stream.start_time(),
+            f64::from(stream.avg_frame_rate()),
+        )
+    };
+
+    // Timestamps are relative to the start of the stream when it is known
+    let start_time = match start_time {
+        ffmpeg::ffi::AV_NOPTS_VALUE => 0,
+        start_time => start_time,
+    };
+
+    // Create decoder
+    let context_decoder = ffmpeg::codec::context::Context::from_parameters(parameters)
+        .with_context(|| "Failed to create decoder context from video stream parameters")?;
+    let mut decoder = context_decoder
+        .decoder()
+        .video()
+        .with_context(|| "Failed to create video decoder from decoder context")?;
+
+    let mut frame = ffmpeg::frame::Video::empty();
+    let mut scaler = None;
+    let mut index = 0;
+
+    // Hand over every frame the decoder has ready, false once `visit` is done
+    let mut receive_frames = |decoder: &mut ffmpeg::decoder::Video| -> Result<bool> {
+        while decoder.receive_frame(&mut frame).is_ok() {
+            let timestamp = match frame.timestamp().or(frame.pts()) {
+                Some(timestamp) => (timestamp - start_time) as f64 * time_base,
+                None if frame_rate > 0.0 => index as f64 / frame_rate,
+                None => 0.0,
+            };
+            let key = frame.is_key();
+            let keep_going = visit(Frame {
+                index,
+                timestamp,
+                key,
+                frame: &frame,
+                scaler: &mut scaler,
+            })?;
+            index += 1;
+            if !keep_going {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    };
+
+    // Read packets until the end of the file
+    for (stream, packet) in context.packets() {
+        if stream.index() != stream_index {
+            continue;
+        }
+        decoder
+            .send_packet(&packet)
+            .with_context(|| "Failed to decode packet")?;
+        if !receive_frames(&mut decoder)? {
+            return Ok(());
+        }
+    }
+
+    // Drain the decoder
+    decoder
+        .send_eof()
+        .with_context(|| "Failed to flush decoder")?;
+    receive_frames(&mut decoder)?;
+
+    Ok(())
+}
+
+/// Write a frame as PNG or JPEG
+pub fn save_frame(image: &RgbImage, path: &Path, format: FrameFormat, quality: u8) -> Result<()> {
+    match format {
+        FrameFormat::Png => image
+            .save(path)
+            .with_context(|| format!("Couldn't save image to {:?}", path)),
+        FrameFormat::Jpeg => {
+            let file =
+                File::create(path).with_context(|| format!("Couldn't create file: {:?}", path))?;
+            JpegEncoder::new_with_quality(BufWriter::new(file), quality)
+                .encode_image(image)
+                .with_context(|| format!("Couldn't save image to {:?}", path))
+        }
+    }
+}
+
+/// Duration of a file in seconds as reported by its container
+pub fn video_duration(input: &Path) -> Result<f64> {
+    let context = ffmpeg::format::input(&input).with_context(|| "Couldn't read video")?;
+    match context.duration() {
+        duration if duration > 0 => Ok(duration as f64 / ffmpeg::ffi::AV_TIME_BASE as f64),
+        _ => Err(anyhow::Error::msg("Unknown video duration")),
+    }
+}
+
+/// Write the part of a video between `start` and `end` seconds to `output`
+///
+/// Copy mode remuxes packets without re-encoding, so the clip starts at the last keyframe at or
+/// before `start`. Re-encode mode encodes the frames in the range with H.264 for frame-accurate
+/// cuts. Audio packets are copied in both modes, other streams are dropped.
+pub fn cut_video(
+    input: &Path,
+    output: &Path,
+    start: f64,
+    end: f64,
+    mode: VideoCutMode,
+) -> Result<()> {
+    // Read context
+    let mut ictx = ffmpeg::format::input(&input).with_context(|| "Couldn't read video")?;
+    let mut octx = ffmpeg::format::output(&output)
+        .with_context(|| format!("Couldn't write to {:?}", output))?;
+    let global_header = octx
+        .format()
+        .flags()
+        .contains(ffmpeg::format::flag::Flags::GLOBAL_HEADER);
+
+    // Times are relative to the start of the video stream
+    let (video_index, origin) = {
+        let stream = ictx
+            .streams()
+            .best(Type::Video)
+            .with_context(|| "No video stream found in file")?;
+        let origin = match stream.start_time() {
+            ffmpeg::ffi::AV_NOPTS_VALUE => 0.0,
+            start_time => start_time as f64 * f64::from(stream.time_base()),
+        };
+        (stream.index(), origin)
+    };
+
+    // Map the video stream and audio streams to output streams
+    let mut stream_mapping: Vec<Option<usize>> = vec![None; ictx.nb_streams() as usize];
+    let mut time_bases = vec![Rational::new(0, 1); ictx.nb_streams() as usize];
+    let mut transcoder = None;
+    for stream in ictx.streams() {
+        let index = stream.index();
+        let medium = stream.parameters().medium();
+        if index != video_index && medium != Type::Audio {
+            continue;
+        }
+        time_bases[index] = stream.time_base();
+
+        if index == video_index && mode == VideoCutMode::Reencode {
+            transcoder = Some(Transcoder::new(&stream, &mut octx, global_header)?);
+            stream_mapping[index] = transcoder.as_ref().map(|t| t.ost_index);
+        } else {
+            let mut ost = octx
+                .add_stream(ffmpeg::encoder::find(ffmpeg::codec::Id::None))
+                .with_context(|| "Failed to add output stream")?;
+            ost.set_parameters(stream.parameters());
+            // Codec tags of the input container may not be valid in the output one
+            unsafe {
+                (*ost.parameters().as_mut_ptr()).codec_tag = 0;
+            }
+            stream_mapping[index] = Some(ost.index());
+        }
+    }
+
+    octx.set_metadata(ictx.metadata().to_owned());
+    octx.write_header()
+        .with_context(|| format!("Couldn't write header of {:?}", output))?;
+    let output_time_bases: Vec<Rational> = octx.streams().map(|s| s.time_base()).collect();
+
+    // Jump to the last keyframe at or before the start
+    if start > 0.0 {
+        let timestamp = ((start + origin) * ffmpeg::ffi::AV_TIME_BASE as f64) as i64;
+        ictx.seek(timestamp, ..timestamp)
+            .with_context(|| "Failed to seek in video")?;
+    }
+
+    // Start of the clip, known from the first video packet when copying
+    let mut clip_start = match mode {
+        VideoCutMode::Copy => None,
+        VideoCutMode::Reencode => Some(start),
+    };
+    let mut pending: Vec<(usize, ffmpeg::Packet)> = Vec::new();
+    let mut video_packets = 0;
+
+    for (stream, mut packet) in ictx.packets() {
+        let index = stream.index();
+        if stream_mapping[index].is_none() {
+            continue;
+        }
+        let time_base = f64::from(time_bases[index]);
+        let time = packet
+            .pts()
+            .or(packet.dts())
+            .map(|ts| ts as f64 * time_base - origin);
+
+        // Stop well after the end, packets of different streams are interleaved loosely
+        let decode_time = packet.dts().map(|ts| ts as f64 * time_base - origin);
+        if decode_time
+            .or(time)
+            .is_some_and(|t| t >= end + INTERLEAVING_MARGIN)
+        {
+            break;
+        }
+
+        // Hold other packets until the video tells where a copied clip starts
+        if clip_start.is_none() {
+            if index != video_index {
+                pending.push((index, packet));
+                continue;
+            }
+            clip_start = Some(time.unwrap_or(start).min(start));
+        }
+        let clip_start = clip_start.unwrap_or(start);
+
+        // Re-encode the video frames in the range
+        if let Some(transcoder) = transcoder.as_mut().filter(|_| index == video_index) {
+            let ost_time_base = output_time_bases[transcoder.ost_index];
+            transcoder.send_packet(&packet, (start, end), origin, ost_time_base, &mut octx)?;
+            continue;
+        }
+
+        // Packets decoded after the end are not needed, though references of earlier frames are
+        let kept = match index == video_index {
+            true => decode_time.or(time).is_some_and(|t| t < end),
+            false => time.is_some_and(|t| t >= clip_start && t < end),
+        };
+        if index == video_index && kept {
+            video_packets += 1;
+        }
+
+        let pending_packets = std::mem::take(&mut pending);
+        for (pending_index, mut pending_packet) in pending_packets {
+            let pending_time = pending_packet
+                .pts()
+                .or(pending_packet.dts())
+                .map(|ts| ts as f64 * f64::from(time_bases[pending_index]) - origin);
+            if pending_time.is_some_and(|t| t >= clip_start && t < end) {
+                write_packet(
+                    &mut pending_packet,
+                    pending_index,
+                    clip_start + origin,
+                    &stream_mapping,
+                    &time_bases,
+                    &output_time_bases,
+                    &mut octx,
+                )?;
+            }
+        }
+        if kept {
+            write_packet(
+                &mut packet,
+                index,
+                clip_start + origin,
+                &stream_mapping,
+                &time_bases,
+                &output_time_bases,
+                &mut octx,
+            )?;
+        }
+    }
+
+    // Flush the decoder and encoder
+    if let Some(transcoder) = transcoder.as_mut() {
+        let ost_time_base = output_time_bases[transcoder.ost_index];
+        transcoder.finish((start, end), origin, ost_time_base, &mut octx)?;
+        video_packets = transcoder.frames;
+    }
+
+    octx.write_trailer()
+        .with_context(|| format!("Couldn't finalize {:?}", output))?;
+
+    if video_packets == 0 {
+        return Err(anyhow::Error::msg("No video frames in the requested range"));
+    }
+
+    Ok(())
+}
+
+// Rebase a copied packet on the start of the clip and write it
+fn write_packet(
+    packet: &mut ffmpeg::Packet,
+    index: usize,
+    clip_start: f64,
+    stream_mapping: &[Option<usize>],
+    time_bases: &[Rational],
+    output_time_bases: &[Rational],
+    octx: &mut ffmpeg::format::context::Output,
+) -> Result<()> {
+    let ost_index = stream_mapping[index].with_context(|| "Missing output stream")?;
+
+    let offset = (clip_start / f64::from(time_bases[index])).round() as i64;
+    packet.set_pts(packet.pts().map(|ts| ts - offset));
+    packet.set_dts(packet.dts().map(|ts| ts - offset));
+    packet.rescale_ts(time_bases[index], output_time_bases[ost_index]);
+    packet.set_position(-1);
+    packet.set_stream(ost_index);
+
+    packet
+        .write_interleaved(octx)
+        .with_context(|| "Failed to write packet")
+}
+
+/// Decoder and H.264 encoder of a re-encoded video stream
+struct Transcoder {
+    decoder: ffmpeg::decoder::Video,
+    encoder: ffmpeg::encoder::video::Encoder,
+    time_base: Rational,
+    ost_index: usize,
+    frames: usize,
+}
+
+impl Transcoder {
+    fn new(
+        stream: &ffmpeg::format::stream::Stream,
+        octx: &mut ffmpeg::format::context::Output,
+        global_header: bool,
+    ) -> Result<Self> {
+        // Create decoder
+        let decoder = ffmpeg::codec::context::Context::from_parameters(stream.parameters())
+            .with_context(|| "Failed to create decoder context from video stream parameters")?
+            .decoder()
+            .video()
+            .with_context(|| "Failed to create video decoder from decoder context")?;
+
+        // Create encoder with the same frame layout
+        let codec = ffmpeg::encoder::find(ffmpeg::codec::Id::H264)
+            .with_context(|| "No H.264 encoder available")?;
+        let mut ost = octx
+            .add_stream(codec)
+            .with_context(|| "Failed to add output stream")?;
+        let mut encoder = ffmpeg::codec::context::Context::new_with_codec(codec)
+            .encoder()
+            .video()
+            .with_context(|| "Failed to create video encoder")?;
+        encoder.set_width(decoder.width());
+        encoder.set_height(decoder.height());
+        encoder.set_aspect_ratio(decoder.aspect_ratio());
+        encoder.set_format(decoder.format());
+        encoder.set_frame_rate(decoder.frame_rate());
+        encoder.set_time_base(stream.time_base());
+        if global_header {
+            encoder.set_flags(ffmpeg::codec::Flags::GLOBAL_HEADER);
+        }
+        let encoder = encoder
+            .open_as(codec)
+            .with_context(|| "Failed to open H.264 encoder")?;
+        ost.set_parameters(&encoder);
+
+        Ok(Transcoder {
+            decoder,
+            encoder,
+            time_base: stream.time_base(),
+            ost_index: ost.index(),
+            frames: 0,
+        })
+    }
+
+    fn send_packet(
+        &mut self,
+        packet: &ffmpeg::Packet,
+        range: (f64, f64),
+        origin: f64,
+        ost_time_base: Rational,
+        octx: &mut ffmpeg::format::context::Output,
+    ) -> Result<()> {
+        self.decoder
+            .send_packet(packet)
+            .with_context(|| "Failed to decode packet")?;
+        self.encode_frames(range, origin, ost_time_base, octx)
+    }
+
+    fn finish(
+        &mut self,
+        range: (f64, f64),
+        origin: f64,
+        ost_time_base: Rational,
+        octx: &mut ffmpeg::format::context::Output,
+    ) -> Result<()> {
+        self.decoder
+            .send_eof()
+            .with_context(|| "Failed to flush decoder")?;
+        self.encode_frames(range, origin, ost_time_base, octx)?;
+        self.encoder
+            .send_eof()
+            .with_context(|| "Failed to flush encoder")?;
+        write_encoded(
+            &mut self.encoder,
+            self.ost_index,
+            self.time_base,
+            ost_time_base,
+            octx,
+        )
+    }
+
+    // Encode the decoded frames in the range, with times relative to its start
+    fn encode_frames(
+        &mut self,
+        (start, end): (f64, f64),
+        origin: f64,
+        ost_time_base: Rational,
+        octx: &mut ffmpeg::format::context::Output,
+    ) -> Result<()> {
+        let time_base = f64::from(self.time_base);
+        let mut frame = ffmpeg::frame::Video::empty();
+        while self.decoder.receive_frame(&mut frame).is_ok() {
+            let Some(timestamp) = frame.timestamp().or(frame.pts()) else {
+                continue;
+            };
+            let time = timestamp as f64 * time_base - origin;
+            if time < start - TIME_TOLERANCE || time >= end - TIME_TOLERANCE {
+                continue;
+            }
+            frame.set_pts(Some(((time - start) / time_base).round() as i64));
+            frame.set_kind(ffmpeg::picture::Type::None);
+            self.encoder
+                .send_frame(&frame)
+                .with_context(|| "Failed to encode frame")?;
+            self.frames += 1;
+            write_encoded(
+                &mut self.encoder,
+                self.ost_index,
+                self.time_base,
+                ost_time_base,
+                octx,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Write the packets an encoder has ready, with timestamps in the encoder time base
+fn write_encoded(
+    encoder: &mut ffmpeg::encoder::video::Encoder,
+    ost_index: usize,
+    time_base: Rational,
+    ost_time_base: Rational,
+    octx: &mut ffmpeg::format::context::Output,
+) -> Result<()> {
+    let mut packet = ffmpeg::Packet::empty();
+    while encoder.receive_packet(&mut packet).is_ok() {
+        packet.set_stream(ost_index);
+        packet.rescale_ts(time_base, ost_time_base);
+        packet
+            .write_interleaved(octx)
+            .with_context(|| "Failed to write packet")?;
+    }
+    Ok(())
+}
+
+/// Target encoding of `video convert`, `None` keeps the original property
+#[derive(Debug, Clone, Copy)]
+pub struct Conversion {
+    pub codec: VideoCodec,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fps: Option<f64>,
+    pub pixel_format: Option<Pixel>,
+    pub crf: Option<u32>,
+}
+
+/// Re-encode the video stream of a file at a constant frame rate, copying its audio streams
+///
+/// Frames are scaled with Lanczos filtering and duplicated or dropped to reach the target frame
+/// rate. Setting only one of width and height keeps the aspect ratio.
+pub fn convert_video(input: &Path, output: &Path, conversion: Conversion) -> Result<()> {
+    // Read context
+    let mut ictx = ffmpeg::format::input(&input).with_context(|| "Couldn't read video")?;
+    let mut octx = ffmpeg::format::output(&output)
+        .with_context(|| format!("Couldn't write to {:?}", output))?;
+    let global_header = octx
+        .format()
+        .flags()
+        .contains(ffmpeg::format::flag::Flags::GLOBAL_HEADER);
+
+    let video_index = ictx
+        .streams()
+        .best(Type::Video)
+        .with_context(|| "No video stream found in file")?
+        .index();
+
+    // Map the video stream to an encoder and copy audio streams
+    let mut stream_mapping: Vec<Option<usize>> = vec![None; ictx.nb_streams() as usize];
+    let mut time_bases = vec![Rational::new(0, 1); ictx.nb_streams() as usize];
+    let mut converter = None;
+    let mut origin = 0.0;
+    for stream in ictx.streams() {
+        let index = stream.index();
+        time_bases[index] = stream.time_base();
+
+        if index == video_index {
+            // Times are relative to the start of the video stream
+            origin = match stream.start_time() {
+                ffmpeg::ffi::AV_NOPTS_VALUE => 0.0,
+                start_time => start_time as f64 * f64::from(stream.time_base()),
+            };
+            let new_converter = Converter::new(&stream, &mut octx, conversion, global_header)?;
+            stream_mapping[index] = Some(new_converter.ost_index);
+            converter = Some(new_converter);
+        } else if stream.parameters().medium() == Type::Audio {
+            let mut ost = octx
+                .add_stream(ffmpeg::encoder::find(ffmpeg::codec::Id::None))
+                .with_context(|| "Failed to add output stream")?;
+            ost.set_parameters(stream.parameters());
+            // Codec tags of the input container may not be valid in the output one
+            unsafe {
+                (*ost.parameters().as_mut_ptr()).codec_tag = 0;
+            }
+            stream_mapping[index] = Some(ost.index());
+        }
+    }
+    let mut converter = converter.with_context(|| "No video stream found in file")?;
+
+    octx.set_metadata(ictx.metadata().to_owned());
+    octx.write_header()
+        .with_context(|| format!("Couldn't write header of {:?}", output))?;
+    let output_time_bases: Vec<Rational> = octx.streams().map(|s| s.time_base()).collect();
+    let ost_time_base = output_time_bases[converter.ost_index];
+
+    for (stream, mut packet) in ictx.packets() {
+        let index = stream.index();
+        if index == video_index {
+            converter.send_packet(&packet, origin, ost_time_base, &mut octx)?;
+        } else if stream_mapping[index].is_some() {
+            write_packet(
+                &mut packet,
+                index,
+                origin,
+                &stream_mapping,
+                &time_bases,
+                &output_time_bases,
+                &mut octx,
+            )?;
+        }
+    }
+
+    // Flush the decoder and encoder
+    converter.finish(origin, ost_time_base, &mut octx)?;
+
+    octx.write_trailer()
+        .with_context(|| format!("Couldn't finalize {:?}", output))?;
+
+    Ok(())
+}
+
+/// Encode RGB images of the given size into a video at a constant frame rate
+///
+/// Returns the number of frames written.
+pub fn encode_images(
+    output: &Path,
+    images: impl Iterator<Item = Result<RgbImage>>,
+    (width, height): (u32, u32),
+    fps: f64,
+    codec: VideoCodec,
+    crf: Option<u32>,
+) -> Result<usize> {
+    let mut octx = ffmpeg::format::output(&output)
+        .with_context(|| format!("Couldn't write to {:?}", output))?;
+    let global_header = octx
+        .format()
+        .flags()
+        .contains(ffmpeg::format::flag::Flags::GLOBAL_HEADER);
+
+    // Create encoder, RGB stays lossless with FFV1
+    let format = match codec {
+        VideoCodec::H264 | VideoCodec::Vp9 => Pixel::YUV420P,
+        VideoCodec::Mjpeg => Pixel::YUVJ420P,
+        VideoCodec::Ffv1 => Pixel::GBRP,
+    };
+    let frame_rate = fps_to_rational(fps);
+    let (mut encoder, ost_index) = open_encoder(
+        &mut octx,
+        codec,
+        crf,
+        (width, height, format),
+        frame_rate,
+        global_header,
+    )?;
+
+    octx.write_header()
+        .with_context(|| format!("Couldn't write header of {:?}", output))?;
+    let ost_time_base = octx
+        .stream(ost_index)
+        .with_context(|| "Missing output stream")?
+        .time_base();
+
+    let mut scaler = Scaler::get(
+        Pixel::RGB24,
+        width,
+        height,
+        format,
+        width,
+        height,
+        Flags::BILINEAR,
+    )
+    .with_context(|| "Failed to create pixel format converter")?;
+    let mut rgb = ffmpeg::frame::Video::new(Pixel::RGB24, width, height);
+
+    let mut n_frames = 0;
+    for image in images {
+        let image = image?;
+        if image.dimensions() != (width, height) {
+            return Err(anyhow::Error::msg(format!(
+                "Image of size {:?} in a video of size {:?}",
+                image.dimensions(),
+                (width, height)
+            )));
+        }
+
+        // Rows of the frame may be padded
+        let stride = rgb.stride(0);
+        let row_length = width as usize * 3;
+        let data = rgb.data_mut(0);
+        for (row, pixels) in image.as_raw().chunks_exact(row_length).enumerate() {
+            data[row * stride..row * stride + row_length].copy_from_slice(pixels);
+        }
+
+        let mut frame = ffmpeg::frame::Video::empty();
+        scaler
+            .run(&rgb, &mut frame)
+            .with_context(|| "Failed to convert image")?;
+        frame.set_pts(Some(n_frames as i64));
+        encoder
+            .send_frame(&frame)
+            .with_context(|| "Failed to encode frame")?;
+        write_encoded(
+            &mut encoder,
+            ost_index,
+            frame_rate.invert(),
+            ost_time_base,
+            &mut octx,
+        )?;
+        n_frames += 1;
+    }
+
+    // Flush the encoder
+    encoder
+        .send_eof()
+        .with_context(|| "Failed to flush encoder")?;
+    write_encoded(
+        &mut encoder,
+        ost_index,
+        frame_rate.invert(),
+        ost_time_base,
+        &mut octx,
+    )?;
+
+    octx.write_trailer()
+        .with_context(|| format!("Couldn't finalize {:?}", output))?;
+
+    Ok(n_frames)
+}
+
+/// Decoder, scaler and encoder of a converted video stream
+struct Converter {
+    decoder: ffmpeg::decoder::Video,
+    encoder: ffmpeg::encoder::video::Encoder,
+    scaler: Option<Scaler>,
+    /// Output frame layout
+    width: u32,
+    height: u32,
+    format: Pixel,
+    /// Input and encoder time bases, the latter being the inverse of the frame rate
+    time_base: Rational,
+    encoder_time_base: Rational,
+    /// Last converted frame, encoded until the next one is due
+    held: Option<(f64, ffmpeg::frame::Video)>,
+    next_pts: i64,
+    ost_index: usize,
+}
+
+impl Converter {
+    fn new(
+        stream: &ffmpeg::format::stream::Stream,
+        octx: &mut ffmpeg::format::context::Output,
+        conversion: Conversion,
+        global_header: bool,
+    ) -> Result<Self> {
+        // Create decoder
+        let decoder = ffmpeg::codec::context::Context::from_parameters(stream.parameters())
+            .with_context(|| "Failed to create decoder context from video stream parameters")?
+            .decoder()
+            .video()
+            .with_context(|| "Failed to create video decoder from decoder context")?;
+
+        // Resolve the output layout, keeping the aspect ratio when a side is missing
+        let (width, height) = match (conversion.width, conversion.height) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (
+                width,
+                even(decoder.height() as f64 * width as f64 / decoder.width() as f64),
+            ),
+            (None, Some(height)) => (
+                even(decoder.width() as f64 * height as f64 / decoder.height() as f64),
+                height,
+            ),
+            (None, None) => (decoder.width(), decoder.height()),
+        };
+        let format = match (conversion.pixel_format, conversion.codec) {
+            (Some(format), _) => format,
+            (None, VideoCodec::H264 | VideoCodec::Vp9) => Pixel::YUV420P,
+            (None, VideoCodec::Mjpeg) => Pixel::YUVJ420P,
+            (None, VideoCodec::Ffv1) => decoder.format(),
+        };
+        let frame_rate = match conversion.fps {
+            Some(fps) => fps_to_rational(fps),
+            None => stream.avg_frame_rate(),
+        };
+        if frame_rate.numerator() <= 0 || frame_rate.denominator() <= 0 {
+            return Err(anyhow::Error::msg("Unknown frame rate, set one explicitly"));
+        }
+
+        // Create encoder
+        let (encoder, ost_index) = open_encoder(
+            octx,
+            conversion.codec,
+            conversion.crf,
+            (width, height, format),
+            frame_rate,
+            global_header,
+        )?;
+
+        Ok(Converter {
+            decoder,
+            encoder,
+            scaler: None,
+            width,
+            height,
+            format,
+            time_base: stream.time_base(),
+            encoder_time_base: frame_rate.invert(),
+            held: None,
+            next_pts: 0,
+            ost_index,
+        })
+    }
+
+    fn send_packet(
+        &mut self,
+        packet: &ffmpeg::Packet,
+        origin: f64,
+        ost_time_base: Rational,
+        octx: &mut ffmpeg::format::context::Output,
+    ) -> Result<()> {
+        self.decoder
+            .send_packet(packet)
+            .with_context(|| "Failed to decode packet")?;
+        self.convert_frames(origin, ost_time_base, octx)
+    }
+
+    fn finish(
+        &mut self,
+        origin: f64,
+        ost_time_base: Rational,
+        octx: &mut ffmpeg::format::context::Output,
+    ) -> Result<()> {
+        self.decoder
+            .send_eof()
+            .with_context(|| "Failed to flush decoder")?;
+        self.convert_frames(origin, ost_time_base, octx)?;
+
+        // The last frame lasts one output frame
+        if let Some((time, _)) = self.held {
+            self.encode_until(
+                time + f64::from(self.encoder_time_base),
+                ost_time_base,
+                octx,
+            )?;
+        }
+
+        self.encoder
+            .send_eof()
+            .with_context(|| "Failed to flush encoder")?;
+        write_encoded(
+            &mut self.encoder,
+            self.ost_index,
+            self.encoder_time_base,
+            ost_time_base,
+            octx,
+        )
+    }
+
+    // Scale the decoded frames and encode the held one for the output frames due before them
+    fn convert_frames(
+        &mut self,
+        origin: f64,
+        ost_time_base: Rational,
+        octx: &mut ffmpeg::format::context::Output,
+    ) -> Result<()> {
+        let mut frame = ffmpeg::frame::Video::empty();
+        while self.decoder.receive_frame(&mut frame).is_ok() {
+            let Some(timestamp) = frame.timestamp().or(frame.pts()) else {
+                continue;
+            };
+            let time = timestamp as f64 * f64::from(self.time_base) - origin;
+            self.encode_until(time, ost_time_base, octx)?;
+
+            // The scaler is reused across frames unless their format or size changes
+            let outdated = match self.scaler.as_ref() {
+                Some(scaler) => {
+                    scaler.input().format != frame.format()
+                        || scaler.input().width != frame.width()
+                        || scaler.input().height != frame.height()
+                }
+                None => true,
+            };
+            if outdated {
+                self.scaler = Some(
+                    Scaler::get(
+                        frame.format(),
+                        frame.width(),
+                        frame.height(),
+                        self.format,
+                        self.width,
+                        self.height,
+                        Flags::LANCZOS,
+                    )
+                    .with_context(|| "Failed to create frame scaler")?,
+                );
+            }
+            let scaler = self.scaler.as_mut().with_context(|| "Missing scaler")?;
+
+            let mut scaled = ffmpeg::frame::Video::empty();
+            scaler
+                .run(&frame, &mut scaled)
+                .with_context(|| "Failed to scale frame")?;
+            self.held = Some((time, scaled));
+        }
+        Ok(())
+    }
+
+    // Encode the held frame for every output frame due before `time`
+    fn encode_until(
+        &mut self,
+        time: f64,
+        ost_time_base: Rational,
+        octx: &mut ffmpeg::format::context::Output,
+    ) -> Result<()> {
+        let frame_duration = f64::from(self.encoder_time_base);
+        while let Some((_, held)) = self.held.as_mut() {
+            if self.next_pts as f64 * frame_duration >= time - TIME_TOLERANCE {
+                break;
+            }
+            held.set_pts(Some(self.next_pts));
+            held.set_kind(ffmpeg::picture::Type::None);
+            self.encoder
+                .send_frame(held)
+                .with_context(|| "Failed to encode frame")?;
+            self.next_pts += 1;
+            write_encoded(
+                &mut self.encoder,
+                self.ost_index,
+                self.encoder_time_base,
+                ost_time_base,
+                octx,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Frame rate as a rational, exact for rates with up to three decimals
+fn fps_to_rational(fps: f64) -> Rational {
+    Rational::new((fps * 1000.0).round() as i32, 1000).reduce()
+}
+
+// Add a video stream to the output with an encoder of `codec` and its options
+fn open_encoder(
+    octx: &mut ffmpeg::format::context::Output,
+    codec: VideoCodec,
+    crf: Option<u32>,
+    (width, height, format): (u32, u32, Pixel),
+    frame_rate: Rational,
+    global_header: bool,
+) -> Result<(ffmpeg::encoder::video::Encoder, usize)> {
+    let (id, options) = match codec {
+        VideoCodec::H264 => (
+            ffmpeg::codec::Id::H264,
+            vec![("crf", crf.unwrap_or(23).to_string())],
+        ),
+        VideoCodec::Vp9 => (
+            ffmpeg::codec::Id::VP9,
+            vec![
+                ("crf", crf.unwrap_or(31).to_string()),
+                ("b", "0".to_string()),
+                ("row-mt", "1".to_string()),
+            ],
+        ),
+        VideoCodec::Mjpeg => (ffmpeg::codec::Id::MJPEG, vec![]),
+        VideoCodec::Ffv1 => (ffmpeg::codec::Id::FFV1, vec![("level", "3".to_string())]),
+    };
+    let encoder_codec =
+        ffmpeg::encoder::find(id).with_context(|| format!("No {:?} encoder available", codec))?;
+    let mut ost = octx
+        .add_stream(encoder_codec)
+        .with_context(|| "Failed to add output stream")?;
+    let mut encoder = ffmpeg::codec::context::Context::new_with_codec(encoder_codec)
+        .encoder()
+        .video()
+        .with_context(|| "Failed to create video encoder")?;
+    encoder.set_width(width);
+    encoder.set_height(height);
+    encoder.set_format(format);
+    encoder.set_frame_rate(Some(frame_rate));
+    encoder.set_time_base(frame_rate.invert());
+    if global_header {
+        encoder.set_flags(ffmpeg::codec::Flags::GLOBAL_HEADER);
+    }
+    let mut dictionary = ffmpeg::Dictionary::new();
+    for (key, value) in &options {
+        dictionary.set(key, value);
+    }
+    let encoder = encoder
+        .open_as_with(encoder_codec, dictionary)
+        .with_context(|| format!("Failed to open {:?} encoder", codec))?;
+    ost.set_parameters(&encoder);
+
+    Ok((encoder, ost.index()))
+}
+
+/// Round a derived frame size to an even number, as chroma subsampled formats require
+pub fn even(size: f64) -> u32 {
+    ((size / 2.0).round() as u32 * 2).max(2)
+}
+
+/// Reject odd requested sizes for the codecs encoding chroma subsampled frames
+pub fn check_even_size(codec: VideoCodec, width: Option<u32>, height: Option<u32>) -> Result<()> {
+    let odd = |size: Option<u32>| size.is_some_and(|size| size % 2 == 1);
+    if codec != VideoCodec::Ffv1 && (odd(width) || odd(height)) {
+        return Err(anyhow::Error::msg(format!(
+            "{:?} requires an even width and height",
+            codec
+        )));
+    }
+    Ok(())
+}
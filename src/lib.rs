@@ -1,4 +1,4 @@
-use clap::{Args, Parser};
+use clap::{Args, Parser, ValueEnum};
 
 pub mod commands;
 pub mod utils;
@@ -17,27 +17,95 @@ pub struct ImageSummaryArgs {
     pub target: String,
 }
 
+/// What happens to the EXIF metadata of images
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MetadataMode {
+    /// Drop all metadata
+    Strip,
+    /// Keep the metadata of the primary image
+    Keep,
+    /// Keep the metadata without GPS location
+    KeepNoGps,
+}
+
+/// How `image resize` maps images to `--height` and `--width`, or `--size` and `--factor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ResizeMode {
+    /// Exactly the requested size, a missing side follows the aspect ratio
+    Stretch,
+    /// Largest size within the requested one keeping the aspect ratio, a missing side is unbounded
+    Fit,
+    /// Cover the requested size keeping the aspect ratio, then crop the centre
+    Fill,
+    /// Fit, then pad to the requested size with `--pad-color`
+    Pad,
+    /// Shortest side to `--size`, keeping the aspect ratio
+    Shortest,
+    /// Longest side to `--size`, keeping the aspect ratio
+    Longest,
+    /// Scale both sides by `--factor`
+    Scale,
+}
+
+/// Resampling filter of `image resize`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ResizeFilter {
+    Nearest,
+    Triangle,
+    CatmullRom,
+    Gaussian,
+    Lanczos3,
+}
+
 #[derive(Debug, Parser)]
 pub struct ImageResizeArgs {
     /// Input file or directory
     #[arg(required = true)]
     pub input: String,
 
-    /// Requested height
-    #[arg(required = true)]
-    pub height: u32,
-
-    /// Requested width
-    #[arg(required = true)]
-    pub width: u32,
-
     /// Output file or directory
     #[arg(required = true)]
     pub output: String,
 
+    /// Requested height, not used in shortest, longest and scale modes
+    #[arg(long)]
+    pub height: Option<u32>,
+
+    /// Requested width, not used in shortest, longest and scale modes
+    #[arg(long)]
+    pub width: Option<u32>,
+
     /// Flag to enable overwriting of input file
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub overwrite: bool,
+
+    /// How images are mapped to the requested size
+    #[arg(long, value_enum, default_value_t = ResizeMode::Stretch)]
+    pub mode: ResizeMode,
+
+    /// Side length in shortest and longest modes
+    #[arg(long)]
+    pub size: Option<u32>,
+
+    /// Factor both sides are multiplied by in scale mode, e.g. 0.5
+    #[arg(long)]
+    pub factor: Option<f64>,
+
+    /// Resampling filter
+    #[arg(long, value_enum, default_value_t = ResizeFilter::Lanczos3)]
+    pub filter: ResizeFilter,
+
+    /// Color of the padding in pad mode, as RRGGBB hex
+    #[arg(long, default_value = "000000")]
+    pub pad_color: String,
+
+    /// Rotate and flip images according to their EXIF orientation first
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub auto_orient: bool,
+
+    /// EXIF metadata of the outputs, only kept in JPEG and PNG files
+    #[arg(long, value_enum, default_value_t = MetadataMode::Strip)]
+    pub metadata: MetadataMode,
 }
 
 #[derive(Debug, Parser)]
@@ -61,6 +129,14 @@ pub struct ImageTessellateArgs {
     /// Delete original file
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub delete_original: bool,
+
+    /// Rotate and flip images according to their EXIF orientation first
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub auto_orient: bool,
+
+    /// EXIF metadata of the outputs, only kept in JPEG and PNG files
+    #[arg(long, value_enum, default_value_t = MetadataMode::Strip)]
+    pub metadata: MetadataMode,
 }
 
 #[derive(Debug, Parser)]
@@ -76,6 +152,14 @@ pub struct ImageToLandscapeArgs {
     /// Delete original file
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub overwrite: bool,
+
+    /// Rotate and flip images according to their EXIF orientation first
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub auto_orient: bool,
+
+    /// EXIF metadata of the outputs, only kept in JPEG and PNG files
+    #[arg(long, value_enum, default_value_t = MetadataMode::Strip)]
+    pub metadata: MetadataMode,
 }
 
 #[derive(Debug, Parser)]
@@ -91,6 +175,14 @@ pub struct ImageToPortraitArgs {
     /// Delete original file
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub overwrite: bool,
+
+    /// Rotate and flip images according to their EXIF orientation first
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub auto_orient: bool,
+
+    /// EXIF metadata of the outputs, only kept in JPEG and PNG files
+    #[arg(long, value_enum, default_value_t = MetadataMode::Strip)]
+    pub metadata: MetadataMode,
 }
 
 #[derive(Debug, Parser)]
@@ -98,6 +190,18 @@ pub struct AudioSummaryArgs {
     /// Target directory or file
     #[arg(required = true)]
     pub target: String,
+
+    /// Decode WAV files and report their loudness
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub loudness: bool,
+
+    /// Print only a CSV table with one row per file, its properties and tags
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub per_file: bool,
+
+    /// Write the per-file table to a CSV or Parquet file
+    #[arg(long)]
+    pub output: Option<String>,
 }
 
 #[derive(Debug, Parser)]
@@ -119,6 +223,46 @@ pub struct AudioSplitArgs {
     pub delete_original: bool,
 }
 
+/// Resamplers of `audio resample`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ResampleMethod {
+    /// Synchronous FFT resampler
+    Fft,
+    /// Windowed sinc interpolation, the most configurable
+    Sinc,
+    /// Linear interpolation without anti-aliasing, fast but only fit for previews
+    Linear,
+}
+
+/// Interpolation between the precomputed points of a sinc filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SincInterpolation {
+    Nearest,
+    Linear,
+    Quadratic,
+    Cubic,
+}
+
+/// Window applied to sinc filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SincWindow {
+    Blackman,
+    Blackman2,
+    BlackmanHarris,
+    BlackmanHarris2,
+    Hann,
+    Hann2,
+}
+
+/// What `audio resample` does when resampling pushes samples past full scale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ClipHandling {
+    /// Print the files that clip
+    Report,
+    /// Lower the gain of the files that would clip
+    Prevent,
+}
+
 #[derive(Debug, Parser)]
 pub struct AudioResampleArgs {
     /// Input file or directory
@@ -133,6 +277,30 @@ pub struct AudioResampleArgs {
     #[arg(required = true)]
     pub output: String,
 
+    /// Resampler to use
+    #[arg(long, value_enum, default_value_t = ResampleMethod::Fft)]
+    pub method: ResampleMethod,
+
+    /// Interpolation of the sinc resampler, unused for ratios it handles exactly
+    #[arg(long, value_enum, default_value_t = SincInterpolation::Cubic)]
+    pub interpolation: SincInterpolation,
+
+    /// Window of the sinc resampler
+    #[arg(long, value_enum, default_value_t = SincWindow::BlackmanHarris2)]
+    pub window: SincWindow,
+
+    /// Length of the sinc filter, longer is sharper and slower
+    #[arg(long, default_value_t = 256)]
+    pub sinc_len: usize,
+
+    /// Cutoff of the sinc filter relative to the lowest Nyquist frequency, derived from the window when not given
+    #[arg(long)]
+    pub cutoff: Option<f64>,
+
+    /// What to do when resampling pushes samples past full scale
+    #[arg(long, value_enum, default_value_t = ClipHandling::Report)]
+    pub clipping: ClipHandling,
+
     /// Flag to enable overwriting of input file
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub overwrite: bool,
@@ -161,11 +329,916 @@ pub struct AudioTrimArgs {
     pub overwrite: bool,
 }
 
+#[derive(Debug, Parser)]
+pub struct AudioNormalizeArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Target integrated loudness in LUFS (default -23 when no target is given)
+    #[arg(long, conflicts_with = "peak", allow_hyphen_values = true)]
+    pub lufs: Option<f64>,
+
+    /// Target sample peak in dBFS
+    #[arg(long, allow_hyphen_values = true)]
+    pub peak: Option<f64>,
+
+    /// Limiter ceiling in dBFS
+    #[arg(long, default_value_t = -1.0, allow_hyphen_values = true)]
+    pub ceiling: f64,
+
+    /// Flag to enable overwriting of input file
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub overwrite: bool,
+}
+
+/// Channel operation applied by `audio channels`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChannelMode {
+    /// Downmix to mono by averaging, or keep only `--channel`
+    Mono,
+    /// Write each channel to its own file
+    Split,
+    /// Reorder, drop or duplicate channels following `--map`
+    Remap,
+}
+
+#[derive(Debug, Parser)]
+pub struct AudioChannelsArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Channel operation
+    #[arg(required = true, value_enum)]
+    pub mode: ChannelMode,
+
+    /// Output file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Channel to keep when converting to mono (0-based)
+    #[arg(long)]
+    pub channel: Option<usize>,
+
+    /// Comma-separated input channel for each output channel, e.g. `1,0` or `0,0`
+    #[arg(long, value_delimiter = ',')]
+    pub map: Vec<usize>,
+
+    /// Optionally resample to this sample rate in the same pass
+    #[arg(long)]
+    pub sr: Option<u32>,
+
+    /// Flag to enable overwriting of input file
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub overwrite: bool,
+}
+
+/// Sample format of WAV files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WavSampleFormat {
+    /// Integer PCM
+    Int,
+    /// 32-bit IEEE float
+    Float,
+}
+
+/// Dither added before reducing the bit depth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Dither {
+    /// Plain rounding
+    None,
+    /// Triangular probability density function dither
+    Tpdf,
+}
+
+#[derive(Debug, Parser)]
+pub struct AudioConvertArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Target bits per sample (8, 16, 24 or 32), keeps the original when not given
+    #[arg(long)]
+    pub bits: Option<u16>,
+
+    /// Target sample format, keeps the original when not given
+    #[arg(long, value_enum)]
+    pub format: Option<WavSampleFormat>,
+
+    /// Dither applied when reducing the bit depth
+    #[arg(long, value_enum, default_value_t = Dither::Tpdf)]
+    pub dither: Dither,
+
+    /// Shape the quantization noise towards high frequencies
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub noise_shaping: bool,
+
+    /// Seed for the dither noise
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// Flag to enable overwriting of input file
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct AudioCheckArgs {
+    /// Target directory or file
+    #[arg(required = true)]
+    pub target: String,
+
+    /// Minimum number of consecutive full-scale samples counted as clipping
+    #[arg(long, default_value_t = 3)]
+    pub clip_run: usize,
+
+    /// Largest acceptable DC offset, as a fraction of full scale
+    #[arg(long, default_value_t = 0.01)]
+    pub dc_threshold: f64,
+
+    /// Level in dBFS under which a 50 ms window is considered silent
+    #[arg(long, default_value_t = -60.0, allow_hyphen_values = true)]
+    pub silence_threshold: f64,
+
+    /// Fraction of silent windows above which a file is reported as mostly silent
+    #[arg(long, default_value_t = 0.9)]
+    pub silence_ratio: f64,
+
+    /// Largest acceptable difference in seconds between header and decoded durations
+    #[arg(long, default_value_t = 0.1)]
+    pub duration_tolerance: f64,
+
+    /// Write the per-file report to a CSV or Parquet file
+    #[arg(long)]
+    pub output: Option<String>,
+}
+
+/// Features computed by `audio spectrogram`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SpectrogramKind {
+    /// STFT magnitude
+    Stft,
+    /// Log-power mel spectrogram in dB
+    Mel,
+    /// Mel-frequency cepstral coefficients
+    Mfcc,
+}
+
+/// File format of computed features
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FeatureFormat {
+    /// Grayscale image, time along x and low frequencies at the bottom
+    Png,
+    /// NumPy float32 array of shape (frames, bins)
+    Npy,
+    /// Table with one row per frame and one column per bin
+    Parquet,
+}
+
+#[derive(Debug, Parser)]
+pub struct AudioSpectrogramArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Features to compute
+    #[arg(long, value_enum, default_value_t = SpectrogramKind::Mel)]
+    pub kind: SpectrogramKind,
+
+    /// Output format, taken from the output extension or npy when not given
+    #[arg(long, value_enum)]
+    pub format: Option<FeatureFormat>,
+
+    /// Window (and FFT) length in samples
+    #[arg(long, default_value_t = 1024)]
+    pub window: usize,
+
+    /// Hop between consecutive windows in samples
+    #[arg(long, default_value_t = 256)]
+    pub hop: usize,
+
+    /// Number of mel bands
+    #[arg(long, default_value_t = 80)]
+    pub n_mels: usize,
+
+    /// Number of MFCCs kept
+    #[arg(long, default_value_t = 13)]
+    pub n_mfcc: usize,
+}
+
+#[derive(Debug, Parser)]
+pub struct AudioTransformArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Operations applied in order, as name=value: fade-in, fade-out and pad (seconds),
+    /// gain (dB), speed and tempo (factor) or pitch (semitones)
+    #[arg(
+        long = "op",
+        required = true,
+        value_delimiter = ',',
+        allow_hyphen_values = true
+    )]
+    pub operations: Vec<String>,
+
+    /// Flag to enable overwriting of input file
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct AudioConcatArgs {
+    /// Input directory (files joined in sorted order) or CSV/Parquet manifest with a path column
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output WAV file
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Crossfade between consecutive files in seconds
+    #[arg(long, default_value_t = 0.0)]
+    pub crossfade: f64,
+
+    /// Output sample rate, the one of the first file when not given
+    #[arg(long)]
+    pub sr: Option<u32>,
+
+    /// Where to write the start and end of each file, next to the output as CSV when not given
+    #[arg(long)]
+    pub cues: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct AudioMixArgs {
+    /// Tracks to overlay onto the first one, which sets the length and spec of the mix
+    #[arg(required = true, num_args = 2..)]
+    pub inputs: Vec<String>,
+
+    /// Output WAV file
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Comma-separated gain in dB for each track
+    #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+    pub gains: Vec<f64>,
+
+    /// Scale the other tracks to this signal-to-noise ratio in dB with respect to the first
+    #[arg(long, allow_hyphen_values = true)]
+    pub snr: Option<f64>,
+
+    /// Repeat tracks shorter than the first one instead of padding them with silence
+    #[arg(long = "loop", action = clap::ArgAction::SetTrue)]
+    pub loop_tracks: bool,
+
+    /// Flag to enable overwriting of input file
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub overwrite: bool,
+}
+
+/// What `audio dedup` does with duplicates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DedupAction {
+    /// Only report clusters of duplicates
+    Report,
+    /// Replace duplicates that are byte-identical to the kept file with hardlinks to it
+    Hardlink,
+    /// Move duplicates to `--destination`
+    Move,
+}
+
+#[derive(Debug, Parser)]
+pub struct AudioDedupArgs {
+    /// Target directory
+    #[arg(required = true)]
+    pub target: String,
+
+    /// Fingerprint similarity between 0 and 1 above which files are duplicates
+    #[arg(long, default_value_t = 0.85)]
+    pub threshold: f64,
+
+    /// Largest shift in seconds between matching files, e.g. when one was trimmed
+    #[arg(long, default_value_t = 5.0)]
+    pub max_offset: f64,
+
+    /// What to do with duplicates, the longest file of each cluster is kept
+    #[arg(long, value_enum, default_value_t = DedupAction::Report)]
+    pub action: DedupAction,
+
+    /// Directory duplicates are moved to, keeping their relative path
+    #[arg(long)]
+    pub destination: Option<String>,
+
+    /// Write the clusters to a CSV or Parquet file
+    #[arg(long)]
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct AudioCutArgs {
+    /// CSV or Parquet manifest with one segment per row
+    #[arg(required = true)]
+    pub manifest: String,
+
+    /// Output directory, with a subdirectory per label
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Directory relative file paths of the manifest are taken from
+    #[arg(long)]
+    pub root: Option<String>,
+
+    /// Column with the path of the source file
+    #[arg(long, default_value = "file")]
+    pub file_column: String,
+
+    /// Column with the start of the segment in seconds
+    #[arg(long, default_value = "start")]
+    pub start_column: String,
+
+    /// Column with the end of the segment in seconds
+    #[arg(long, default_value = "end")]
+    pub end_column: String,
+
+    /// Column with the label of the segment, used when present
+    #[arg(long, default_value = "label")]
+    pub label_column: String,
+
+    /// Column with the output name of the segment, used when present
+    #[arg(long, default_value = "name")]
+    pub name_column: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct AudioVadArgs {
+    /// Target directory or file
+    #[arg(required = true)]
+    pub target: String,
+
+    /// Write the speech segments (file, start, end) to a CSV or Parquet file
+    #[arg(long)]
+    pub output: Option<String>,
+
+    /// Duration of the analysis frames in seconds
+    #[arg(long, default_value_t = 0.03)]
+    pub frame: f64,
+
+    /// Level in dB above the noise floor of a file over which frames are speech
+    #[arg(long, default_value_t = 10.0)]
+    pub threshold: f64,
+
+    /// Level in dBFS under which frames are never speech
+    #[arg(long, default_value_t = -60.0, allow_hyphen_values = true)]
+    pub silence_threshold: f64,
+
+    /// Zero-crossing rate between 0 and 1 above which frames are noise rather than speech
+    #[arg(long, default_value_t = 0.4)]
+    pub max_zcr: f64,
+
+    /// Shortest speech segment kept, in seconds
+    #[arg(long, default_value_t = 0.25)]
+    pub min_speech: f64,
+
+    /// Shortest pause splitting two segments, in seconds
+    #[arg(long, default_value_t = 0.3)]
+    pub min_silence: f64,
+
+    /// Padding added around each segment, in seconds
+    #[arg(long, default_value_t = 0.1)]
+    pub padding: f64,
+}
+
+#[derive(Debug, Parser)]
+pub struct VideoExtractAudioArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output WAV file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Resample to this sample rate, keeps the original when not given
+    #[arg(long)]
+    pub sr: Option<u32>,
+
+    /// Number of output channels, either the original count, 1 to downmix or any count from mono
+    #[arg(long)]
+    pub channels: Option<usize>,
+
+    /// Bits per sample of the WAV files (8, 16, 24 or 32)
+    #[arg(long, default_value_t = 16)]
+    pub bits: u16,
+
+    /// Sample format of the WAV files
+    #[arg(long, value_enum, default_value_t = WavSampleFormat::Int)]
+    pub format: WavSampleFormat,
+}
+
+/// How `video trim` and `video split` cut clips
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VideoCutMode {
+    /// Copy packets without re-encoding, cuts start at keyframes
+    Copy,
+    /// Re-encode the video with H.264 for frame-accurate cuts
+    Reencode,
+}
+
+#[derive(Debug, Parser)]
+pub struct VideoTrimArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Target length in seconds
+    #[arg(required = true)]
+    pub length: f64,
+
+    /// Output file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Start offset in seconds
+    #[arg(default_value_t = 0.0)]
+    pub offset: f64,
+
+    /// How clips are cut
+    #[arg(long, value_enum, default_value_t = VideoCutMode::Copy)]
+    pub mode: VideoCutMode,
+}
+
+#[derive(Debug, Parser)]
+pub struct VideoSplitArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Chunk duration in seconds
+    #[arg(required = true)]
+    pub chunk_duration: f64,
+
+    /// Output directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// How clips are cut
+    #[arg(long, value_enum, default_value_t = VideoCutMode::Copy)]
+    pub mode: VideoCutMode,
+
+    /// Delete original file
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub delete_original: bool,
+}
+
+/// Codecs of `video convert`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VideoCodec {
+    /// H.264 with libx264
+    H264,
+    /// VP9 with libvpx
+    Vp9,
+    /// Motion JPEG, every frame is a keyframe
+    Mjpeg,
+    /// Lossless FFV1
+    Ffv1,
+}
+
+#[derive(Debug, Parser)]
+pub struct VideoConvertArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Target codec
+    #[arg(long, value_enum, default_value_t = VideoCodec::H264)]
+    pub codec: VideoCodec,
+
+    /// Target width, keeping the aspect ratio if no height is given
+    #[arg(long)]
+    pub width: Option<u32>,
+
+    /// Target height, keeping the aspect ratio if no width is given
+    #[arg(long)]
+    pub height: Option<u32>,
+
+    /// Target frame rate, keeps the average frame rate of the input when not given
+    #[arg(long)]
+    pub fps: Option<f64>,
+
+    /// Target pixel format as named by ffmpeg, e.g. `yuv420p`, defaults to one the codec supports
+    #[arg(long)]
+    pub pixel_format: Option<String>,
+
+    /// Constant rate factor of H.264 and VP9, lower is better quality
+    #[arg(long)]
+    pub crf: Option<u32>,
+
+    /// Container of the output files in a directory (ts, mp4, mkv or mov), keeps the input one
+    #[arg(long)]
+    pub extension: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct VideoFromFramesArgs {
+    /// Input directory of numbered images
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output video file
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Frame rate of the video
+    #[arg(long, default_value_t = 25.0)]
+    pub fps: f64,
+
+    /// File name pattern with a `%d` or `%05d` placeholder for the frame number, images are
+    /// sorted naturally by name when not given
+    #[arg(long)]
+    pub pattern: Option<String>,
+
+    /// Width of the video, keeping the aspect ratio of the first image if no height is given
+    #[arg(long)]
+    pub width: Option<u32>,
+
+    /// Height of the video, keeping the aspect ratio of the first image if no width is given
+    #[arg(long)]
+    pub height: Option<u32>,
+
+    /// Codec of the video
+    #[arg(long, value_enum, default_value_t = VideoCodec::H264)]
+    pub codec: VideoCodec,
+
+    /// Constant rate factor of H.264 and VP9, lower is better quality
+    #[arg(long)]
+    pub crf: Option<u32>,
+}
+
+/// Frame difference used by `video scenes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SceneMethod {
+    /// Distance between color histograms, robust to motion within a shot
+    Histogram,
+    /// Mean absolute pixel difference, sensitive to motion
+    Pixel,
+}
+
+#[derive(Debug, Parser)]
+pub struct VideoScenesArgs {
+    /// Target directory or file
+    #[arg(required = true)]
+    pub target: String,
+
+    /// Write the shots to a CSV or Parquet file with columns file, shot, start and end
+    #[arg(long)]
+    pub output: Option<String>,
+
+    /// Frame difference used to detect cuts
+    #[arg(long, value_enum, default_value_t = SceneMethod::Histogram)]
+    pub method: SceneMethod,
+
+    /// Difference between 0 and 1 above which frames are cut, 0.3 for histograms and 0.15
+    /// for pixels when not given
+    #[arg(long)]
+    pub threshold: Option<f64>,
+
+    /// Minimum shot duration in seconds
+    #[arg(long, default_value_t = 0.5)]
+    pub min_shot: f64,
+
+    /// Write the middle frame of each shot to this directory
+    #[arg(long)]
+    pub keyframes: Option<String>,
+
+    /// Image format of the keyframes
+    #[arg(long, value_enum, default_value_t = FrameFormat::Jpeg)]
+    pub format: FrameFormat,
+}
+
+#[derive(Debug, Parser)]
+pub struct VideoCheckArgs {
+    /// Target directory or file
+    #[arg(required = true)]
+    pub target: String,
+
+    /// Largest acceptable difference in seconds between announced and decoded durations
+    #[arg(long, default_value_t = 0.5)]
+    pub duration_tolerance: f64,
+
+    /// Write the per-file report to a CSV or Parquet file
+    #[arg(long)]
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct VideoThumbnailsArgs {
+    /// Input video file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output image file, or directory with one image per video
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Number of evenly spaced frames per video
+    #[arg(short = 'n', long, default_value_t = 9)]
+    pub count: usize,
+
+    /// Number of columns of the grid, square by default
+    #[arg(long)]
+    pub columns: Option<usize>,
+
+    /// Width of each thumbnail in pixels
+    #[arg(long, default_value_t = 320)]
+    pub width: u32,
+
+    /// Do not write the timestamp of each frame
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub no_timestamps: bool,
+
+    /// Image format when writing to a directory
+    #[arg(long, value_enum, default_value_t = FrameFormat::Jpeg)]
+    pub format: FrameFormat,
+
+    /// JPEG quality between 1 and 100
+    #[arg(long, default_value_t = 90)]
+    pub quality: u8,
+}
+
+/// File format written by `image convert`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImageFileFormat {
+    Png,
+    Jpeg,
+    /// Lossless WebP
+    Webp,
+    Bmp,
+    Tiff,
+    Gif,
+}
+
+/// Color type of images written by `image convert`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImageColor {
+    Rgb,
+    Rgba,
+    Gray,
+    GrayAlpha,
+    /// RGB with 16 bits per channel
+    Rgb16,
+    /// RGBA with 16 bits per channel
+    Rgba16,
+    /// Grayscale with 16 bits per channel
+    Gray16,
+    /// Grayscale and alpha with 16 bits per channel
+    GrayAlpha16,
+}
+
+/// Compression level of PNG files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PngCompression {
+    Fast,
+    Default,
+    Best,
+}
+
+#[derive(Debug, Parser)]
+pub struct ImageConvertArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Target format, taken from the extension of an output file when not given
+    #[arg(long, value_enum)]
+    pub format: Option<ImageFileFormat>,
+
+    /// JPEG quality between 1 and 100
+    #[arg(long, default_value_t = 90)]
+    pub quality: u8,
+
+    /// PNG compression level
+    #[arg(long, value_enum, default_value_t = PngCompression::Default)]
+    pub compression: PngCompression,
+
+    /// Target color type, the closest one the format can store when not given
+    #[arg(long, value_enum)]
+    pub color: Option<ImageColor>,
+
+    /// Flag to enable overwriting of input file
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub overwrite: bool,
+
+    /// Rotate and flip images according to their EXIF orientation first
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub auto_orient: bool,
+
+    /// EXIF metadata of the outputs, only kept in JPEG and PNG files
+    #[arg(long, value_enum, default_value_t = MetadataMode::Strip)]
+    pub metadata: MetadataMode,
+}
+
+#[derive(Debug, Parser)]
+pub struct ImageAutoOrientArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// EXIF metadata of the outputs, only kept in JPEG and PNG files
+    #[arg(long, value_enum, default_value_t = MetadataMode::Strip)]
+    pub metadata: MetadataMode,
+
+    /// Flag to enable overwriting of input file
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub overwrite: bool,
+}
+
+/// Where `image crop` takes the crop from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CropMode {
+    /// Box starting at `x` and `y`
+    Box,
+    /// Centre of the image
+    Center,
+    /// Random position, seeded per file
+    Random,
+}
+
+#[derive(Debug, Parser)]
+pub struct ImageCropArgs {
+    /// Input file or directory, relative paths of the manifest are taken from it
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output file or directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Where the crop is taken from
+    #[arg(long, value_enum, default_value_t = CropMode::Center)]
+    pub mode: CropMode,
+
+    /// Left edge of the box in pixels
+    #[arg(long, default_value_t = 0)]
+    pub x: u32,
+
+    /// Top edge of the box in pixels
+    #[arg(long, default_value_t = 0)]
+    pub y: u32,
+
+    /// Width of the crop, the rest of the image or from the aspect ratio when not given
+    #[arg(long)]
+    pub width: Option<u32>,
+
+    /// Height of the crop, the rest of the image or from the aspect ratio when not given
+    #[arg(long)]
+    pub height: Option<u32>,
+
+    /// Aspect ratio of the crop as `W:H`, the largest crop of that ratio when no side is given
+    #[arg(long)]
+    pub aspect: Option<String>,
+
+    /// Seed of random crops
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// CSV or Parquet manifest with one box per row, replacing `--mode`
+    #[arg(long)]
+    pub manifest: Option<String>,
+
+    /// Manifest column with the path of the source file
+    #[arg(long, default_value = "file")]
+    pub file_column: String,
+
+    /// Manifest column with the left edge of the box
+    #[arg(long, default_value = "x")]
+    pub x_column: String,
+
+    /// Manifest column with the top edge of the box
+    #[arg(long, default_value = "y")]
+    pub y_column: String,
+
+    /// Manifest column with the width of the box
+    #[arg(long, default_value = "width")]
+    pub width_column: String,
+
+    /// Manifest column with the height of the box
+    #[arg(long, default_value = "height")]
+    pub height_column: String,
+
+    /// Manifest column with the label of the box, used when present
+    #[arg(long, default_value = "label")]
+    pub label_column: String,
+
+    /// Manifest column with the output name of the box, used when present
+    #[arg(long, default_value = "name")]
+    pub name_column: String,
+
+    /// Flag to enable overwriting of input file
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub overwrite: bool,
+
+    /// Rotate and flip images according to their EXIF orientation first
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub auto_orient: bool,
+
+    /// EXIF metadata of the outputs, only kept in JPEG and PNG files
+    #[arg(long, value_enum, default_value_t = MetadataMode::Strip)]
+    pub metadata: MetadataMode,
+}
+
 #[derive(Debug, Parser)]
 pub struct VideoSummaryArgs {
     /// Target directory or file
     #[arg(required = true)]
     pub target: String,
+
+    /// Print only a CSV table with one row per file, its container, stream and codec properties
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub per_file: bool,
+
+    /// Write the per-file table to a CSV or Parquet file
+    #[arg(long)]
+    pub output: Option<String>,
+}
+
+/// Image format of extracted video frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FrameFormat {
+    Png,
+    Jpeg,
+}
+
+#[derive(Debug, Parser)]
+pub struct VideoFramesArgs {
+    /// Input file or directory
+    #[arg(required = true)]
+    pub input: String,
+
+    /// Output directory
+    #[arg(required = true)]
+    pub output: String,
+
+    /// Extract every Nth frame
+    #[arg(long, conflicts_with_all = ["fps", "keyframes", "timestamps"])]
+    pub every: Option<usize>,
+
+    /// Extract frames at this rate per second
+    #[arg(long, conflicts_with_all = ["keyframes", "timestamps"])]
+    pub fps: Option<f64>,
+
+    /// Extract keyframes only
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "timestamps")]
+    pub keyframes: bool,
+
+    /// Comma-separated times in seconds, each extracting the first frame at or after it
+    #[arg(long, value_delimiter = ',')]
+    pub timestamps: Vec<f64>,
+
+    /// Image format of the frames
+    #[arg(long, value_enum, default_value_t = FrameFormat::Png)]
+    pub format: FrameFormat,
+
+    /// JPEG quality between 1 and 100
+    #[arg(long, default_value_t = 90)]
+    pub quality: u8,
+
+    /// Resize frames to this width, keeping the aspect ratio if no height is given
+    #[arg(long)]
+    pub width: Option<u32>,
+
+    /// Resize frames to this height, keeping the aspect ratio if no width is given
+    #[arg(long)]
+    pub height: Option<u32>,
 }
 
 #[derive(Debug, Args)]
@@ -1,9 +1,13 @@
 use clap::{Args, Parser, Subcommand};
 use datatool::{
-    AudioResampleArgs, AudioSplitArgs, AudioSummaryArgs, AudioTrimArgs, CountArgs, ImageResizeArgs,
-    ImageSummaryArgs, ImageTessellateArgs, ImageToLandscapeArgs, ImageToPortraitArgs,
-    PointcloudConvertArgs, PointcloudSummaryArgs, TableSchemaArgs, TableToCsvArgs,
-    TableToParquetArgs, VideoSummaryArgs,
+    AudioChannelsArgs, AudioCheckArgs, AudioConcatArgs, AudioConvertArgs, AudioCutArgs,
+    AudioDedupArgs, AudioMixArgs, AudioNormalizeArgs, AudioResampleArgs, AudioSpectrogramArgs,
+    AudioSplitArgs, AudioSummaryArgs, AudioTransformArgs, AudioTrimArgs, AudioVadArgs, CountArgs,
+    ImageAutoOrientArgs, ImageConvertArgs, ImageCropArgs, ImageResizeArgs, ImageSummaryArgs,
+    ImageTessellateArgs, ImageToLandscapeArgs, ImageToPortraitArgs, PointcloudConvertArgs,
+    PointcloudSummaryArgs, TableSchemaArgs, TableToCsvArgs, TableToParquetArgs, VideoCheckArgs,
+    VideoConvertArgs, VideoExtractAudioArgs, VideoFramesArgs, VideoFromFramesArgs, VideoScenesArgs,
+    VideoSplitArgs, VideoSummaryArgs, VideoThumbnailsArgs, VideoTrimArgs,
 };
 
 /// Rust implementation of bash commands
@@ -47,6 +51,28 @@ enum AudioSubCommand {
     Resample(AudioResampleArgs),
     /// Trim audio files to a specified length.
     Trim(AudioTrimArgs),
+    /// Normalize audio files to a target loudness or peak level.
+    Normalize(AudioNormalizeArgs),
+    /// Downmix, split or remap the channels of audio files.
+    Channels(AudioChannelsArgs),
+    /// Convert the bit depth and sample format of audio files.
+    Convert(AudioConvertArgs),
+    /// Check audio files for clipping, DC offset, silence and corruption.
+    Check(AudioCheckArgs),
+    /// Compute STFT, mel spectrogram or MFCC features of audio files.
+    Spectrogram(AudioSpectrogramArgs),
+    /// Apply a chain of fades, padding, gain, speed, tempo and pitch changes to audio files.
+    Transform(AudioTransformArgs),
+    /// Join audio files into one, with optional crossfades.
+    Concat(AudioConcatArgs),
+    /// Overlay audio tracks with per-track gain or at a target SNR.
+    Mix(AudioMixArgs),
+    /// Find duplicate and near-duplicate audio files with acoustic fingerprints.
+    Dedup(AudioDedupArgs),
+    /// Extract segments listed in a CSV/Parquet manifest into WAV files organised by label.
+    Cut(AudioCutArgs),
+    /// Detect speech segments in audio files and report speech ratios.
+    Vad(AudioVadArgs),
 }
 
 // ----------- IMAGE -----------
@@ -66,6 +92,12 @@ enum ImageSubCommand {
     Tessellate(ImageTessellateArgs),
     ToLandscape(ImageToLandscapeArgs),
     ToPortrait(ImageToPortraitArgs),
+    /// Convert images to another format, color type or encoder setting.
+    Convert(ImageConvertArgs),
+    /// Rotate and flip images according to their EXIF orientation.
+    AutoOrient(ImageAutoOrientArgs),
+    /// Crop a box, the centre or a random region of images, or boxes listed in a manifest.
+    Crop(Box<ImageCropArgs>),
 }
 
 // ----------- VIDEO -----------
@@ -77,8 +109,26 @@ struct VideoCommand {
 
 #[derive(Debug, Subcommand)]
 enum VideoSubCommand {
+    /// Fully decode video files and report corrupt or truncated ones.
+    Check(VideoCheckArgs),
+    /// Re-encode video files to another codec, resolution or frame rate.
+    Convert(VideoConvertArgs),
+    /// Extract the audio track of video files as WAV.
+    ExtractAudio(VideoExtractAudioArgs),
+    /// Extract frames from video files as images.
+    Frames(VideoFramesArgs),
+    /// Encode a directory of numbered images into a video.
+    FromFrames(VideoFromFramesArgs),
+    /// Detect shot boundaries in video files.
+    Scenes(VideoScenesArgs),
+    /// Split video files into chunks of specified duration.
+    Split(VideoSplitArgs),
     /// Get metadata about video files.
     Summary(VideoSummaryArgs),
+    /// Compose evenly spaced frames of video files into grid images.
+    Thumbnails(VideoThumbnailsArgs),
+    /// Trim video files to a specified length.
+    Trim(VideoTrimArgs),
 }
 
 // ----------- FILE -----------
@@ -133,6 +183,19 @@ fn main() {
             AudioSubCommand::Split(args) => datatool::commands::audio::split::execute(args),
             AudioSubCommand::Resample(args) => datatool::commands::audio::resample::execute(args),
             AudioSubCommand::Trim(args) => datatool::commands::audio::trim::execute(args),
+            AudioSubCommand::Normalize(args) => datatool::commands::audio::normalize::execute(args),
+            AudioSubCommand::Channels(args) => datatool::commands::audio::channels::execute(args),
+            AudioSubCommand::Convert(args) => datatool::commands::audio::convert::execute(args),
+            AudioSubCommand::Check(args) => datatool::commands::audio::check::execute(args),
+            AudioSubCommand::Spectrogram(args) => {
+                datatool::commands::audio::spectrogram::execute(args)
+            }
+            AudioSubCommand::Transform(args) => datatool::commands::audio::transform::execute(args),
+            AudioSubCommand::Concat(args) => datatool::commands::audio::concat::execute(args),
+            AudioSubCommand::Mix(args) => datatool::commands::audio::mix::execute(args),
+            AudioSubCommand::Dedup(args) => datatool::commands::audio::dedup::execute(args),
+            AudioSubCommand::Cut(args) => datatool::commands::audio::cut::execute(args),
+            AudioSubCommand::Vad(args) => datatool::commands::audio::vad::execute(args),
         },
         Command::Image(image_command) => match image_command.command {
             ImageSubCommand::Summary(args) => datatool::commands::image::summary::execute(args),
@@ -146,9 +209,29 @@ fn main() {
             ImageSubCommand::ToPortrait(args) => {
                 datatool::commands::image::to_portrait::execute(args)
             }
+            ImageSubCommand::Convert(args) => datatool::commands::image::convert::execute(args),
+            ImageSubCommand::AutoOrient(args) => {
+                datatool::commands::image::auto_orient::execute(args)
+            }
+            ImageSubCommand::Crop(args) => datatool::commands::image::crop::execute(*args),
         },
         Command::Video(video_command) => match video_command.command {
+            VideoSubCommand::Check(args) => datatool::commands::video::check::execute(args),
+            VideoSubCommand::Convert(args) => datatool::commands::video::convert::execute(args),
+            VideoSubCommand::ExtractAudio(args) => {
+                datatool::commands::video::extract_audio::execute(args)
+            }
+            VideoSubCommand::Frames(args) => datatool::commands::video::frames::execute(args),
+            VideoSubCommand::FromFrames(args) => {
+                datatool::commands::video::from_frames::execute(args)
+            }
+            VideoSubCommand::Scenes(args) => datatool::commands::video::scenes::execute(args),
+            VideoSubCommand::Split(args) => datatool::commands::video::split::execute(args),
             VideoSubCommand::Summary(args) => datatool::commands::video::summary::execute(args),
+            VideoSubCommand::Thumbnails(args) => {
+                datatool::commands::video::thumbnails::execute(args)
+            }
+            VideoSubCommand::Trim(args) => datatool::commands::video::trim::execute(args),
         },
         Command::File(file_command) => match file_command.command {
             FileSubCommand::Count(args) => datatool::commands::file::count::execute(args),
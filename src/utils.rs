@@ -1,7 +1,8 @@
 use anyhow::{Context, Result};
 use polars::prelude::*;
+use std::collections::HashMap;
 use std::io;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 // Check if file with given path has one of the desired extensions
 pub fn file_has_right_extension(path: &Path, extensions: &[&str]) -> Result<(), io::Error> {
@@ -74,3 +75,109 @@ pub fn read_table(path: &Path) -> Result<LazyFrame> {
         _ => Err(anyhow::Error::msg("Unsupported file format")),
     }
 }
+
+// Write table, the format is chosen from the extension
+pub fn write_table(df: &mut DataFrame, path: &Path) -> Result<()> {
+    // Extract extension
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .ok_or_else(|| anyhow::Error::msg("Failed to extract file extension"))?;
+
+    // Ensure the parent directory exists
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+    }
+
+    // Open output file
+    let mut file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create file: {:?}", path))?;
+
+    match extension.as_str() {
+        "parquet" => ParquetWriter::new(&mut file)
+            .finish(df)
+            .map(|_| ())
+            .with_context(|| format!("Failed to write parquet file: {:?}", path)),
+        "csv" => CsvWriter::new(&mut file)
+            .finish(df)
+            .with_context(|| format!("Failed to write CSV file: {:?}", path)),
+        _ => Err(anyhow::Error::msg("Unsupported file format")),
+    }
+}
+
+// Stable seed derived from a path, given relative to the input so that it does not depend
+// on where the data lives while same-named files in different directories differ
+pub fn seed_from_path(path: &Path) -> u64 {
+    // FNV-1a hash
+    path.to_string_lossy()
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+// Values of a column as strings, None if the table has no such column
+pub fn string_column(df: &DataFrame, name: &str) -> Result<Option<Vec<Option<String>>>> {
+    match df.column(name) {
+        Ok(column) => {
+            let column = column
+                .cast(&DataType::String)
+                .with_context(|| format!("Failed to read column {:?} as strings", name))?;
+            let values = column
+                .str()?
+                .into_iter()
+                .map(|value| value.map(str::to_string))
+                .collect();
+            Ok(Some(values))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+// Values of a required column as floats
+pub fn float_column(df: &DataFrame, name: &str) -> Result<Vec<Option<f64>>> {
+    let column = df
+        .column(name)
+        .with_context(|| format!("Missing column: {:?}", name))?
+        .cast(&DataType::Float64)
+        .with_context(|| format!("Failed to read column {:?} as numbers", name))?;
+    Ok(column.f64()?.into_iter().collect())
+}
+
+// Keep names and labels inside the output directory
+pub fn sanitize(name: &str) -> String {
+    match name {
+        "" | "." | ".." => "_".to_string(),
+        _ => name.replace(['/', '\\'], "_"),
+    }
+}
+
+// Directory of a file relative to `root`, or none for files outside of it
+pub fn relative_directory<'a>(file: &'a Path, root: &Path) -> &'a Path {
+    match file.strip_prefix(root) {
+        Ok(relative_path)
+            if relative_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_))) =>
+        {
+            relative_path.parent().unwrap_or(Path::new(""))
+        }
+        _ => Path::new(""),
+    }
+}
+
+// Error out when two inputs would be written to the same output
+pub fn check_distinct_outputs(files: &[(PathBuf, PathBuf)]) -> Result<()> {
+    let mut inputs: HashMap<&Path, &Path> = HashMap::new();
+    for (input, output) in files {
+        if let Some(other) = inputs.insert(output, input) {
+            return Err(anyhow::Error::msg(format!(
+                "{:?} and {:?} would both be written to {:?}",
+                other, input, output
+            )));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::audio;
+use datatool::{AudioChannelsArgs, ChannelMode};
+use hound::WavReader;
+use std::fs;
+
+#[test]
+fn test_audio_channels_mono_resample_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 2.0, 44100, 2, 16)?;
+
+    // Define args to downmix and resample in one go
+    let args = AudioChannelsArgs {
+        input: input_path.to_string_lossy().to_string(),
+        mode: ChannelMode::Mono,
+        output: output_path.to_string_lossy().to_string(),
+        channel: None,
+        map: vec![],
+        sr: Some(16000),
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::channels::execute(args)?;
+
+    // Verify output file is mono at the new sample rate
+    let reader = WavReader::open(output_path)?;
+    assert_eq!(reader.spec().channels, 1);
+    assert_eq!(reader.spec().sample_rate, 16000);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_channels_split_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let output_dir = test_dir.join("output");
+    fs::create_dir(&input_dir)?;
+
+    // Create test files in nested structure
+    let nested_dir = input_dir.join("nested");
+    fs::create_dir(&nested_dir)?;
+    create_test_wav(&input_dir.join("test1.wav"), 1.0, 44100, 2, 16)?;
+    create_test_wav(&nested_dir.join("test2.wav"), 1.0, 44100, 2, 16)?;
+
+    // Define args
+    let args = AudioChannelsArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        mode: ChannelMode::Split,
+        output: output_dir.to_string_lossy().to_string(),
+        channel: None,
+        map: vec![],
+        sr: None,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::channels::execute(args)?;
+
+    // Verify one mono file per channel
+    for path in [
+        output_dir.join("test1@ch0.wav"),
+        output_dir.join("test1@ch1.wav"),
+        output_dir.join("nested/test2@ch0.wav"),
+        output_dir.join("nested/test2@ch1.wav"),
+    ] {
+        let reader = WavReader::open(path)?;
+        assert_eq!(reader.spec().channels, 1);
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_channels_remap_upmix_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 1.0, 44100, 1, 16)?;
+
+    // Duplicate the mono channel into stereo
+    let args = AudioChannelsArgs {
+        input: input_path.to_string_lossy().to_string(),
+        mode: ChannelMode::Remap,
+        output: output_path.to_string_lossy().to_string(),
+        channel: None,
+        map: vec![0, 0],
+        sr: None,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::channels::execute(args)?;
+
+    // Verify output file is stereo with the same length
+    let reader = WavReader::open(output_path)?;
+    assert_eq!(reader.spec().channels, 2);
+    assert_eq!(reader.duration(), 44100);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_channels_invalid_channel_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 1.0, 44100, 2, 16)?;
+
+    // Ask for a channel that does not exist
+    let args = AudioChannelsArgs {
+        input: input_path.to_string_lossy().to_string(),
+        mode: ChannelMode::Mono,
+        output: output_path.to_string_lossy().to_string(),
+        channel: Some(2),
+        map: vec![],
+        sr: None,
+        overwrite: false,
+    };
+
+    // Execute command and expect error
+    let result = audio::channels::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
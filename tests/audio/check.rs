@@ -0,0 +1,198 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::audio;
+use datatool::utils::read_table;
+use datatool::AudioCheckArgs;
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::fs;
+use std::path::Path;
+
+// Write a mono 16 bits file with the given samples
+fn write_wav(path: &Path, samples: &[i16]) -> Result<()> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(path, spec)?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+#[test]
+fn test_audio_check_clean_file_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    create_test_wav(&input_path, 1.0, 44100, 2, 16)?;
+
+    // Define args
+    let args = AudioCheckArgs {
+        target: input_path.to_string_lossy().to_string(),
+        clip_run: 3,
+        dc_threshold: 0.01,
+        silence_threshold: -60.0,
+        silence_ratio: 0.9,
+        duration_tolerance: 0.1,
+        output: None,
+    };
+
+    // Execute command
+    audio::check::execute(args)?;
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_check_silent_and_clipped_files_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create a silent file, a clipped file and a clean one
+    write_wav(&test_dir.join("silent.wav"), &[0; 16000])?;
+    let clipped: Vec<i16> = (0..16000)
+        .map(|i| {
+            if (i / 20) % 2 == 0 {
+                i16::MAX
+            } else {
+                i16::MIN
+            }
+        })
+        .collect();
+    write_wav(&test_dir.join("clipped.wav"), &clipped)?;
+    create_test_wav(&test_dir.join("clean.wav"), 1.0, 16000, 1, 16)?;
+
+    // Define args
+    let table_path = test_dir.join("report.csv");
+    let args = AudioCheckArgs {
+        target: test_dir.to_string_lossy().to_string(),
+        clip_run: 3,
+        dc_threshold: 0.01,
+        silence_threshold: -60.0,
+        silence_ratio: 0.9,
+        duration_tolerance: 0.1,
+        output: Some(table_path.to_string_lossy().to_string()),
+    };
+
+    // Execute command
+    let result = audio::check::execute(args);
+    assert!(result.is_err());
+
+    // The report covers all files, issues included
+    let df = read_table(&table_path)?.collect()?;
+    assert_eq!(df.height(), 3);
+    let issues: Vec<String> = df
+        .column("issues")?
+        .str()?
+        .into_iter()
+        .map(|issue| issue.unwrap_or_default().to_string())
+        .collect();
+    assert_eq!(issues[0], "");
+    assert!(issues[1].starts_with("clipping"));
+    assert_eq!(issues[2], "silent");
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_check_truncated_file_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create a file and cut its data short while keeping the header
+    let input_path = test_dir.join("input.wav");
+    create_test_wav(&input_path, 1.0, 44100, 1, 16)?;
+    let bytes = fs::read(&input_path)?;
+    fs::write(&input_path, &bytes[..bytes.len() / 2 + 1])?;
+
+    // Define args
+    let args = AudioCheckArgs {
+        target: input_path.to_string_lossy().to_string(),
+        clip_run: 3,
+        dc_threshold: 0.01,
+        silence_threshold: -60.0,
+        silence_ratio: 0.9,
+        duration_tolerance: 0.1,
+        output: None,
+    };
+
+    // Execute command
+    let result = audio::check::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_check_nonexistent_target_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Define args
+    let args = AudioCheckArgs {
+        target: test_dir
+            .join("nonexistent.wav")
+            .to_string_lossy()
+            .to_string(),
+        clip_run: 3,
+        dc_threshold: 0.01,
+        silence_threshold: -60.0,
+        silence_ratio: 0.9,
+        duration_tolerance: 0.1,
+        output: None,
+    };
+
+    // Execute command
+    let result = audio::check::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_check_zero_clip_run_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.wav");
+    create_test_wav(&input_path, 1.0, 16000, 1, 16)?;
+
+    // Define args
+    let args = AudioCheckArgs {
+        target: input_path.to_string_lossy().to_string(),
+        clip_run: 0,
+        dc_threshold: 0.01,
+        silence_threshold: -60.0,
+        silence_ratio: 0.9,
+        duration_tolerance: 0.1,
+        output: None,
+    };
+
+    // Execute command
+    let result = audio::check::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
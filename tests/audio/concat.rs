@@ -0,0 +1,132 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::audio;
+use datatool::commands::audio::audio_utils::read_wav;
+use datatool::utils::read_table;
+use datatool::AudioConcatArgs;
+use std::fs;
+
+#[test]
+fn test_audio_concat_directory_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files, with a different sample rate and channel count for the second one
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(&input_dir)?;
+    create_test_wav(&input_dir.join("a.wav"), 1.0, 16000, 2, 16)?;
+    create_test_wav(&input_dir.join("b.wav"), 0.5, 32000, 1, 16)?;
+    let output_path = test_dir.join("joined.wav");
+
+    // Define args
+    let args = AudioConcatArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        crossfade: 0.0,
+        sr: None,
+        cues: None,
+    };
+
+    // Execute command
+    audio::concat::execute(args)?;
+
+    // Verify output
+    let (spec, samples) = read_wav(&output_path)?;
+    assert_eq!(spec.sample_rate, 16000);
+    assert_eq!(spec.channels, 2);
+    assert_eq!(samples[0].len(), 24000);
+
+    // Verify the cue list
+    let df = read_table(&test_dir.join("joined.csv"))?.collect()?;
+    assert_eq!(df.height(), 2);
+    let starts: Vec<f64> = df.column("start")?.f64()?.into_no_null_iter().collect();
+    let ends: Vec<f64> = df.column("end")?.f64()?.into_no_null_iter().collect();
+    assert_eq!(starts, vec![0.0, 1.0]);
+    assert_eq!(ends, vec![1.0, 1.5]);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_concat_manifest_crossfade_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files and a manifest listing them in reverse order
+    let first_path = test_dir.join("a.wav");
+    let second_path = test_dir.join("b.wav");
+    create_test_wav(&first_path, 1.0, 16000, 1, 16)?;
+    create_test_wav(&second_path, 1.0, 16000, 1, 16)?;
+    let manifest_path = test_dir.join("manifest.csv");
+    fs::write(
+        &manifest_path,
+        format!(
+            "path\n{}\n{}\n",
+            second_path.to_string_lossy(),
+            first_path.to_string_lossy()
+        ),
+    )?;
+    let output_path = test_dir.join("joined.wav");
+    let cues_path = test_dir.join("cues.parquet");
+
+    // Define args
+    let args = AudioConcatArgs {
+        input: manifest_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        crossfade: 0.25,
+        sr: Some(8000),
+        cues: Some(cues_path.to_string_lossy().to_string()),
+    };
+
+    // Execute command
+    audio::concat::execute(args)?;
+
+    // The crossfade overlaps the files
+    let (spec, samples) = read_wav(&output_path)?;
+    assert_eq!(spec.sample_rate, 8000);
+    assert_eq!(samples[0].len(), 14000);
+
+    // Verify the cue list follows the manifest order
+    let df = read_table(&cues_path)?.collect()?;
+    let paths: Vec<&str> = df.column("path")?.str()?.into_no_null_iter().collect();
+    assert!(paths[0].ends_with("b.wav"));
+    let starts: Vec<f64> = df.column("start")?.f64()?.into_no_null_iter().collect();
+    assert_eq!(starts, vec![0.0, 0.75]);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_concat_output_directory_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(&input_dir)?;
+    create_test_wav(&input_dir.join("a.wav"), 1.0, 16000, 1, 16)?;
+
+    // Define args
+    let args = AudioConcatArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: test_dir.join("output").to_string_lossy().to_string(),
+        crossfade: 0.0,
+        sr: None,
+        cues: None,
+    };
+
+    // Execute command
+    let result = audio::concat::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
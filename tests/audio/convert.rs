@@ -0,0 +1,214 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::audio;
+use datatool::{AudioConvertArgs, Dither, WavSampleFormat};
+use hound::{SampleFormat, WavReader};
+use std::fs;
+
+#[test]
+fn test_audio_convert_bit_depth_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 2.0, 44100, 2, 32)?;
+
+    // Define args to go down to 16 bits with noise shaping
+    let args = AudioConvertArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        bits: Some(16),
+        format: None,
+        dither: Dither::Tpdf,
+        noise_shaping: true,
+        seed: 0,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::convert::execute(args)?;
+
+    // Verify output spec
+    let reader = WavReader::open(output_path)?;
+    assert_eq!(reader.spec().bits_per_sample, 16);
+    assert_eq!(reader.spec().sample_format, SampleFormat::Int);
+    assert_eq!(reader.duration(), 88200);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_convert_float_round_trip_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let float_path = test_dir.join("float.wav");
+    let int_path = test_dir.join("int.wav");
+    create_test_wav(&input_path, 1.0, 44100, 1, 16)?;
+
+    // Convert to float
+    let args = AudioConvertArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: float_path.to_string_lossy().to_string(),
+        bits: None,
+        format: Some(WavSampleFormat::Float),
+        dither: Dither::Tpdf,
+        noise_shaping: false,
+        seed: 0,
+        overwrite: false,
+    };
+    audio::convert::execute(args)?;
+
+    let reader = WavReader::open(&float_path)?;
+    assert_eq!(reader.spec().bits_per_sample, 32);
+    assert_eq!(reader.spec().sample_format, SampleFormat::Float);
+
+    // And back to integers without dither
+    let args = AudioConvertArgs {
+        input: float_path.to_string_lossy().to_string(),
+        output: int_path.to_string_lossy().to_string(),
+        bits: Some(16),
+        format: Some(WavSampleFormat::Int),
+        dither: Dither::None,
+        noise_shaping: false,
+        seed: 0,
+        overwrite: false,
+    };
+    audio::convert::execute(args)?;
+
+    // Verify the round trip is lossless
+    let original: Vec<i16> = WavReader::open(&input_path)?
+        .samples::<i16>()
+        .collect::<Result<_, _>>()?;
+    let converted: Vec<i16> = WavReader::open(&int_path)?
+        .samples::<i16>()
+        .collect::<Result<_, _>>()?;
+    assert_eq!(original, converted);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_convert_directory_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let output_dir = test_dir.join("output");
+    fs::create_dir(&input_dir)?;
+
+    // Create test files in nested structure
+    let nested_dir = input_dir.join("nested");
+    fs::create_dir(&nested_dir)?;
+    create_test_wav(&input_dir.join("test1.wav"), 1.0, 44100, 1, 16)?;
+    create_test_wav(&nested_dir.join("test2.wav"), 1.0, 44100, 2, 32)?;
+
+    // Define args
+    let args = AudioConvertArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        bits: Some(8),
+        format: None,
+        dither: Dither::Tpdf,
+        noise_shaping: false,
+        seed: 42,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::convert::execute(args)?;
+
+    // Verify output files have the new bit depth
+    for path in [
+        output_dir.join("test1.wav"),
+        output_dir.join("nested/test2.wav"),
+    ] {
+        let reader = WavReader::open(path)?;
+        assert_eq!(reader.spec().bits_per_sample, 8);
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_convert_invalid_float_bits_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 1.0, 44100, 1, 16)?;
+
+    // Float samples can only be 32 bits
+    let args = AudioConvertArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        bits: Some(16),
+        format: Some(WavSampleFormat::Float),
+        dither: Dither::Tpdf,
+        noise_shaping: false,
+        seed: 0,
+        overwrite: false,
+    };
+
+    // Execute command and expect error
+    let result = audio::convert::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_convert_dither_seeded_per_path() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let output_dir = test_dir.join("output");
+    fs::create_dir_all(input_dir.join("a"))?;
+    fs::create_dir_all(input_dir.join("b"))?;
+
+    // Create identical files with the same name in different directories
+    create_test_wav(&input_dir.join("a").join("test.wav"), 1.0, 16000, 1, 16)?;
+    create_test_wav(&input_dir.join("b").join("test.wav"), 1.0, 16000, 1, 16)?;
+
+    // Define args to go down to 8 bits
+    let args = AudioConvertArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        bits: Some(8),
+        format: None,
+        dither: Dither::Tpdf,
+        noise_shaping: false,
+        seed: 0,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::convert::execute(args)?;
+
+    // Verify the dither noise differs between the two files
+    let first = fs::read(output_dir.join("a").join("test.wav"))?;
+    let second = fs::read(output_dir.join("b").join("test.wav"))?;
+    assert_ne!(first, second);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
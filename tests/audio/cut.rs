@@ -0,0 +1,229 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::audio;
+use datatool::commands::audio::audio_utils::read_wav;
+use datatool::AudioCutArgs;
+use std::fs;
+
+#[test]
+fn test_audio_cut_labels_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files and a manifest with relative paths and labels
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(&input_dir)?;
+    create_test_wav(&input_dir.join("a.wav"), 2.0, 16000, 2, 16)?;
+    create_test_wav(&input_dir.join("b.wav"), 1.0, 8000, 1, 16)?;
+    let manifest_path = test_dir.join("manifest.csv");
+    fs::write(
+        &manifest_path,
+        "file,start,end,label\na.wav,0,0.5,dog\na.wav,1,1.25,cat\nb.wav,0.5,1,dog\n",
+    )?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = AudioCutArgs {
+        manifest: manifest_path.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        root: Some(input_dir.to_string_lossy().to_string()),
+        file_column: "file".to_string(),
+        start_column: "start".to_string(),
+        end_column: "end".to_string(),
+        label_column: "label".to_string(),
+        name_column: "name".to_string(),
+    };
+
+    // Execute command
+    audio::cut::execute(args)?;
+
+    // Verify output, segments are numbered per source file
+    let (spec, samples) = read_wav(&output_dir.join("dog").join("a@0.wav"))?;
+    assert_eq!(spec.channels, 2);
+    assert_eq!(samples[0].len(), 8000);
+    let (_, samples) = read_wav(&output_dir.join("cat").join("a@1.wav"))?;
+    assert_eq!(samples[0].len(), 4000);
+    let (spec, samples) = read_wav(&output_dir.join("dog").join("b@0.wav"))?;
+    assert_eq!(spec.sample_rate, 8000);
+    assert_eq!(samples[0].len(), 4000);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_cut_same_stem_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create same-named test files in different directories
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(input_dir.join("a"))?;
+    fs::create_dir_all(input_dir.join("b"))?;
+    create_test_wav(&input_dir.join("a").join("rec.wav"), 1.0, 16000, 1, 16)?;
+    create_test_wav(&input_dir.join("b").join("rec.wav"), 1.0, 16000, 1, 16)?;
+    let manifest_path = test_dir.join("manifest.csv");
+    fs::write(
+        &manifest_path,
+        "file,start,end\na/rec.wav,0,0.5\nb/rec.wav,0,0.25\n",
+    )?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = AudioCutArgs {
+        manifest: manifest_path.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        root: Some(input_dir.to_string_lossy().to_string()),
+        file_column: "file".to_string(),
+        start_column: "start".to_string(),
+        end_column: "end".to_string(),
+        label_column: "label".to_string(),
+        name_column: "name".to_string(),
+    };
+
+    // Execute command
+    audio::cut::execute(args)?;
+
+    // Verify output, the directory of each source file is mirrored
+    let (_, samples) = read_wav(&output_dir.join("a").join("rec@0.wav"))?;
+    assert_eq!(samples[0].len(), 8000);
+    let (_, samples) = read_wav(&output_dir.join("b").join("rec@0.wav"))?;
+    assert_eq!(samples[0].len(), 4000);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_cut_names_and_clamped_end_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files and a manifest with names, without labels
+    let input_path = test_dir.join("input.wav");
+    create_test_wav(&input_path, 1.0, 16000, 1, 16)?;
+    let manifest_path = test_dir.join("manifest.csv");
+    fs::write(
+        &manifest_path,
+        format!(
+            "file,start,end,name\n{0},0,0.5,first\n{0},0.5,1.2,second\n",
+            input_path.to_string_lossy()
+        ),
+    )?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = AudioCutArgs {
+        manifest: manifest_path.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        root: None,
+        file_column: "file".to_string(),
+        start_column: "start".to_string(),
+        end_column: "end".to_string(),
+        label_column: "label".to_string(),
+        name_column: "name".to_string(),
+    };
+
+    // Execute command
+    audio::cut::execute(args)?;
+
+    // Verify output, the end past the file is clamped to its length
+    let (_, samples) = read_wav(&output_dir.join("first.wav"))?;
+    assert_eq!(samples[0].len(), 8000);
+    let (_, samples) = read_wav(&output_dir.join("second.wav"))?;
+    assert_eq!(samples[0].len(), 8000);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_cut_unsafe_names_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files and a manifest with dotted names and a label escaping the output
+    let input_path = test_dir.join("input.wav");
+    create_test_wav(&input_path, 1.0, 16000, 1, 16)?;
+    let manifest_path = test_dir.join("manifest.csv");
+    fs::write(
+        &manifest_path,
+        format!(
+            "file,start,end,label,name\n{0},0,0.5,..,v1.2\n{0},0.5,1,..,..\n",
+            input_path.to_string_lossy()
+        ),
+    )?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = AudioCutArgs {
+        manifest: manifest_path.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        root: None,
+        file_column: "file".to_string(),
+        start_column: "start".to_string(),
+        end_column: "end".to_string(),
+        label_column: "label".to_string(),
+        name_column: "name".to_string(),
+    };
+
+    // Execute command
+    audio::cut::execute(args)?;
+
+    // Verify output, names keep their dots and nothing is written outside the output
+    assert!(output_dir.join("_").join("v1.2.wav").exists());
+    assert!(output_dir.join("_").join("_.wav").exists());
+    assert!(!test_dir.join("v1.2.wav").exists());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_cut_invalid_segments_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    create_test_wav(&test_dir.join("input.wav"), 1.0, 16000, 1, 16)?;
+    let output_dir = test_dir.join("output");
+
+    // End before start, start past the end of the file and two rows with the same output
+    let manifests = [
+        "file,start,end\ninput.wav,0.5,0.2\n",
+        "file,start,end\ninput.wav,2,3\n",
+        "file,start,end,name\ninput.wav,0,0.5,x\ninput.wav,0.5,1,x\n",
+    ];
+    for (i, manifest) in manifests.iter().enumerate() {
+        let manifest_path = test_dir.join(format!("manifest_{}.csv", i));
+        fs::write(&manifest_path, manifest)?;
+
+        // Define args
+        let args = AudioCutArgs {
+            manifest: manifest_path.to_string_lossy().to_string(),
+            output: output_dir.to_string_lossy().to_string(),
+            root: Some(test_dir.to_string_lossy().to_string()),
+            file_column: "file".to_string(),
+            start_column: "start".to_string(),
+            end_column: "end".to_string(),
+            label_column: "label".to_string(),
+            name_column: "name".to_string(),
+        };
+
+        // Execute command
+        assert!(audio::cut::execute(args).is_err());
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
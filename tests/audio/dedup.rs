@@ -0,0 +1,255 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::audio;
+use datatool::utils::read_table;
+use datatool::{AudioDedupArgs, DedupAction};
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::fs;
+use std::path::Path;
+
+// Pick 40 random notes of 0.25 s with a linear congruential generator
+fn melody_notes(seed: u64) -> Vec<f64> {
+    let mut state = seed;
+    (0..40)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            220.0 * 2_f64.powf(((state >> 33) % 24) as f64 / 12.0)
+        })
+        .collect()
+}
+
+// Write a melody of notes, optionally trimming its start
+fn create_test_notes(path: &Path, notes: &[f64], sample_rate: u32, trim: f64) -> Result<()> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(path, spec)?;
+
+    let start = (trim * sample_rate as f64) as usize;
+    let end = (10.0 * sample_rate as f64) as usize;
+    for i in start..end {
+        let t = i as f64 / sample_rate as f64;
+        let frequency = notes[(t / 0.25) as usize];
+        let sample = 0.4 * (2.0 * std::f64::consts::PI * frequency * t).sin()
+            + 0.2 * (4.0 * std::f64::consts::PI * frequency * t).sin();
+        writer.write_sample((sample * i16::MAX as f64) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+// Write a melody of random notes, optionally trimming its start
+fn create_test_melody(path: &Path, seed: u64, sample_rate: u32, trim: f64) -> Result<()> {
+    create_test_notes(path, &melody_notes(seed), sample_rate, trim)
+}
+
+// Create an original, a trimmed copy at another sample rate and an unrelated melody
+fn create_test_dataset(input_dir: &Path) -> Result<()> {
+    fs::create_dir_all(input_dir.join("nested"))?;
+    create_test_melody(&input_dir.join("a.wav"), 1, 44100, 0.0)?;
+    create_test_melody(&input_dir.join("nested").join("b.wav"), 1, 22050, 0.55)?;
+    create_test_melody(&input_dir.join("c.wav"), 2, 44100, 0.0)?;
+    Ok(())
+}
+
+#[test]
+fn test_audio_dedup_report_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_dir = test_dir.join("input");
+    create_test_dataset(&input_dir)?;
+
+    // Define args
+    let table_path = test_dir.join("duplicates.csv");
+    let args = AudioDedupArgs {
+        target: input_dir.to_string_lossy().to_string(),
+        threshold: 0.85,
+        max_offset: 5.0,
+        action: DedupAction::Report,
+        destination: None,
+        output: Some(table_path.to_string_lossy().to_string()),
+    };
+
+    // Execute command
+    audio::dedup::execute(args)?;
+
+    // Only the original and its trimmed copy are clustered, the longest is kept
+    let df = read_table(&table_path)?.collect()?;
+    assert_eq!(df.height(), 2);
+    let paths: Vec<&str> = df.column("path")?.str()?.into_no_null_iter().collect();
+    let keep: Vec<bool> = df.column("keep")?.bool()?.into_no_null_iter().collect();
+    assert!(paths[0].ends_with("a.wav"));
+    assert!(paths[1].ends_with("b.wav"));
+    assert_eq!(keep, vec![true, false]);
+
+    // Files are left untouched
+    assert!(input_dir.join("nested").join("b.wav").exists());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_dedup_move_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_dir = test_dir.join("input");
+    let destination = test_dir.join("duplicates");
+    create_test_dataset(&input_dir)?;
+
+    // Define args
+    let args = AudioDedupArgs {
+        target: input_dir.to_string_lossy().to_string(),
+        threshold: 0.85,
+        max_offset: 5.0,
+        action: DedupAction::Move,
+        destination: Some(destination.to_string_lossy().to_string()),
+        output: None,
+    };
+
+    // Execute command
+    audio::dedup::execute(args)?;
+
+    // The duplicate is moved, keeping its relative path
+    assert!(!input_dir.join("nested").join("b.wav").exists());
+    assert!(destination.join("nested").join("b.wav").exists());
+    assert!(input_dir.join("a.wav").exists());
+    assert!(input_dir.join("c.wav").exists());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_dedup_hardlink_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files, with an exact copy of the original
+    let input_dir = test_dir.join("input");
+    create_test_dataset(&input_dir)?;
+    fs::copy(input_dir.join("a.wav"), input_dir.join("copy.wav"))?;
+    let trimmed = fs::read(input_dir.join("nested").join("b.wav"))?;
+
+    // Define args
+    let args = AudioDedupArgs {
+        target: input_dir.to_string_lossy().to_string(),
+        threshold: 0.85,
+        max_offset: 5.0,
+        action: DedupAction::Hardlink,
+        destination: None,
+        output: None,
+    };
+
+    // Execute command
+    audio::dedup::execute(args)?;
+
+    // The exact copy is linked to the kept file
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        assert_eq!(
+            fs::metadata(input_dir.join("copy.wav"))?.ino(),
+            fs::metadata(input_dir.join("a.wav"))?.ino()
+        );
+    }
+    assert_eq!(
+        fs::read(input_dir.join("copy.wav"))?,
+        fs::read(input_dir.join("a.wav"))?
+    );
+
+    // The re-encoded duplicate keeps its own content
+    assert_eq!(fs::read(input_dir.join("nested").join("b.wav"))?, trimmed);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_dedup_transitive_match_untouched_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files, the medley shares its first half with one melody and its second
+    // half with the other, which don't match each other
+    let input_dir = test_dir.join("input");
+    let destination = test_dir.join("duplicates");
+    fs::create_dir_all(&input_dir)?;
+    let (first, second) = (melody_notes(1), melody_notes(2));
+    let medley: Vec<f64> = first[..20].iter().chain(&second[20..]).copied().collect();
+    create_test_notes(&input_dir.join("a.wav"), &first, 44100, 0.0)?;
+    create_test_notes(&input_dir.join("b.wav"), &medley, 44100, 0.0)?;
+    create_test_notes(&input_dir.join("c.wav"), &second, 44100, 0.0)?;
+
+    // Define args
+    let table_path = test_dir.join("duplicates.csv");
+    let args = AudioDedupArgs {
+        target: input_dir.to_string_lossy().to_string(),
+        threshold: 0.35,
+        max_offset: 0.0,
+        action: DedupAction::Move,
+        destination: Some(destination.to_string_lossy().to_string()),
+        output: Some(table_path.to_string_lossy().to_string()),
+    };
+
+    // Execute command
+    audio::dedup::execute(args)?;
+
+    // All files are clustered through the medley
+    let df = read_table(&table_path)?.collect()?;
+    assert_eq!(df.height(), 3);
+
+    // Only the medley matches the kept file and is moved
+    assert!(input_dir.join("a.wav").exists());
+    assert!(destination.join("b.wav").exists());
+    assert!(input_dir.join("c.wav").exists());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_dedup_file_target_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    create_test_wav(&input_path, 1.0, 16000, 1, 16)?;
+
+    // Define args
+    let args = AudioDedupArgs {
+        target: input_path.to_string_lossy().to_string(),
+        threshold: 0.85,
+        max_offset: 5.0,
+        action: DedupAction::Report,
+        destination: None,
+        output: None,
+    };
+
+    // Execute command
+    let result = audio::dedup::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
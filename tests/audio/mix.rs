@@ -0,0 +1,139 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::audio;
+use datatool::commands::audio::audio_utils::{read_wav, write_samples};
+use datatool::commands::audio::loudness::{from_db, rms, to_db};
+use datatool::AudioMixArgs;
+use hound::{SampleFormat, WavSpec};
+
+#[test]
+fn test_audio_mix_snr_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create a signal and a shorter noise track
+    let signal_path = test_dir.join("signal.wav");
+    let noise_path = test_dir.join("noise.wav");
+    let output_path = test_dir.join("mixed.wav");
+    create_test_wav(&signal_path, 2.0, 16000, 1, 16)?;
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let noise: Vec<f64> = (0..4000)
+        .map(|i| ((i * 7919) % 200) as f64 / 1000.0 - 0.1)
+        .collect();
+    write_samples(&noise_path, spec, &[noise])?;
+
+    // Define args
+    let args = AudioMixArgs {
+        inputs: vec![
+            signal_path.to_string_lossy().to_string(),
+            noise_path.to_string_lossy().to_string(),
+        ],
+        output: output_path.to_string_lossy().to_string(),
+        gains: vec![-6.0, 0.0],
+        snr: Some(10.0),
+        loop_tracks: true,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::mix::execute(args)?;
+
+    // The added noise is 10 dB under the attenuated signal over the whole length
+    let (_, mut signal) = read_wav(&signal_path)?;
+    signal[0].iter_mut().for_each(|s| *s *= from_db(-6.0));
+    let (spec, mixed) = read_wav(&output_path)?;
+    assert_eq!(spec.bits_per_sample, 16);
+    assert_eq!(mixed[0].len(), 32000);
+    let difference: Vec<f64> = mixed[0]
+        .iter()
+        .zip(&signal[0])
+        .map(|(m, s)| m - s)
+        .collect();
+    let snr = to_db(rms(&signal) / rms(&[difference]));
+    assert!((snr - 10.0).abs() < 0.1);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_mix_gains_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files, the second one is mono at another sample rate
+    let first_path = test_dir.join("first.wav");
+    let second_path = test_dir.join("second.wav");
+    let output_path = test_dir.join("mixed.wav");
+    create_test_wav(&first_path, 1.0, 16000, 2, 16)?;
+    create_test_wav(&second_path, 1.0, 8000, 1, 16)?;
+
+    // Define args, silencing the first track
+    let args = AudioMixArgs {
+        inputs: vec![
+            first_path.to_string_lossy().to_string(),
+            second_path.to_string_lossy().to_string(),
+        ],
+        output: output_path.to_string_lossy().to_string(),
+        gains: vec![-120.0, -6.0],
+        snr: None,
+        loop_tracks: false,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::mix::execute(args)?;
+
+    // Verify output
+    let (spec, mixed) = read_wav(&output_path)?;
+    assert_eq!(spec.channels, 2);
+    assert_eq!(spec.sample_rate, 16000);
+    assert_eq!(mixed[0].len(), 16000);
+    assert!((to_db(rms(&mixed)) - (-6.0 - 3.01)).abs() < 0.2);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_mix_gain_count_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let first_path = test_dir.join("first.wav");
+    let second_path = test_dir.join("second.wav");
+    create_test_wav(&first_path, 1.0, 16000, 1, 16)?;
+    create_test_wav(&second_path, 1.0, 16000, 1, 16)?;
+
+    // Define args with a single gain for two tracks
+    let args = AudioMixArgs {
+        inputs: vec![
+            first_path.to_string_lossy().to_string(),
+            second_path.to_string_lossy().to_string(),
+        ],
+        output: test_dir.join("mixed.wav").to_string_lossy().to_string(),
+        gains: vec![-3.0],
+        snr: None,
+        loop_tracks: false,
+        overwrite: false,
+    };
+
+    // Execute command
+    let result = audio::mix::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
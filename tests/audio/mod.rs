@@ -1,4 +1,15 @@
+mod channels;
+mod check;
+mod concat;
+mod convert;
+mod cut;
+mod dedup;
+mod mix;
+mod normalize;
 mod resample;
+mod spectrogram;
 mod split;
 mod summary;
+mod transform;
 mod trim;
+mod vad;
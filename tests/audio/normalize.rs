@@ -0,0 +1,198 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use clap::Parser;
+use datatool::commands::audio;
+use datatool::commands::audio::audio_utils::read_wav;
+use datatool::commands::audio::loudness::measure;
+use datatool::AudioNormalizeArgs;
+use std::fs;
+
+#[test]
+fn test_audio_normalize_lufs_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 5.0, 48000, 2, 16)?;
+
+    // Define args
+    let args = AudioNormalizeArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        lufs: Some(-23.0),
+        peak: None,
+        ceiling: -1.0,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::normalize::execute(args)?;
+
+    // Verify output loudness is close to the target
+    let (spec, samples) = read_wav(&output_path)?;
+    let loudness = measure(&samples, spec.sample_rate);
+    assert!((loudness.integrated + 23.0).abs() < 0.5);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_normalize_peak_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 2.0, 44100, 1, 16)?;
+
+    // Define args
+    let args = AudioNormalizeArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        lufs: None,
+        peak: Some(-6.0),
+        ceiling: -1.0,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::normalize::execute(args)?;
+
+    // Verify output peak is close to the target
+    let (spec, samples) = read_wav(&output_path)?;
+    let loudness = measure(&samples, spec.sample_rate);
+    assert!((loudness.sample_peak + 6.0).abs() < 0.1);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_normalize_limiter_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 2.0, 44100, 2, 16)?;
+
+    // Ask for a level that would clip without the limiter
+    let args = AudioNormalizeArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        lufs: Some(-5.0),
+        peak: None,
+        ceiling: -3.0,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::normalize::execute(args)?;
+
+    // Verify no sample exceeds the ceiling
+    let (spec, samples) = read_wav(&output_path)?;
+    let loudness = measure(&samples, spec.sample_rate);
+    assert!(loudness.sample_peak <= -3.0 + 0.01);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_normalize_directory_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let output_dir = test_dir.join("output");
+    fs::create_dir(&input_dir)?;
+
+    // Create test files in nested structure
+    let wav_path1 = input_dir.join("test1.wav");
+    let nested_dir = input_dir.join("nested");
+    fs::create_dir(&nested_dir)?;
+    let wav_path2 = nested_dir.join("test2.wav");
+
+    create_test_wav(&wav_path1, 2.0, 44100, 1, 16)?;
+    create_test_wav(&wav_path2, 2.0, 44100, 2, 32)?;
+
+    // Define args
+    let args = AudioNormalizeArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        lufs: None,
+        peak: None,
+        ceiling: -1.0,
+        overwrite: false,
+    };
+
+    // Execute command
+    audio::normalize::execute(args)?;
+
+    // Verify output files exist
+    assert!(output_dir.join("test1.wav").exists());
+    assert!(output_dir.join("nested/test2.wav").exists());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_normalize_conflicting_targets_error() {
+    // Parse args with both targets
+    let result = AudioNormalizeArgs::try_parse_from([
+        "normalize",
+        "input.wav",
+        "output.wav",
+        "--lufs",
+        "-23",
+        "--peak",
+        "-1",
+    ]);
+
+    // Verify error
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_audio_normalize_peak_above_ceiling_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 1.0, 44100, 1, 16)?;
+
+    // Define args with a peak target above the ceiling
+    let args = AudioNormalizeArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        lufs: None,
+        peak: Some(-0.5),
+        ceiling: -1.0,
+        overwrite: false,
+    };
+
+    // Execute command and expect error
+    let result = audio::normalize::execute(args);
+    assert!(result.is_err());
+    assert!(!output_path.exists());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -1,9 +1,11 @@
 use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
 use anyhow::Result;
-use hound::WavReader;
 use datatool::commands::audio;
-use datatool::AudioResampleArgs;
+use datatool::commands::audio::audio_utils::{read_wav, resample_samples};
+use datatool::{AudioResampleArgs, ClipHandling, ResampleMethod, SincInterpolation, SincWindow};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
 use std::fs;
+use std::path::Path;
 
 #[test]
 fn test_audio_resample_file_success() -> Result<()> {
@@ -20,6 +22,12 @@ fn test_audio_resample_file_success() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: output_path.to_string_lossy().to_string(),
         sr: 22050,
+        method: ResampleMethod::Fft,
+        interpolation: SincInterpolation::Cubic,
+        window: SincWindow::BlackmanHarris2,
+        sinc_len: 256,
+        cutoff: None,
+        clipping: ClipHandling::Report,
         overwrite: false,
     };
 
@@ -59,6 +67,12 @@ fn test_audio_resample_directory_success() -> Result<()> {
         input: input_dir.to_string_lossy().to_string(),
         output: output_dir.to_string_lossy().to_string(),
         sr: 22050,
+        method: ResampleMethod::Fft,
+        interpolation: SincInterpolation::Cubic,
+        window: SincWindow::BlackmanHarris2,
+        sinc_len: 256,
+        cutoff: None,
+        clipping: ClipHandling::Report,
         overwrite: false,
     };
 
@@ -98,6 +112,12 @@ fn test_audio_resample_same_rate_success() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: output_path.to_string_lossy().to_string(),
         sr: 44100,
+        method: ResampleMethod::Fft,
+        interpolation: SincInterpolation::Cubic,
+        window: SincWindow::BlackmanHarris2,
+        sinc_len: 256,
+        cutoff: None,
+        clipping: ClipHandling::Report,
         overwrite: false,
     };
 
@@ -129,6 +149,12 @@ fn test_audio_resample_overwrite_protection_error() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: input_path.to_string_lossy().to_string(),
         sr: 22050,
+        method: ResampleMethod::Fft,
+        interpolation: SincInterpolation::Cubic,
+        window: SincWindow::BlackmanHarris2,
+        sinc_len: 256,
+        cutoff: None,
+        clipping: ClipHandling::Report,
         overwrite: false,
     };
 
@@ -156,6 +182,12 @@ fn test_audio_resample_overwrite_success() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: input_path.to_string_lossy().to_string(),
         sr: 22050,
+        method: ResampleMethod::Fft,
+        interpolation: SincInterpolation::Cubic,
+        window: SincWindow::BlackmanHarris2,
+        sinc_len: 256,
+        cutoff: None,
+        clipping: ClipHandling::Report,
         overwrite: true,
     };
 
@@ -188,6 +220,12 @@ fn test_audio_resample_very_high_rate_success() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: output_path.to_string_lossy().to_string(),
         sr: 44100,
+        method: ResampleMethod::Fft,
+        interpolation: SincInterpolation::Cubic,
+        window: SincWindow::BlackmanHarris2,
+        sinc_len: 256,
+        cutoff: None,
+        clipping: ClipHandling::Report,
         overwrite: false,
     };
 
@@ -220,6 +258,12 @@ fn test_audio_resample_higher_rate() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: output_path.to_string_lossy().to_string(),
         sr: 44100,
+        method: ResampleMethod::Fft,
+        interpolation: SincInterpolation::Cubic,
+        window: SincWindow::BlackmanHarris2,
+        sinc_len: 256,
+        cutoff: None,
+        clipping: ClipHandling::Report,
         overwrite: false,
     };
 
@@ -252,6 +296,12 @@ fn test_audio_resample_mono_8bit() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: output_path.to_string_lossy().to_string(),
         sr: 22050,
+        method: ResampleMethod::Fft,
+        interpolation: SincInterpolation::Cubic,
+        window: SincWindow::BlackmanHarris2,
+        sinc_len: 256,
+        cutoff: None,
+        clipping: ClipHandling::Report,
         overwrite: false,
     };
 
@@ -286,6 +336,12 @@ fn test_audio_resample_stereo_32bit() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: output_path.to_string_lossy().to_string(),
         sr: 22050,
+        method: ResampleMethod::Fft,
+        interpolation: SincInterpolation::Cubic,
+        window: SincWindow::BlackmanHarris2,
+        sinc_len: 256,
+        cutoff: None,
+        clipping: ClipHandling::Report,
         overwrite: false,
     };
 
@@ -304,3 +360,172 @@ fn test_audio_resample_stereo_32bit() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_audio_resample_alignment_success() -> Result<()> {
+    // A click half a second into one second of silence
+    let mut samples = vec![vec![0.0; 16000]];
+    samples[0][8000] = 1.0;
+
+    // Resample to half the rate
+    let resampled = resample_samples(&samples, 16000, 8000)?;
+
+    // Verify the length is scaled and the click is not delayed
+    assert_eq!(resampled[0].len(), 8000);
+    let peak = resampled[0]
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+        .map(|(i, _)| i)
+        .unwrap();
+    assert!(peak.abs_diff(4000) <= 1, "click moved to {}", peak);
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_resample_methods_agree_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    create_test_wav(&input_path, 1.0, 48000, 1, 16)?;
+
+    // Execute command with every resampler
+    let methods = [
+        ResampleMethod::Fft,
+        ResampleMethod::Sinc,
+        ResampleMethod::Linear,
+    ];
+    let mut outputs = Vec::new();
+    for (i, method) in methods.into_iter().enumerate() {
+        let output_path = test_dir.join(format!("output_{}.wav", i));
+        let args = AudioResampleArgs {
+            input: input_path.to_string_lossy().to_string(),
+            output: output_path.to_string_lossy().to_string(),
+            sr: 44100,
+            method,
+            interpolation: SincInterpolation::Cubic,
+            window: SincWindow::BlackmanHarris2,
+            sinc_len: 256,
+            cutoff: None,
+            clipping: ClipHandling::Report,
+            overwrite: false,
+        };
+        audio::resample::execute(args)?;
+        outputs.push(read_wav(&output_path)?.1.remove(0));
+    }
+
+    // Same length, and the same aligned sine away from the edges
+    for output in &outputs {
+        assert_eq!(output.len(), 44100);
+        let error = output[1000..43000]
+            .iter()
+            .zip(&outputs[0][1000..43000])
+            .fold(0.0, |error: f64, (a, b)| error.max((a - b).abs()));
+        assert!(error < 0.01);
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_resample_prevent_clipping_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create a full-scale square wave, whose resampled edges overshoot
+    let input_path = test_dir.join("input.wav");
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 44100,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(&input_path, spec)?;
+    for i in 0..44100 {
+        let sample = match (i / 50) % 2 {
+            0 => i16::MAX,
+            _ => -i16::MAX,
+        };
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+
+    // Execute command, reporting or preventing clipping
+    let reported_path = test_dir.join("reported.wav");
+    let prevented_path = test_dir.join("prevented.wav");
+    for (output_path, clipping) in [
+        (&reported_path, ClipHandling::Report),
+        (&prevented_path, ClipHandling::Prevent),
+    ] {
+        let args = AudioResampleArgs {
+            input: input_path.to_string_lossy().to_string(),
+            output: output_path.to_string_lossy().to_string(),
+            sr: 32000,
+            method: ResampleMethod::Sinc,
+            interpolation: SincInterpolation::Cubic,
+            window: SincWindow::BlackmanHarris2,
+            sinc_len: 256,
+            cutoff: None,
+            clipping,
+            overwrite: false,
+        };
+        audio::resample::execute(args)?;
+    }
+
+    // Clipped files are flat at full scale, others only touch it at their peak
+    let count_clipped = |path: &Path| -> Result<usize> {
+        let (_, samples) = read_wav(path)?;
+        Ok(samples[0]
+            .iter()
+            .filter(|sample| sample.abs() >= 32767.0 / 32768.0)
+            .count())
+    };
+    let reported = count_clipped(&reported_path)?;
+    let prevented = count_clipped(&prevented_path)?;
+    assert!(reported > 1000);
+    assert!(prevented < reported / 10);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_resample_invalid_cutoff_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 1.0, 44100, 1, 16)?;
+
+    // Define args with a cutoff above the Nyquist frequency
+    let args = AudioResampleArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        sr: 16000,
+        method: ResampleMethod::Sinc,
+        interpolation: SincInterpolation::Cubic,
+        window: SincWindow::BlackmanHarris2,
+        sinc_len: 256,
+        cutoff: Some(1.5),
+        clipping: ClipHandling::Report,
+        overwrite: false,
+    };
+
+    // Execute command and expect error
+    assert!(audio::resample::execute(args).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::audio;
+use datatool::utils::read_table;
+use datatool::{AudioSpectrogramArgs, FeatureFormat, SpectrogramKind};
+use std::fs;
+
+#[test]
+fn test_audio_spectrogram_stft_npy_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.npy");
+    create_test_wav(&input_path, 1.0, 16000, 1, 16)?;
+
+    // Define args
+    let args = AudioSpectrogramArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        kind: SpectrogramKind::Stft,
+        format: None,
+        window: 1024,
+        hop: 256,
+        n_mels: 80,
+        n_mfcc: 13,
+    };
+
+    // Execute command
+    audio::spectrogram::execute(args)?;
+
+    // Verify the header
+    let bytes = fs::read(&output_path)?;
+    assert_eq!(&bytes[..6], b"\x93NUMPY");
+    let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    assert_eq!((10 + header_len) % 64, 0);
+    let header = String::from_utf8_lossy(&bytes[10..10 + header_len]);
+    assert!(header.contains("'shape': (60, 513)"));
+
+    // The 440 Hz sine peaks in bin 440 * 1024 / 16000 = 28
+    let data: Vec<f32> = bytes[10 + header_len..]
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect();
+    assert_eq!(data.len(), 60 * 513);
+    let frame = &data[10 * 513..11 * 513];
+    let peak_bin = (0..513)
+        .max_by(|&a, &b| frame[a].total_cmp(&frame[b]))
+        .unwrap();
+    assert_eq!(peak_bin, 28);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_spectrogram_mel_png_directory_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_dir = test_dir.join("input");
+    let output_dir = test_dir.join("output");
+    fs::create_dir_all(input_dir.join("nested"))?;
+    create_test_wav(&input_dir.join("a.wav"), 1.0, 16000, 2, 16)?;
+    create_test_wav(&input_dir.join("nested").join("b.wav"), 0.5, 16000, 1, 16)?;
+
+    // Define args
+    let args = AudioSpectrogramArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        kind: SpectrogramKind::Mel,
+        format: Some(FeatureFormat::Png),
+        window: 512,
+        hop: 160,
+        n_mels: 64,
+        n_mfcc: 13,
+    };
+
+    // Execute command
+    audio::spectrogram::execute(args)?;
+
+    // Verify the images, one column per frame and one row per mel band
+    let image = image::open(output_dir.join("a.png"))?;
+    assert_eq!(image.height(), 64);
+    assert_eq!(image.width(), 98);
+    let image = image::open(output_dir.join("nested").join("b.png"))?;
+    assert_eq!(image.height(), 64);
+    assert_eq!(image.width(), 48);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_spectrogram_mfcc_parquet_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.parquet");
+    create_test_wav(&input_path, 1.0, 16000, 1, 16)?;
+
+    // Define args
+    let args = AudioSpectrogramArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        kind: SpectrogramKind::Mfcc,
+        format: None,
+        window: 1024,
+        hop: 256,
+        n_mels: 40,
+        n_mfcc: 13,
+    };
+
+    // Execute command
+    audio::spectrogram::execute(args)?;
+
+    // Verify the table
+    let df = read_table(&output_path)?.collect()?;
+    assert_eq!(df.height(), 60);
+    assert_eq!(df.width(), 14);
+    assert!(df.column("time").is_ok());
+    assert!(df.column("mfcc_12").is_ok());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_spectrogram_invalid_n_mfcc_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    create_test_wav(&input_path, 1.0, 16000, 1, 16)?;
+
+    // Define args with more coefficients than mel bands
+    let args = AudioSpectrogramArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("output.npy").to_string_lossy().to_string(),
+        kind: SpectrogramKind::Mfcc,
+        format: None,
+        window: 1024,
+        hop: 256,
+        n_mels: 10,
+        n_mfcc: 13,
+    };
+
+    // Execute command
+    let result = audio::spectrogram::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
 use anyhow::Result;
 use datatool::commands::audio;
+use datatool::utils::read_table;
 use datatool::AudioSummaryArgs;
 
 #[test]
@@ -15,6 +16,9 @@ fn test_audio_summary_directory_success() -> Result<()> {
     // Define args
     let args = AudioSummaryArgs {
         target: test_dir.to_string_lossy().to_string(),
+        loudness: false,
+        per_file: false,
+        output: None,
     };
 
     // Execute command
@@ -38,6 +42,9 @@ fn test_audio_summary_file_success() -> Result<()> {
     // Define args
     let args = AudioSummaryArgs {
         target: wav_path.to_string_lossy().to_string(),
+        loudness: false,
+        per_file: false,
+        output: None,
     };
 
     // Execute command
@@ -57,6 +64,9 @@ fn test_audio_summary_empty_directory_error() -> Result<()> {
     // Run summary command
     let args = AudioSummaryArgs {
         target: test_dir.to_string_lossy().to_string(),
+        loudness: false,
+        per_file: false,
+        output: None,
     };
 
     // Execute and expect an error
@@ -81,6 +91,9 @@ fn test_audio_summary_non_existing_directory_error() -> Result<()> {
     // Run summary command
     let args = AudioSummaryArgs {
         target: test_dir.to_string_lossy().to_string(),
+        loudness: false,
+        per_file: false,
+        output: None,
     };
 
     // Execute and expect an error
@@ -122,6 +135,9 @@ fn test_audio_summary_nested_directories_success() -> Result<()> {
     // Define args to scan the root directory
     let args = AudioSummaryArgs {
         target: test_dir.to_string_lossy().to_string(),
+        loudness: false,
+        per_file: false,
+        output: None,
     };
 
     // Execute command
@@ -132,3 +148,63 @@ fn test_audio_summary_nested_directories_success() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_audio_summary_loudness_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let wav_path = test_dir.join("test.wav");
+    create_test_wav(&wav_path, 5.0, 48000, 2, 16)?;
+
+    // Define args
+    let args = AudioSummaryArgs {
+        target: test_dir.to_string_lossy().to_string(),
+        loudness: true,
+        per_file: false,
+        output: None,
+    };
+
+    // Execute command
+    audio::summary::execute(args)?;
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_summary_per_file_table_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    create_test_wav(&test_dir.join("test1.wav"), 2.0, 44100, 1, 16)?;
+    create_test_wav(&test_dir.join("test2.wav"), 3.0, 48000, 2, 32)?;
+    let table_path = test_dir.join("summary.csv");
+
+    // Define args
+    let args = AudioSummaryArgs {
+        target: test_dir.to_string_lossy().to_string(),
+        loudness: false,
+        per_file: true,
+        output: Some(table_path.to_string_lossy().to_string()),
+    };
+
+    // Execute command
+    audio::summary::execute(args)?;
+
+    // Verify one row per file with the expected columns
+    let df = read_table(&table_path)?.collect()?;
+    assert_eq!(df.height(), 2);
+    for column in ["path", "codec", "duration", "sample_rate", "channels", "bit_depth"] {
+        assert!(df.column(column).is_ok());
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::audio;
+use datatool::commands::audio::audio_utils::read_wav;
+use datatool::AudioTransformArgs;
+use std::path::Path;
+
+// Estimate the frequency of a sine from the zero crossings in the middle of the signal
+fn estimate_frequency(samples: &[f64], sample_rate: u32) -> f64 {
+    let middle = &samples[samples.len() / 4..samples.len() * 3 / 4];
+    let crossings = middle
+        .windows(2)
+        .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+        .count();
+    crossings as f64 / 2.0 / (middle.len() as f64 / sample_rate as f64)
+}
+
+// Run the given operations from input to output
+fn transform(input: &Path, output: &Path, operations: &[&str]) -> Result<()> {
+    let args = AudioTransformArgs {
+        input: input.to_string_lossy().to_string(),
+        output: output.to_string_lossy().to_string(),
+        operations: operations.iter().map(|op| op.to_string()).collect(),
+        overwrite: false,
+    };
+    audio::transform::execute(args)
+}
+
+#[test]
+fn test_audio_transform_fade_gain_pad_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 1.0, 16000, 2, 16)?;
+
+    // Execute command
+    transform(
+        &input_path,
+        &output_path,
+        &["fade-in=0.1", "gain=-6", "pad=2"],
+    )?;
+
+    // Verify output
+    let (spec, samples) = read_wav(&output_path)?;
+    assert_eq!(spec.channels, 2);
+    assert_eq!(samples[0].len(), 32000);
+    assert_eq!(samples[0][0], 0.0);
+    assert!(samples[0][16000..].iter().all(|&s| s == 0.0));
+    let peak = samples[0]
+        .iter()
+        .fold(0.0, |peak: f64, s| peak.max(s.abs()));
+    assert!((peak - 0.5).abs() < 0.01);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_transform_speed_and_tempo_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let speed_path = test_dir.join("speed.wav");
+    let tempo_path = test_dir.join("tempo.wav");
+    create_test_wav(&input_path, 2.0, 16000, 1, 16)?;
+
+    // Execute command
+    transform(&input_path, &speed_path, &["speed=2"])?;
+    transform(&input_path, &tempo_path, &["tempo=2"])?;
+
+    // Speed halves the duration and doubles the frequency
+    let (_, samples) = read_wav(&speed_path)?;
+    assert!((samples[0].len() as i64 - 16000).abs() < 100);
+    assert!((estimate_frequency(&samples[0], 16000) - 880.0).abs() < 20.0);
+
+    // Tempo halves the duration and keeps the frequency
+    let (_, samples) = read_wav(&tempo_path)?;
+    assert_eq!(samples[0].len(), 16000);
+    assert!((estimate_frequency(&samples[0], 16000) - 440.0).abs() < 20.0);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_transform_pitch_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 2.0, 16000, 1, 16)?;
+
+    // Execute command
+    transform(&input_path, &output_path, &["pitch=12"])?;
+
+    // An octave up doubles the frequency and keeps the duration
+    let (_, samples) = read_wav(&output_path)?;
+    assert_eq!(samples[0].len(), 32000);
+    assert!((estimate_frequency(&samples[0], 16000) - 880.0).abs() < 20.0);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_transform_invalid_operation_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.wav");
+    let output_path = test_dir.join("output.wav");
+    create_test_wav(&input_path, 1.0, 16000, 1, 16)?;
+
+    // Unknown operations and invalid values are rejected
+    assert!(transform(&input_path, &output_path, &["reverb=0.5"]).is_err());
+    assert!(transform(&input_path, &output_path, &["tempo=0"]).is_err());
+    assert!(transform(&input_path, &output_path, &["gain"]).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
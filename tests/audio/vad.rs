@@ -0,0 +1,190 @@
+use crate::utils::{cleanup_test_dir, create_test_wav, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::audio;
+use datatool::utils::read_table;
+use datatool::AudioVadArgs;
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+// Write low-level noise with bursts of tone between the given times in seconds
+fn create_test_speech(path: &Path, duration: f64, bursts: &[(f64, f64)]) -> Result<()> {
+    let sample_rate = 16000;
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(path, spec)?;
+
+    // Linear congruential generator for the background noise
+    let mut state: u64 = 1;
+    for i in 0..(duration * sample_rate as f64) as usize {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let noise = ((state >> 33) as f64 / (1u64 << 31) as f64 - 0.5) * 0.005;
+
+        let t = i as f64 / sample_rate as f64;
+        let tone = match bursts.iter().any(|&(start, end)| t >= start && t < end) {
+            true => 0.5 * (2.0 * PI * 220.0 * t).sin(),
+            false => 0.0,
+        };
+        writer.write_sample(((tone + noise) * i16::MAX as f64) as i16)?;
+    }
+    writer.finalize()?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_vad_segments_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files, a short burst and a pause too short to split are ignored
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(&input_dir)?;
+    create_test_speech(
+        &input_dir.join("speech.wav"),
+        4.0,
+        &[(0.5, 1.5), (1.6, 2.0), (2.5, 2.6), (3.0, 3.5)],
+    )?;
+    let output_path = test_dir.join("segments.csv");
+
+    // Define args
+    let args = AudioVadArgs {
+        target: input_dir.to_string_lossy().to_string(),
+        output: Some(output_path.to_string_lossy().to_string()),
+        frame: 0.03,
+        threshold: 10.0,
+        silence_threshold: -60.0,
+        max_zcr: 0.4,
+        min_speech: 0.25,
+        min_silence: 0.3,
+        padding: 0.1,
+    };
+
+    // Execute command
+    audio::vad::execute(args)?;
+
+    // Verify output
+    let df = read_table(&output_path)?.collect()?;
+    assert_eq!(df.height(), 2);
+    let starts: Vec<f64> = df.column("start")?.f64()?.into_no_null_iter().collect();
+    let ends: Vec<f64> = df.column("end")?.f64()?.into_no_null_iter().collect();
+    for (found, expected) in starts.iter().zip([0.4, 2.9]) {
+        assert!((found - expected).abs() < 0.05);
+    }
+    for (found, expected) in ends.iter().zip([2.1, 3.6]) {
+        assert!((found - expected).abs() < 0.05);
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_vad_no_speech_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files with noise only
+    let input_path = test_dir.join("noise.wav");
+    create_test_speech(&input_path, 2.0, &[])?;
+    let output_path = test_dir.join("segments.parquet");
+
+    // Define args
+    let args = AudioVadArgs {
+        target: input_path.to_string_lossy().to_string(),
+        output: Some(output_path.to_string_lossy().to_string()),
+        frame: 0.03,
+        threshold: 10.0,
+        silence_threshold: -60.0,
+        max_zcr: 0.4,
+        min_speech: 0.25,
+        min_silence: 0.3,
+        padding: 0.1,
+    };
+
+    // Execute command
+    audio::vad::execute(args)?;
+
+    // Verify output
+    let df = read_table(&output_path)?.collect()?;
+    assert_eq!(df.height(), 0);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_vad_continuous_speech_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files without any silence
+    let input_path = test_dir.join("tone.wav");
+    create_test_wav(&input_path, 1.0, 16000, 2, 16)?;
+    let output_path = test_dir.join("segments.csv");
+
+    // Define args
+    let args = AudioVadArgs {
+        target: input_path.to_string_lossy().to_string(),
+        output: Some(output_path.to_string_lossy().to_string()),
+        frame: 0.03,
+        threshold: 10.0,
+        silence_threshold: -60.0,
+        max_zcr: 0.4,
+        min_speech: 0.25,
+        min_silence: 0.3,
+        padding: 0.1,
+    };
+
+    // Execute command
+    audio::vad::execute(args)?;
+
+    // Verify output, the whole file is a single segment
+    let df = read_table(&output_path)?.collect()?;
+    assert_eq!(df.height(), 1);
+    let end = df.column("end")?.f64()?.get(0).unwrap_or_default();
+    assert!((end - 1.0).abs() < 1e-6);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_audio_vad_no_files_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Define args
+    let args = AudioVadArgs {
+        target: test_dir.to_string_lossy().to_string(),
+        output: None,
+        frame: 0.03,
+        threshold: 10.0,
+        silence_threshold: -60.0,
+        max_zcr: 0.4,
+        min_speech: 0.25,
+        min_silence: 0.3,
+        padding: 0.1,
+    };
+
+    // Execute command
+    assert!(audio::vad::execute(args).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
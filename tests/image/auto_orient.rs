@@ -0,0 +1,149 @@
+use crate::utils::{cleanup_test_dir, create_test_image_with_exif, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::image;
+use datatool::{ImageAutoOrientArgs, MetadataMode};
+use exif::{In, Tag};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+// Read the EXIF metadata of a file, if any
+fn read_exif(path: &Path) -> Option<exif::Exif> {
+    let file = File::open(path).ok()?;
+    exif::Reader::new()
+        .read_from_container(&mut BufReader::new(file))
+        .ok()
+}
+
+#[test]
+fn test_image_auto_orient_file_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+    let input_path = test_dir.join("input.jpg");
+    let output_path = test_dir.join("output.jpg");
+    create_test_image_with_exif(&input_path, 64, 32, 6)?;
+
+    // Define args
+    let args = ImageAutoOrientArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        metadata: MetadataMode::Keep,
+        overwrite: false,
+    };
+
+    // Execute command
+    image::auto_orient::execute(args)?;
+
+    // Verify the image was rotated clockwise, the red half ends up on top
+    let img = ::image::open(&output_path)?.to_rgb8();
+    assert_eq!(img.dimensions(), (32, 64));
+    assert!(img.get_pixel(16, 8)[0] > 200);
+    assert!(img.get_pixel(16, 56)[2] > 200);
+
+    // Verify metadata was kept with an upright orientation
+    let exif = read_exif(&output_path).expect("EXIF metadata should be kept");
+    let orientation = exif.get_field(Tag::Orientation, In::PRIMARY).unwrap();
+    assert_eq!(orientation.value.get_uint(0), Some(1));
+    assert!(exif.get_field(Tag::Make, In::PRIMARY).is_some());
+    assert!(exif.get_field(Tag::GPSLatitudeRef, In::PRIMARY).is_some());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_image_auto_orient_strip_metadata() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+    let input_path = test_dir.join("input.jpg");
+    let output_path = test_dir.join("output.jpg");
+    create_test_image_with_exif(&input_path, 64, 32, 3)?;
+
+    // Define args
+    let args = ImageAutoOrientArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        metadata: MetadataMode::Strip,
+        overwrite: false,
+    };
+
+    // Execute command
+    image::auto_orient::execute(args)?;
+
+    // Verify the image was rotated by 180 degrees and metadata is gone
+    let img = ::image::open(&output_path)?.to_rgb8();
+    assert_eq!(img.dimensions(), (64, 32));
+    assert!(img.get_pixel(8, 16)[2] > 200);
+    assert!(img.get_pixel(56, 16)[0] > 200);
+    assert!(read_exif(&output_path).is_none());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_image_auto_orient_directory_without_gps() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let output_dir = test_dir.join("output");
+    std::fs::create_dir_all(input_dir.join("nested"))?;
+    create_test_image_with_exif(&input_dir.join("test1.jpg"), 64, 32, 8)?;
+    create_test_image_with_exif(&input_dir.join("nested").join("test2.jpg"), 64, 32, 1)?;
+
+    // Define args
+    let args = ImageAutoOrientArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        metadata: MetadataMode::KeepNoGps,
+        overwrite: false,
+    };
+
+    // Execute command
+    image::auto_orient::execute(args)?;
+
+    // Verify output structure and orientation
+    let first = output_dir.join("test1.jpg");
+    let second = output_dir.join("nested").join("test2.jpg");
+    assert_eq!(::image::image_dimensions(&first)?, (32, 64));
+    assert_eq!(::image::image_dimensions(&second)?, (64, 32));
+
+    // Verify GPS fields were dropped but the rest was kept
+    for path in [first, second] {
+        let exif = read_exif(&path).expect("EXIF metadata should be kept");
+        assert!(exif.get_field(Tag::Make, In::PRIMARY).is_some());
+        assert!(exif.get_field(Tag::GPSLatitudeRef, In::PRIMARY).is_none());
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_image_auto_orient_overwrite_protection_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+    let input_path = test_dir.join("input.jpg");
+    create_test_image_with_exif(&input_path, 64, 32, 6)?;
+
+    // Define args
+    let args = ImageAutoOrientArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: input_path.to_string_lossy().to_string(),
+        metadata: MetadataMode::Strip,
+        overwrite: false,
+    };
+
+    // Execute command
+    let result = image::auto_orient::execute(args);
+
+    // Verify error
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+    Ok(())
+}
@@ -0,0 +1,231 @@
+use crate::utils::{cleanup_test_dir, create_test_image, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::image;
+use datatool::{ImageColor, ImageConvertArgs, ImageFileFormat, MetadataMode, PngCompression};
+use std::fs;
+
+#[test]
+fn test_image_convert_directory_to_jpeg_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files in a nested structure, one of them with alpha
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(input_dir.join("nested"))?;
+    create_test_image(&input_dir.join("test1.png"), 64, 48, 3)?;
+    ::image::RgbaImage::from_pixel(32, 32, ::image::Rgba([255, 0, 0, 128]))
+        .save(input_dir.join("nested").join("test2.png"))?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = ImageConvertArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        format: Some(ImageFileFormat::Jpeg),
+        quality: 80,
+        compression: PngCompression::Default,
+        color: None,
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    image::convert::execute(args)?;
+
+    // Verify output, alpha is dropped for JPEG
+    let first = ::image::open(output_dir.join("test1.jpg"))?;
+    assert_eq!((first.width(), first.height()), (64, 48));
+    let second = ::image::open(output_dir.join("nested").join("test2.jpg"))?;
+    assert_eq!(second.color(), ::image::ColorType::Rgb8);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_image_convert_color_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.png");
+    create_test_image(&input_path, 64, 48, 3)?;
+
+    // Expected color type of the output for each requested one
+    let cases = [
+        (ImageColor::Gray, ::image::ColorType::L8),
+        (ImageColor::Rgba, ::image::ColorType::Rgba8),
+        (ImageColor::Gray16, ::image::ColorType::L16),
+        (ImageColor::Rgb16, ::image::ColorType::Rgb16),
+    ];
+
+    for (color, expected) in cases {
+        // Define args, the format comes from the output file
+        let output_path = test_dir.join(format!("{:?}.png", color));
+        let args = ImageConvertArgs {
+            input: input_path.to_string_lossy().to_string(),
+            output: output_path.to_string_lossy().to_string(),
+            format: None,
+            quality: 90,
+            compression: PngCompression::Best,
+            color: Some(color),
+            overwrite: false,
+            auto_orient: false,
+            metadata: MetadataMode::Strip,
+        };
+
+        // Execute command
+        image::convert::execute(args)?;
+
+        // Verify output
+        assert_eq!(::image::open(&output_path)?.color(), expected);
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_image_convert_gray_alpha_to_tiff_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create a half transparent grayscale test file
+    let input_path = test_dir.join("input.png");
+    ::image::GrayAlphaImage::from_pixel(64, 48, ::image::LumaA([200, 128])).save(&input_path)?;
+    let output_path = test_dir.join("output.tiff");
+
+    // Define args, the TIFF encoder has no gray with alpha
+    let args = ImageConvertArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        format: None,
+        quality: 90,
+        compression: PngCompression::Default,
+        color: None,
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    image::convert::execute(args)?;
+
+    // Verify output, expanded to RGBA with the alpha kept
+    let output_img = ::image::open(&output_path)?;
+    assert_eq!(output_img.color(), ::image::ColorType::Rgba8);
+    assert_eq!(
+        output_img.to_rgba8().get_pixel(0, 0).0,
+        [200, 200, 200, 128]
+    );
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_image_convert_unsupported_color_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.png");
+    create_test_image(&input_path, 64, 48, 3)?;
+
+    // Define args, JPEG has no alpha channel
+    let args = ImageConvertArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("output.jpg").to_string_lossy().to_string(),
+        format: None,
+        quality: 90,
+        compression: PngCompression::Default,
+        color: Some(ImageColor::Rgba),
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command and expect error
+    let result = image::convert::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_image_convert_missing_format_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.png");
+    create_test_image(&input_path, 64, 48, 3)?;
+
+    // Define args, a directory output needs a format
+    let args = ImageConvertArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("output").to_string_lossy().to_string(),
+        format: None,
+        quality: 90,
+        compression: PngCompression::Default,
+        color: None,
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command and expect error
+    let result = image::convert::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_image_convert_colliding_outputs_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files sharing their stem
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(&input_dir)?;
+    create_test_image(&input_dir.join("photo.jpg"), 64, 48, 3)?;
+    create_test_image(&input_dir.join("photo.png"), 64, 48, 3)?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = ImageConvertArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        format: Some(ImageFileFormat::Webp),
+        quality: 90,
+        compression: PngCompression::Default,
+        color: None,
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command and expect error before anything is written
+    let result = image::convert::execute(args);
+    assert!(result.is_err());
+    assert!(!output_dir.join("photo.webp").exists());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
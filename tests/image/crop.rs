@@ -0,0 +1,327 @@
+use crate::utils::{cleanup_test_dir, create_test_image, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::image;
+use datatool::{CropMode, ImageCropArgs, MetadataMode};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_image_crop_box_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+    let input_path = test_dir.join("input.png");
+    let output_path = test_dir.join("output.png");
+    create_test_image(&input_path, 100, 80, 3)?;
+
+    // Define args
+    let args = ImageCropArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        mode: CropMode::Box,
+        x: 10,
+        y: 20,
+        width: Some(30),
+        height: Some(40),
+        aspect: None,
+        seed: 0,
+        manifest: None,
+        file_column: "file".to_string(),
+        x_column: "x".to_string(),
+        y_column: "y".to_string(),
+        width_column: "width".to_string(),
+        height_column: "height".to_string(),
+        label_column: "label".to_string(),
+        name_column: "name".to_string(),
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    image::crop::execute(args)?;
+
+    // Verify the crop matches the box
+    let input_img = ::image::open(&input_path)?.to_rgb8();
+    let output_img = ::image::open(&output_path)?.to_rgb8();
+    assert_eq!(output_img.dimensions(), (30, 40));
+    assert_eq!(output_img.get_pixel(0, 0), input_img.get_pixel(10, 20));
+    assert_eq!(output_img.get_pixel(29, 39), input_img.get_pixel(39, 59));
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_image_crop_center_aspect_directory_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let output_dir = test_dir.join("output");
+    fs::create_dir_all(input_dir.join("nested"))?;
+    create_test_image(&input_dir.join("test1.png"), 100, 50, 3)?;
+    create_test_image(&input_dir.join("nested").join("test2.png"), 60, 120, 3)?;
+
+    // Define args
+    let args = ImageCropArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        mode: CropMode::Center,
+        x: 0,
+        y: 0,
+        width: None,
+        height: None,
+        aspect: Some("1:1".to_string()),
+        seed: 0,
+        manifest: None,
+        file_column: "file".to_string(),
+        x_column: "x".to_string(),
+        y_column: "y".to_string(),
+        width_column: "width".to_string(),
+        height_column: "height".to_string(),
+        label_column: "label".to_string(),
+        name_column: "name".to_string(),
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    image::crop::execute(args)?;
+
+    // Verify the largest centred squares were taken
+    let input_img = ::image::open(input_dir.join("test1.png"))?.to_rgb8();
+    let output_img = ::image::open(output_dir.join("test1.png"))?.to_rgb8();
+    assert_eq!(output_img.dimensions(), (50, 50));
+    assert_eq!(output_img.get_pixel(0, 0), input_img.get_pixel(25, 0));
+    assert_eq!(
+        ::image::image_dimensions(output_dir.join("nested").join("test2.png"))?,
+        (60, 60)
+    );
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_image_crop_random_is_seeded() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+    let input_path = test_dir.join("input.png");
+    create_test_image(&input_path, 100, 80, 3)?;
+
+    // Crop twice with the same seed
+    for name in ["first.png", "second.png"] {
+        // Define args
+        let args = ImageCropArgs {
+            input: input_path.to_string_lossy().to_string(),
+            output: test_dir.join(name).to_string_lossy().to_string(),
+            mode: CropMode::Random,
+            x: 0,
+            y: 0,
+            width: Some(32),
+            height: Some(32),
+            aspect: None,
+            seed: 7,
+            manifest: None,
+            file_column: "file".to_string(),
+            x_column: "x".to_string(),
+            y_column: "y".to_string(),
+            width_column: "width".to_string(),
+            height_column: "height".to_string(),
+            label_column: "label".to_string(),
+            name_column: "name".to_string(),
+            overwrite: false,
+            auto_orient: false,
+            metadata: MetadataMode::Strip,
+        };
+
+        // Execute command
+        image::crop::execute(args)?;
+    }
+
+    // Verify both crops are identical
+    let first = ::image::open(test_dir.join("first.png"))?.to_rgb8();
+    let second = ::image::open(test_dir.join("second.png"))?.to_rgb8();
+    assert_eq!(first.dimensions(), (32, 32));
+    assert_eq!(first, second);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_image_crop_random_seeded_per_path() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(input_dir.join("a"))?;
+    fs::create_dir_all(input_dir.join("b"))?;
+    create_test_image(&input_dir.join("a").join("input.png"), 100, 80, 3)?;
+    create_test_image(&input_dir.join("b").join("input.png"), 100, 80, 3)?;
+    let moved_dir = test_dir.join("moved");
+
+    // Crop the same files from two locations
+    for (input, output) in [(&input_dir, "first"), (&moved_dir, "second")] {
+        if input == &moved_dir {
+            fs::rename(&input_dir, &moved_dir)?;
+        }
+
+        // Define args
+        let args = ImageCropArgs {
+            input: input.to_string_lossy().to_string(),
+            output: test_dir.join(output).to_string_lossy().to_string(),
+            mode: CropMode::Random,
+            x: 0,
+            y: 0,
+            width: Some(32),
+            height: Some(32),
+            aspect: None,
+            seed: 7,
+            manifest: None,
+            file_column: "file".to_string(),
+            x_column: "x".to_string(),
+            y_column: "y".to_string(),
+            width_column: "width".to_string(),
+            height_column: "height".to_string(),
+            label_column: "label".to_string(),
+            name_column: "name".to_string(),
+            overwrite: false,
+            auto_orient: false,
+            metadata: MetadataMode::Strip,
+        };
+
+        // Execute command
+        image::crop::execute(args)?;
+    }
+
+    // Verify crops depend on the relative path only
+    let crop = |output: &str, directory: &str| {
+        ::image::open(test_dir.join(output).join(directory).join("input.png"))
+            .map(|image| image.to_rgb8())
+    };
+    assert_eq!(crop("first", "a")?, crop("second", "a")?);
+    assert_ne!(crop("first", "a")?, crop("first", "b")?);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_image_crop_manifest_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files and a manifest with a box spilling over the border
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(input_dir.join("nested"))?;
+    create_test_image(&input_dir.join("a.png"), 100, 80, 3)?;
+    create_test_image(&input_dir.join("nested").join("b.png"), 50, 50, 3)?;
+    create_test_image(&input_dir.join("nested").join("a.png"), 50, 50, 3)?;
+    let manifest_path = test_dir.join("manifest.csv");
+    fs::write(
+        &manifest_path,
+        "file,x,y,width,height,label\n\
+         a.png,10,10,20,30,dog\n\
+         a.png,-5,60,30.4,40,cat\n\
+         nested/b.png,0,0,25,25,dog\n\
+         nested/a.png,0,0,10,10,dog\n",
+    )?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = ImageCropArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        mode: CropMode::Center,
+        x: 0,
+        y: 0,
+        width: None,
+        height: None,
+        aspect: None,
+        seed: 0,
+        manifest: Some(manifest_path.to_string_lossy().to_string()),
+        file_column: "file".to_string(),
+        x_column: "x".to_string(),
+        y_column: "y".to_string(),
+        width_column: "width".to_string(),
+        height_column: "height".to_string(),
+        label_column: "label".to_string(),
+        name_column: "name".to_string(),
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    image::crop::execute(args)?;
+
+    // Verify output, boxes are numbered per source file, mirroring its directory, and clamped
+    // to the image
+    let dimensions = |path: &Path| ::image::image_dimensions(path);
+    assert_eq!(
+        dimensions(&output_dir.join("dog").join("a@0.png"))?,
+        (20, 30)
+    );
+    assert_eq!(
+        dimensions(&output_dir.join("cat").join("a@1.png"))?,
+        (25, 20)
+    );
+    assert_eq!(
+        dimensions(&output_dir.join("dog").join("nested").join("b@0.png"))?,
+        (25, 25)
+    );
+    assert_eq!(
+        dimensions(&output_dir.join("dog").join("nested").join("a@0.png"))?,
+        (10, 10)
+    );
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_image_crop_box_outside_image_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+    let input_path = test_dir.join("input.png");
+    create_test_image(&input_path, 100, 80, 3)?;
+
+    // Define args
+    let args = ImageCropArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("output.png").to_string_lossy().to_string(),
+        mode: CropMode::Box,
+        x: 120,
+        y: 0,
+        width: None,
+        height: None,
+        aspect: None,
+        seed: 0,
+        manifest: None,
+        file_column: "file".to_string(),
+        x_column: "x".to_string(),
+        y_column: "y".to_string(),
+        width_column: "width".to_string(),
+        height_column: "height".to_string(),
+        label_column: "label".to_string(),
+        name_column: "name".to_string(),
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    let result = image::crop::execute(args);
+
+    // Verify error
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+    Ok(())
+}
@@ -1,3 +1,6 @@
+mod auto_orient;
+mod convert;
+mod crop;
 mod resize;
 mod summary;
 mod tessellate;
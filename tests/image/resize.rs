@@ -1,7 +1,7 @@
 use crate::utils::{cleanup_test_dir, create_test_image, setup_test_dir};
 use anyhow::Result;
 use datatool::commands::image;
-use datatool::ImageResizeArgs;
+use datatool::{ImageResizeArgs, MetadataMode, ResizeFilter, ResizeMode};
 use std::fs;
 
 #[test]
@@ -18,9 +18,16 @@ fn test_image_resize_file_success() -> Result<()> {
     let args = ImageResizeArgs {
         input: input_path.to_string_lossy().to_string(),
         output: output_path.to_string_lossy().to_string(),
-        width: 50,
-        height: 50,
+        width: Some(50),
+        height: Some(50),
         overwrite: false,
+        mode: ResizeMode::Stretch,
+        size: None,
+        factor: None,
+        filter: ResizeFilter::Lanczos3,
+        pad_color: "000000".to_string(),
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -56,9 +63,16 @@ fn test_image_resize_directory_success() -> Result<()> {
     let args = ImageResizeArgs {
         input: input_dir.to_string_lossy().to_string(),
         output: output_dir.to_string_lossy().to_string(),
-        width: 50,
-        height: 50,
+        width: Some(50),
+        height: Some(50),
         overwrite: false,
+        mode: ResizeMode::Stretch,
+        size: None,
+        factor: None,
+        filter: ResizeFilter::Lanczos3,
+        pad_color: "000000".to_string(),
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -87,9 +101,16 @@ fn test_image_resize_overwrite_protection_error() -> Result<()> {
     let args = ImageResizeArgs {
         input: input_path.to_string_lossy().to_string(),
         output: input_path.to_string_lossy().to_string(),
-        width: 50,
-        height: 50,
+        width: Some(50),
+        height: Some(50),
         overwrite: false,
+        mode: ResizeMode::Stretch,
+        size: None,
+        factor: None,
+        filter: ResizeFilter::Lanczos3,
+        pad_color: "000000".to_string(),
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command and expect error
@@ -115,9 +136,16 @@ fn test_image_resize_overwrite_success() -> Result<()> {
     let args = ImageResizeArgs {
         input: input_path.to_string_lossy().to_string(),
         output: input_path.to_string_lossy().to_string(),
-        width: 50,
-        height: 50,
+        width: Some(50),
+        height: Some(50),
         overwrite: true,
+        mode: ResizeMode::Stretch,
+        size: None,
+        factor: None,
+        filter: ResizeFilter::Lanczos3,
+        pad_color: "000000".to_string(),
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -131,3 +159,172 @@ fn test_image_resize_overwrite_success() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_image_resize_modes_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create a landscape test file
+    let input_path = test_dir.join("input.png");
+    create_test_image(&input_path, 200, 100, 3)?;
+
+    // Expected (width, height) for a requested 50x50 and a height of 50 for stretch, a size
+    // of 50 and a factor of 0.5
+    let cases = [
+        (ResizeMode::Stretch, None, Some(50), None, None, (100, 50)),
+        (ResizeMode::Fit, Some(50), Some(50), None, None, (50, 25)),
+        (ResizeMode::Fill, Some(50), Some(50), None, None, (50, 50)),
+        (ResizeMode::Pad, Some(50), Some(50), None, None, (50, 50)),
+        (ResizeMode::Shortest, None, None, Some(50), None, (100, 50)),
+        (ResizeMode::Longest, None, None, Some(50), None, (50, 25)),
+        (ResizeMode::Scale, None, None, None, Some(0.5), (100, 50)),
+    ];
+
+    for (mode, width, height, size, factor, expected) in cases {
+        // Define args
+        let output_path = test_dir.join(format!("{:?}.png", mode));
+        let args = ImageResizeArgs {
+            input: input_path.to_string_lossy().to_string(),
+            output: output_path.to_string_lossy().to_string(),
+            width,
+            height,
+            overwrite: false,
+            mode,
+            size,
+            factor,
+            filter: ResizeFilter::CatmullRom,
+            pad_color: "000000".to_string(),
+            auto_orient: false,
+            metadata: MetadataMode::Strip,
+        };
+
+        // Execute command
+        image::resize::execute(args)?;
+
+        // Verify output size
+        let output_img = ::image::open(&output_path)?;
+        assert_eq!(
+            (output_img.width(), output_img.height()),
+            expected,
+            "{:?}",
+            mode
+        );
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_image_resize_pad_color_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create a white landscape test file
+    let input_path = test_dir.join("input.png");
+    ::image::RgbImage::from_pixel(200, 100, ::image::Rgb([255, 255, 255])).save(&input_path)?;
+    let output_path = test_dir.join("output.png");
+
+    // Define args
+    let args = ImageResizeArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        width: Some(100),
+        height: Some(100),
+        overwrite: false,
+        mode: ResizeMode::Pad,
+        size: None,
+        factor: None,
+        filter: ResizeFilter::Nearest,
+        pad_color: "#ff0000".to_string(),
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    image::resize::execute(args)?;
+
+    // Verify letterbox bars above and below the image
+    let output_img = ::image::open(&output_path)?.to_rgb8();
+    assert_eq!(output_img.get_pixel(50, 10).0, [255, 0, 0]);
+    assert_eq!(output_img.get_pixel(50, 50).0, [255, 255, 255]);
+    assert_eq!(output_img.get_pixel(50, 90).0, [255, 0, 0]);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_image_resize_fill_without_size_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.png");
+    create_test_image(&input_path, 100, 100, 3)?;
+
+    // Define args, fill needs both sides
+    let args = ImageResizeArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("output.png").to_string_lossy().to_string(),
+        width: Some(50),
+        height: None,
+        overwrite: false,
+        mode: ResizeMode::Fill,
+        size: None,
+        factor: None,
+        filter: ResizeFilter::Lanczos3,
+        pad_color: "000000".to_string(),
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command and expect error
+    let result = image::resize::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_image_resize_shortest_with_height_and_width_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.png");
+    create_test_image(&input_path, 100, 100, 3)?;
+
+    // Define args, shortest takes its size from --size only
+    let args = ImageResizeArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("output.png").to_string_lossy().to_string(),
+        width: Some(50),
+        height: Some(50),
+        overwrite: false,
+        mode: ResizeMode::Shortest,
+        size: None,
+        factor: None,
+        filter: ResizeFilter::Lanczos3,
+        pad_color: "000000".to_string(),
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command and expect error
+    let result = image::resize::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use crate::utils::{cleanup_test_dir, create_test_image, setup_test_dir};
 use anyhow::Result;
 use datatool::commands::image;
-use datatool::ImageTessellateArgs;
+use datatool::{ImageTessellateArgs, MetadataMode};
 use std::fs;
 
 #[test]
@@ -21,6 +21,8 @@ fn test_image_tessellate_file_success() -> Result<()> {
         n_vertical: 2,
         n_horizontal: 2,
         delete_original: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -62,6 +64,8 @@ fn test_image_tessellate_directory_success() -> Result<()> {
         n_vertical: 2,
         n_horizontal: 2,
         delete_original: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -110,6 +114,8 @@ fn test_image_tessellate_with_delete_original() -> Result<()> {
         n_vertical: 2,
         n_horizontal: 2,
         delete_original: true,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
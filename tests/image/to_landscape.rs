@@ -1,7 +1,7 @@
 use crate::utils::{cleanup_test_dir, create_test_image, setup_test_dir};
 use anyhow::Result;
 use datatool::commands::image;
-use datatool::ImageToLandscapeArgs;
+use datatool::{ImageToLandscapeArgs, MetadataMode};
 use std::fs;
 
 #[test]
@@ -19,6 +19,8 @@ fn test_image_to_landscape_file_success() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -55,6 +57,8 @@ fn test_image_to_landscape_directory_success() -> Result<()> {
         input: input_dir.to_string_lossy().to_string(),
         output: output_dir.to_string_lossy().to_string(),
         overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -84,6 +88,8 @@ fn test_image_to_landscape_overwrite_protection_error() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: input_path.to_string_lossy().to_string(),
         overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command and expect error
@@ -110,6 +116,8 @@ fn test_image_to_landscape_overwrite_success() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: input_path.to_string_lossy().to_string(),
         overwrite: true,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -123,3 +131,37 @@ fn test_image_to_landscape_overwrite_success() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_image_to_landscape_already_oriented_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file already in the target orientation
+    let input_path = test_dir.join("input.png");
+    let output_path = test_dir.join("output.png");
+    create_test_image(&input_path, 100, 50, 3)?;
+
+    // Define args
+    let args = ImageToLandscapeArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    image::to_landscape::execute(args)?;
+
+    // Verify the image was saved without rotation
+    assert_eq!(::image::image_dimensions(&output_path)?, (100, 50));
+    let input_img = ::image::open(&input_path)?.to_rgb8();
+    let output_img = ::image::open(&output_path)?.to_rgb8();
+    assert_eq!(input_img, output_img);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
-use crate::utils::{cleanup_test_dir, create_test_image, setup_test_dir};
+use crate::utils::{
+    cleanup_test_dir, create_test_image, create_test_image_with_exif, setup_test_dir,
+};
 use anyhow::Result;
 use datatool::commands::image;
-use datatool::ImageToPortraitArgs;
+use datatool::{ImageToPortraitArgs, MetadataMode};
 use std::fs;
 
 #[test]
@@ -19,6 +21,8 @@ fn test_image_to_portrait_file_success() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -55,6 +59,8 @@ fn test_image_to_portrait_directory_success() -> Result<()> {
         input: input_dir.to_string_lossy().to_string(),
         output: output_dir.to_string_lossy().to_string(),
         overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -84,6 +90,8 @@ fn test_image_to_portrait_overwrite_protection_error() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: input_path.to_string_lossy().to_string(),
         overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command and expect error
@@ -110,6 +118,8 @@ fn test_image_to_portrait_overwrite_success() -> Result<()> {
         input: input_path.to_string_lossy().to_string(),
         output: input_path.to_string_lossy().to_string(),
         overwrite: true,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
     };
 
     // Execute command
@@ -123,3 +133,113 @@ fn test_image_to_portrait_overwrite_success() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_image_to_portrait_auto_orient_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file, stored portrait but displayed landscape
+    let input_path = test_dir.join("input.jpg");
+    let output_path = test_dir.join("output.jpg");
+    create_test_image_with_exif(&input_path, 50, 100, 6)?;
+
+    // Define args
+    let args = ImageToPortraitArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        overwrite: false,
+        auto_orient: true,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    image::to_portrait::execute(args)?;
+
+    // Verify the image was made upright before rotating, its red half ends up on the right
+    let output_img = ::image::open(&output_path)?.to_rgb8();
+    assert_eq!(output_img.dimensions(), (50, 100));
+    assert!(output_img.get_pixel(40, 50)[0] > 200);
+    assert!(output_img.get_pixel(10, 50)[2] > 200);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_image_to_portrait_already_oriented_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file already in the target orientation
+    let input_path = test_dir.join("input.png");
+    let output_path = test_dir.join("output.png");
+    create_test_image(&input_path, 50, 100, 3)?;
+
+    // Define args
+    let args = ImageToPortraitArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        overwrite: false,
+        auto_orient: false,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    image::to_portrait::execute(args)?;
+
+    // Verify the image was saved without rotation
+    assert_eq!(::image::image_dimensions(&output_path)?, (50, 100));
+    let input_img = ::image::open(&input_path)?.to_rgb8();
+    let output_img = ::image::open(&output_path)?.to_rgb8();
+    assert_eq!(input_img, output_img);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_image_to_portrait_auto_orient_directory_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let output_dir = test_dir.join("output");
+    fs::create_dir(&input_dir)?;
+
+    // Create test files, the first one is stored landscape but displayed portrait
+    create_test_image_with_exif(&input_dir.join("test1.jpg"), 100, 50, 8)?;
+    create_test_image_with_exif(&input_dir.join("test2.jpg"), 100, 50, 1)?;
+
+    // Define args
+    let args = ImageToPortraitArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        overwrite: false,
+        auto_orient: true,
+        metadata: MetadataMode::Strip,
+    };
+
+    // Execute command
+    image::to_portrait::execute(args)?;
+
+    // Verify the first image was only made upright, its red half ends up at the bottom
+    let first = ::image::open(output_dir.join("test1.jpg"))?.to_rgb8();
+    assert_eq!(first.dimensions(), (50, 100));
+    assert!(first.get_pixel(25, 90)[0] > 200);
+    assert!(first.get_pixel(25, 10)[2] > 200);
+
+    // Verify the second image was rotated
+    assert_eq!(
+        ::image::image_dimensions(output_dir.join("test2.jpg"))?,
+        (50, 100)
+    );
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use datatool::commands::video::video_utils::decode_frames;
 use polars::df;
 use polars::prelude::*;
 use std::fs;
@@ -100,6 +101,67 @@ pub fn create_test_image(path: &Path, width: u32, height: u32, channels: u8) ->
     Ok(())
 }
 
+/// Create a JPEG with an EXIF orientation, a camera make and a GPS latitude reference.
+/// The left half of the stored pixels is red and the right half is blue.
+pub fn create_test_image_with_exif(
+    path: &Path,
+    width: u32,
+    height: u32,
+    orientation: u16,
+) -> Result<()> {
+    use exif::experimental::Writer;
+    use exif::{Field, In, Tag, Value};
+    use image::{ImageBuffer, Rgb};
+    use std::io::Cursor;
+
+    // Encode the pixels
+    let img: ImageBuffer<Rgb<u8>, Vec<u8>> = ImageBuffer::from_fn(width, height, |x, _| {
+        if x < width / 2 {
+            Rgb([255, 0, 0])
+        } else {
+            Rgb([0, 0, 255])
+        }
+    });
+    let mut jpeg = Cursor::new(Vec::new());
+    img.write_to(&mut jpeg, image::ImageOutputFormat::Jpeg(95))?;
+    let jpeg = jpeg.into_inner();
+
+    // Encode the metadata
+    let fields = [
+        Field {
+            tag: Tag::Orientation,
+            ifd_num: In::PRIMARY,
+            value: Value::Short(vec![orientation]),
+        },
+        Field {
+            tag: Tag::Make,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"datatool".to_vec()]),
+        },
+        Field {
+            tag: Tag::GPSLatitudeRef,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"N".to_vec()]),
+        },
+    ];
+    let mut writer = Writer::new();
+    fields.iter().for_each(|field| writer.push_field(field));
+    let mut tiff = Cursor::new(Vec::new());
+    writer.write(&mut tiff, false)?;
+    let tiff = tiff.into_inner();
+
+    // Insert an APP1 segment right after the SOI marker
+    let mut data = jpeg[..2].to_vec();
+    data.extend_from_slice(&[0xFF, 0xE1]);
+    data.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    data.extend_from_slice(b"Exif\0\0");
+    data.extend_from_slice(&tiff);
+    data.extend_from_slice(&jpeg[2..]);
+    fs::write(path, data)?;
+
+    Ok(())
+}
+
 /// Create a test video file with specified dimensions, duration and framerate
 pub fn create_test_video(
     path: &Path,
@@ -150,6 +212,66 @@ pub fn create_test_video(
     Ok(())
 }
 
+/// Create a test video file with a sine audio track
+pub fn create_test_video_with_audio(
+    path: &Path,
+    duration_sec: f32,
+    sample_rate: u32,
+    channels: u16,
+) -> Result<()> {
+    use std::process::Command;
+
+    // Use ffmpeg generated sources for both streams
+    let output = Command::new("ffmpeg")
+        .args([
+            "-y", // Overwrite output file if it exists
+            "-f",
+            "lavfi",
+            "-i",
+            "testsrc=size=320x240:rate=10",
+            "-f",
+            "lavfi",
+            "-i",
+            &format!("sine=frequency=440:sample_rate={}", sample_rate),
+            "-t",
+            &duration_sec.to_string(),
+            "-c:v",
+            "libx264",
+            "-pix_fmt",
+            "yuv420p",
+            "-c:a",
+            "aac",
+            "-ac",
+            &channels.to_string(),
+            path.to_str().unwrap(),
+        ])
+        .output()?;
+
+    // Check if ffmpeg command was successful
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "FFmpeg failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Number of frames and size of the first frame of a video
+pub fn probe_frames(path: &Path) -> Result<(usize, (u32, u32))> {
+    let mut n_frames = 0;
+    let mut size = (0, 0);
+    decode_frames(path, |mut frame| {
+        if n_frames == 0 {
+            size = frame.to_rgb()?.dimensions();
+        }
+        n_frames += 1;
+        Ok(true)
+    })?;
+    Ok((n_frames, size))
+}
+
 /// Create table
 pub fn create_test_table(path: &Path) -> Result<()> {
     // Use macro
@@ -0,0 +1,100 @@
+use crate::utils::{cleanup_test_dir, create_test_video_with_audio, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::video;
+use datatool::utils::read_table;
+use datatool::VideoCheckArgs;
+use std::fs;
+
+#[test]
+fn test_video_check_clean_file_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video_with_audio(&input_path, 2.0, 44100, 2)?;
+
+    // Define args
+    let args = VideoCheckArgs {
+        target: input_path.to_string_lossy().to_string(),
+        duration_tolerance: 0.5,
+        output: None,
+    };
+
+    // Execute command
+    video::check::execute(args)?;
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_check_corrupt_files_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create a clean file, a truncated one and one that is not a video at all
+    create_test_video_with_audio(&test_dir.join("clean.mkv"), 2.0, 44100, 2)?;
+    let truncated_path = test_dir.join("truncated.mkv");
+    create_test_video_with_audio(&truncated_path, 4.0, 44100, 2)?;
+    let bytes = fs::read(&truncated_path)?;
+    fs::write(&truncated_path, &bytes[..bytes.len() / 3])?;
+    fs::write(test_dir.join("text.mp4"), "not a video")?;
+
+    // Define args
+    let table_path = test_dir.join("report.csv");
+    let args = VideoCheckArgs {
+        target: test_dir.to_string_lossy().to_string(),
+        duration_tolerance: 0.5,
+        output: Some(table_path.to_string_lossy().to_string()),
+    };
+
+    // Execute command
+    let result = video::check::execute(args);
+    assert!(result.is_err());
+
+    // The report covers all files, issues included
+    let df = read_table(&table_path)?.collect()?;
+    assert_eq!(df.height(), 3);
+    let issues: Vec<String> = df
+        .column("issues")?
+        .str()?
+        .into_iter()
+        .map(|issue| issue.unwrap_or_default().to_string())
+        .collect();
+    assert_eq!(issues[0], "");
+    assert!(issues[1].starts_with("unreadable header"));
+    assert!(issues[2].contains("truncated file"));
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_check_nonexistent_target_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Define args
+    let args = VideoCheckArgs {
+        target: test_dir
+            .join("nonexistent.mp4")
+            .to_string_lossy()
+            .to_string(),
+        duration_tolerance: 0.5,
+        output: None,
+    };
+
+    // Execute command
+    let result = video::check::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
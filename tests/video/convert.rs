@@ -0,0 +1,185 @@
+use crate::utils::{
+    cleanup_test_dir, create_test_video, create_test_video_with_audio, probe_frames, setup_test_dir,
+};
+use anyhow::Result;
+use datatool::commands::audio::audio_utils::decode_audio;
+use datatool::commands::video;
+use datatool::{VideoCodec, VideoConvertArgs};
+use std::fs;
+
+#[test]
+fn test_video_convert_resize_fps_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 2.0, 10)?;
+    let output_path = test_dir.join("output.mp4");
+
+    // Define args
+    let args = VideoConvertArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        codec: VideoCodec::H264,
+        width: Some(160),
+        height: None,
+        fps: Some(5.0),
+        pixel_format: None,
+        crf: None,
+        extension: None,
+    };
+
+    // Execute command
+    video::convert::execute(args)?;
+
+    // Verify output, the aspect ratio is kept
+    let (n_frames, size) = probe_frames(&output_path)?;
+    assert_eq!(n_frames, 10);
+    assert_eq!(size, (160, 120));
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_convert_ffv1_directory_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let nested_dir = input_dir.join("nested");
+    fs::create_dir_all(&nested_dir)?;
+
+    // Create test files in nested structure
+    create_test_video(&input_dir.join("first.mp4"), 320, 240, 1.0, 10)?;
+    create_test_video_with_audio(&nested_dir.join("second.mp4"), 1.0, 44100, 2)?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = VideoConvertArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        codec: VideoCodec::Ffv1,
+        width: None,
+        height: None,
+        fps: None,
+        pixel_format: None,
+        crf: None,
+        extension: Some("mkv".to_string()),
+    };
+
+    // Execute command
+    video::convert::execute(args)?;
+
+    // Verify output, frames are kept and audio is copied
+    let (n_frames, size) = probe_frames(&output_dir.join("first.mkv"))?;
+    assert_eq!(n_frames, 10);
+    assert_eq!(size, (320, 240));
+    let audio = decode_audio(&output_dir.join("nested").join("second.mkv"))?;
+    assert_eq!(audio.sample_rate, 44100);
+    assert_eq!(audio.samples.len(), 2);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_convert_invalid_pixel_format_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 1.0, 10)?;
+
+    // Define args
+    let args = VideoConvertArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("output.mp4").to_string_lossy().to_string(),
+        codec: VideoCodec::H264,
+        width: None,
+        height: None,
+        fps: None,
+        pixel_format: Some("not_a_format".to_string()),
+        crf: None,
+        extension: None,
+    };
+
+    // Execute command
+    assert!(video::convert::execute(args).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_convert_odd_width_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 1.0, 10)?;
+
+    // Define args, H.264 subsamples chroma and needs even sizes
+    let args = VideoConvertArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("output.mp4").to_string_lossy().to_string(),
+        codec: VideoCodec::H264,
+        width: Some(161),
+        height: None,
+        fps: None,
+        pixel_format: None,
+        crf: None,
+        extension: None,
+    };
+
+    // Execute command
+    assert!(video::convert::execute(args).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_convert_colliding_outputs_error() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(&input_dir)?;
+
+    // Create test files sharing their stem
+    create_test_video(&input_dir.join("clip.mkv"), 320, 240, 1.0, 10)?;
+    create_test_video(&input_dir.join("clip.mp4"), 320, 240, 1.0, 10)?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = VideoConvertArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        codec: VideoCodec::H264,
+        width: None,
+        height: None,
+        fps: None,
+        pixel_format: None,
+        crf: None,
+        extension: Some("mp4".to_string()),
+    };
+
+    // Execute command
+    assert!(video::convert::execute(args).is_err());
+    assert!(!output_dir.join("clip.mp4").exists());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use crate::utils::{
+    cleanup_test_dir, create_test_video, create_test_video_with_audio, setup_test_dir,
+};
+use anyhow::Result;
+use datatool::commands::video;
+use datatool::{VideoExtractAudioArgs, WavSampleFormat};
+use hound::{SampleFormat, WavReader};
+use std::fs;
+
+#[test]
+fn test_video_extract_audio_file_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video_with_audio(&input_path, 1.0, 44100, 2)?;
+    let output_path = test_dir.join("output.wav");
+
+    // Define args
+    let args = VideoExtractAudioArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        sr: None,
+        channels: None,
+        bits: 16,
+        format: WavSampleFormat::Int,
+    };
+
+    // Execute command
+    video::extract_audio::execute(args)?;
+
+    // Verify output, the encoder may pad the end of the track
+    let reader = WavReader::open(&output_path)?;
+    let spec = reader.spec();
+    assert_eq!(spec.sample_rate, 44100);
+    assert_eq!(spec.channels, 2);
+    assert_eq!(spec.bits_per_sample, 16);
+    let duration = reader.duration() as f64 / spec.sample_rate as f64;
+    assert!((duration - 1.0).abs() < 0.1);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_extract_audio_directory_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let nested_dir = input_dir.join("nested");
+    fs::create_dir_all(&nested_dir)?;
+
+    // Create test files in nested structure, two of them sharing their stem
+    create_test_video_with_audio(&input_dir.join("first.mp4"), 1.0, 48000, 2)?;
+    create_test_video_with_audio(&input_dir.join("first.mkv"), 1.0, 48000, 2)?;
+    create_test_video_with_audio(&nested_dir.join("second.mkv"), 1.0, 44100, 1)?;
+    let output_dir = test_dir.join("output");
+
+    // Define args, resampling and downmixing to float mono
+    let args = VideoExtractAudioArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        sr: Some(16000),
+        channels: Some(1),
+        bits: 32,
+        format: WavSampleFormat::Float,
+    };
+
+    // Execute command
+    video::extract_audio::execute(args)?;
+
+    // Verify output
+    for path in [
+        output_dir.join("first.mp4.wav"),
+        output_dir.join("first.mkv.wav"),
+        output_dir.join("nested").join("second.mkv.wav"),
+    ] {
+        let spec = WavReader::open(&path)?.spec();
+        assert_eq!(spec.sample_rate, 16000);
+        assert_eq!(spec.channels, 1);
+        assert_eq!(spec.sample_format, SampleFormat::Float);
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_extract_audio_no_audio_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file without an audio stream
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 1.0, 10)?;
+
+    // Define args
+    let args = VideoExtractAudioArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("output.wav").to_string_lossy().to_string(),
+        sr: None,
+        channels: None,
+        bits: 16,
+        format: WavSampleFormat::Int,
+    };
+
+    // Execute command
+    assert!(video::extract_audio::execute(args).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
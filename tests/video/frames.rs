@@ -0,0 +1,216 @@
+use crate::utils::{cleanup_test_dir, create_test_video, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::video;
+use datatool::{FrameFormat, VideoFramesArgs};
+use std::fs;
+use std::path::Path;
+
+// Sorted names of the files in a directory
+fn list_files(dir: &Path) -> Result<Vec<String>> {
+    let mut names: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+#[test]
+fn test_video_frames_every_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 1.0, 10)?;
+    let output_dir = test_dir.join("frames");
+
+    // Define args
+    let args = VideoFramesArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        every: Some(5),
+        fps: None,
+        keyframes: false,
+        timestamps: vec![],
+        format: FrameFormat::Png,
+        quality: 90,
+        width: None,
+        height: None,
+    };
+
+    // Execute command
+    video::frames::execute(args)?;
+
+    // Verify output
+    assert_eq!(
+        list_files(&output_dir)?,
+        vec!["input.mp4@000000.png", "input.mp4@000005.png"]
+    );
+    let image = image::open(output_dir.join("input.mp4@000000.png"))?;
+    assert_eq!((image.width(), image.height()), (320, 240));
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_frames_fps_resize_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let nested_dir = input_dir.join("nested");
+    fs::create_dir_all(&nested_dir)?;
+
+    // Create test files in nested structure
+    create_test_video(&nested_dir.join("clip.mp4"), 320, 240, 2.0, 10)?;
+    let output_dir = test_dir.join("frames");
+
+    // Define args
+    let args = VideoFramesArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        every: None,
+        fps: Some(2.0),
+        keyframes: false,
+        timestamps: vec![],
+        format: FrameFormat::Jpeg,
+        quality: 90,
+        width: Some(160),
+        height: None,
+    };
+
+    // Execute command
+    video::frames::execute(args)?;
+
+    // Verify output, two frames per second with the aspect ratio kept
+    let frames = list_files(&output_dir.join("nested"))?;
+    assert_eq!(frames.len(), 4);
+    assert!(frames.iter().all(|name| name.ends_with(".jpg")));
+    let image = image::open(output_dir.join("nested").join(&frames[0]))?;
+    assert_eq!((image.width(), image.height()), (160, 120));
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_frames_same_stem_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    fs::create_dir_all(&input_dir)?;
+
+    // Create test files sharing their stem
+    create_test_video(&input_dir.join("clip.mp4"), 320, 240, 1.0, 10)?;
+    create_test_video(&input_dir.join("clip.mkv"), 320, 240, 1.0, 10)?;
+    let output_dir = test_dir.join("frames");
+
+    // Define args
+    let args = VideoFramesArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        every: Some(5),
+        fps: None,
+        keyframes: false,
+        timestamps: vec![],
+        format: FrameFormat::Png,
+        quality: 90,
+        width: None,
+        height: None,
+    };
+
+    // Execute command
+    video::frames::execute(args)?;
+
+    // Verify output, frames of both files are kept
+    assert_eq!(
+        list_files(&output_dir)?,
+        vec![
+            "clip.mkv@000000.png",
+            "clip.mkv@000005.png",
+            "clip.mp4@000000.png",
+            "clip.mp4@000005.png"
+        ]
+    );
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_frames_timestamps_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 2.0, 10)?;
+    let output_dir = test_dir.join("frames");
+
+    // Define args
+    let args = VideoFramesArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        every: None,
+        fps: None,
+        keyframes: false,
+        timestamps: vec![1.5, 0.0],
+        format: FrameFormat::Png,
+        quality: 90,
+        width: None,
+        height: None,
+    };
+
+    // Execute command
+    video::frames::execute(args)?;
+
+    // Verify output
+    assert_eq!(
+        list_files(&output_dir)?,
+        vec!["input.mp4@000000.png", "input.mp4@000015.png"]
+    );
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_frames_timestamp_past_end_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 1.0, 10)?;
+
+    // Define args
+    let args = VideoFramesArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("frames").to_string_lossy().to_string(),
+        every: None,
+        fps: None,
+        keyframes: false,
+        timestamps: vec![5.0],
+        format: FrameFormat::Png,
+        quality: 90,
+        width: None,
+        height: None,
+    };
+
+    // Execute command
+    assert!(video::frames::execute(args).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use crate::utils::{cleanup_test_dir, create_test_image, probe_frames, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::video;
+use datatool::{VideoCodec, VideoFromFramesArgs};
+use std::fs;
+
+#[test]
+fn test_video_from_frames_natural_sort_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create numbered images without zero padding
+    let input_dir = test_dir.join("frames");
+    fs::create_dir_all(&input_dir)?;
+    for i in 1..=12 {
+        create_test_image(&input_dir.join(format!("frame{}.png", i)), 64, 48, 3)?;
+    }
+    let output_path = test_dir.join("review.mp4");
+
+    // Define args
+    let args = VideoFromFramesArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        fps: 10.0,
+        pattern: None,
+        width: None,
+        height: None,
+        codec: VideoCodec::H264,
+        crf: None,
+    };
+
+    // Execute command
+    video::from_frames::execute(args)?;
+
+    // Verify output
+    assert_eq!(probe_frames(&output_path)?, (12, (64, 48)));
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_from_frames_pattern_pad_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create images of different sizes and a file that does not match
+    let input_dir = test_dir.join("frames");
+    fs::create_dir_all(&input_dir)?;
+    for i in 0..5 {
+        create_test_image(&input_dir.join(format!("pred_{:03}.png", i)), 64, 48, 3)?;
+    }
+    create_test_image(&input_dir.join("pred_005.png"), 30, 60, 1)?;
+    create_test_image(&input_dir.join("other_006.png"), 64, 48, 3)?;
+    let output_path = test_dir.join("review.mkv");
+
+    // Define args
+    let args = VideoFromFramesArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        fps: 10.0,
+        pattern: Some("pred_%03d.png".to_string()),
+        width: Some(128),
+        height: None,
+        codec: VideoCodec::Ffv1,
+        crf: None,
+    };
+
+    // Execute command
+    video::from_frames::execute(args)?;
+
+    // Verify output, the height follows the first image
+    assert_eq!(probe_frames(&output_path)?, (6, (128, 96)));
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_from_frames_no_frames_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("frames");
+    fs::create_dir_all(&input_dir)?;
+
+    // Define args
+    let args = VideoFromFramesArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: test_dir.join("review.mp4").to_string_lossy().to_string(),
+        fps: 10.0,
+        pattern: None,
+        width: None,
+        height: None,
+        codec: VideoCodec::H264,
+        crf: None,
+    };
+
+    // Execute command
+    assert!(video::from_frames::execute(args).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_from_frames_odd_size_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_dir = test_dir.join("frames");
+    fs::create_dir_all(&input_dir)?;
+    create_test_image(&input_dir.join("frame1.png"), 64, 48, 3)?;
+
+    // Define args, H.264 subsamples chroma and needs even sizes
+    let args = VideoFromFramesArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: test_dir.join("review.mp4").to_string_lossy().to_string(),
+        fps: 10.0,
+        pattern: None,
+        width: Some(64),
+        height: Some(47),
+        codec: VideoCodec::H264,
+        crf: None,
+    };
+
+    // Execute command
+    assert!(video::from_frames::execute(args).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -1 +1,10 @@
+mod check;
+mod convert;
+mod extract_audio;
+mod frames;
+mod from_frames;
+mod scenes;
+mod split;
 mod summary;
+mod thumbnails;
+mod trim;
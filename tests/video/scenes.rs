@@ -0,0 +1,199 @@
+use crate::utils::{cleanup_test_dir, create_test_video, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::video;
+use datatool::utils::read_table;
+use datatool::{FrameFormat, SceneMethod, VideoCodec, VideoFromFramesArgs, VideoScenesArgs};
+use image::{Rgb, RgbImage};
+use std::fs;
+use std::path::Path;
+
+// Encode a 10 fps video of one second per color, so each color is a shot
+fn create_shots_video(path: &Path, colors: &[[u8; 3]]) -> Result<()> {
+    let frames_dir = path.with_extension("frames");
+    fs::create_dir_all(&frames_dir)?;
+    for (shot, color) in colors.iter().enumerate() {
+        for i in 0..10 {
+            RgbImage::from_pixel(64, 48, Rgb(*color))
+                .save(frames_dir.join(format!("frame{:03}.png", shot * 10 + i)))?;
+        }
+    }
+    // Define args
+    let args = VideoFromFramesArgs {
+        input: frames_dir.to_string_lossy().to_string(),
+        output: path.to_string_lossy().to_string(),
+        fps: 10.0,
+        pattern: None,
+        width: None,
+        height: None,
+        codec: VideoCodec::H264,
+        crf: None,
+    };
+
+    video::from_frames::execute(args)?;
+    fs::remove_dir_all(&frames_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_video_scenes_histogram_table_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_shots_video(&input_path, &[[200, 30, 30], [30, 200, 30], [30, 30, 200]])?;
+    let output_path = test_dir.join("shots.csv");
+
+    // Define args
+    let args = VideoScenesArgs {
+        target: input_path.to_string_lossy().to_string(),
+        output: Some(output_path.to_string_lossy().to_string()),
+        method: SceneMethod::Histogram,
+        threshold: None,
+        min_shot: 0.5,
+        keyframes: None,
+        format: FrameFormat::Png,
+    };
+
+    // Execute command
+    video::scenes::execute(args)?;
+
+    // Verify output
+    let df = read_table(&output_path)?.collect()?;
+    assert_eq!(df.height(), 3);
+    let starts: Vec<f64> = df.column("start")?.f64()?.into_no_null_iter().collect();
+    let ends: Vec<f64> = df.column("end")?.f64()?.into_no_null_iter().collect();
+    for (i, (start, end)) in starts.iter().zip(&ends).enumerate() {
+        assert!(
+            (start - i as f64).abs() < 0.05,
+            "start {} of shot {}",
+            start,
+            i
+        );
+        assert!(
+            (end - (i + 1) as f64).abs() < 0.05,
+            "end {} of shot {}",
+            end,
+            i
+        );
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_scenes_pixel_keyframes_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files sharing their stem in a nested directory
+    let input_dir = test_dir.join("videos");
+    fs::create_dir_all(input_dir.join("nested"))?;
+    create_shots_video(
+        &input_dir.join("nested").join("input.mp4"),
+        &[[0, 0, 0], [255, 255, 255]],
+    )?;
+    create_shots_video(
+        &input_dir.join("nested").join("input.mkv"),
+        &[[255, 255, 255], [0, 0, 0]],
+    )?;
+    let keyframes_dir = test_dir.join("keyframes");
+
+    // Define args
+    let args = VideoScenesArgs {
+        target: input_dir.to_string_lossy().to_string(),
+        output: None,
+        method: SceneMethod::Pixel,
+        threshold: None,
+        min_shot: 0.5,
+        keyframes: Some(keyframes_dir.to_string_lossy().to_string()),
+        format: FrameFormat::Png,
+    };
+
+    // Execute command
+    video::scenes::execute(args)?;
+
+    // Verify output, one keyframe per shot taken from the middle of the shot
+    let nested_dir = keyframes_dir.join("nested");
+    let first = image::open(nested_dir.join("input.mp4@0000.png"))?.to_rgb8();
+    let second = image::open(nested_dir.join("input.mp4@0001.png"))?.to_rgb8();
+    assert!(first.get_pixel(32, 24).0[0] < 50);
+    assert!(second.get_pixel(32, 24).0[0] > 200);
+    assert!(!nested_dir.join("input.mp4@0002.png").exists());
+
+    // Keyframes of the file with the same stem are kept apart
+    let first = image::open(nested_dir.join("input.mkv@0000.png"))?.to_rgb8();
+    assert!(first.get_pixel(32, 24).0[0] > 200);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_scenes_min_shot_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files
+    let input_path = test_dir.join("input.mp4");
+    create_shots_video(&input_path, &[[200, 30, 30], [30, 200, 30], [30, 30, 200]])?;
+    let output_path = test_dir.join("shots.csv");
+
+    // Define args, shots shorter than the video cannot be cut
+    let args = VideoScenesArgs {
+        target: input_path.to_string_lossy().to_string(),
+        output: Some(output_path.to_string_lossy().to_string()),
+        method: SceneMethod::Histogram,
+        threshold: None,
+        min_shot: 5.0,
+        keyframes: None,
+        format: FrameFormat::Png,
+    };
+
+    // Execute command
+    video::scenes::execute(args)?;
+
+    // Verify output
+    let df = read_table(&output_path)?.collect()?;
+    assert_eq!(df.height(), 1);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_scenes_invalid_threshold() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 1.0, 10)?;
+
+    // Define args
+    let args = VideoScenesArgs {
+        target: input_path.to_string_lossy().to_string(),
+        output: None,
+        method: SceneMethod::Histogram,
+        threshold: Some(1.5),
+        min_shot: 0.5,
+        keyframes: None,
+        format: FrameFormat::Png,
+    };
+
+    // Execute command
+    let result = video::scenes::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
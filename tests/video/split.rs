@@ -0,0 +1,109 @@
+use crate::utils::{cleanup_test_dir, create_test_video, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::video;
+use datatool::commands::video::video_utils::video_duration;
+use datatool::{VideoCutMode, VideoSplitArgs};
+use std::fs;
+
+#[test]
+fn test_video_split_reencode_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 3.0, 10)?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = VideoSplitArgs {
+        input: input_path.to_string_lossy().to_string(),
+        chunk_duration: 1.0,
+        output: output_dir.to_string_lossy().to_string(),
+        mode: VideoCutMode::Reencode,
+        delete_original: false,
+    };
+
+    // Execute command
+    video::split::execute(args)?;
+
+    // Verify output
+    for i in 0..3 {
+        let duration = video_duration(&output_dir.join(format!("input@{}.mp4", i)))?;
+        assert!((duration - 1.0).abs() < 0.15);
+    }
+    assert!(!output_dir.join("input@3.mp4").exists());
+    assert!(input_path.exists());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_split_copy_delete_original_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let nested_dir = input_dir.join("nested");
+    fs::create_dir_all(&nested_dir)?;
+
+    // Create test file in nested structure
+    let input_path = nested_dir.join("clip.mp4");
+    create_test_video(&input_path, 320, 240, 2.5, 10)?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = VideoSplitArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        chunk_duration: 1.0,
+        output: output_dir.to_string_lossy().to_string(),
+        mode: VideoCutMode::Copy,
+        delete_original: true,
+    };
+
+    // Execute command
+    video::split::execute(args)?;
+
+    // Verify output, the last chunk is shorter
+    for i in 0..3 {
+        assert!(output_dir
+            .join("nested")
+            .join(format!("clip@{}.mp4", i))
+            .exists());
+    }
+    assert!(!input_path.exists());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_split_output_file_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 1.0, 10)?;
+
+    // Define args
+    let args = VideoSplitArgs {
+        input: input_path.to_string_lossy().to_string(),
+        chunk_duration: 0.5,
+        output: test_dir.join("output.mp4").to_string_lossy().to_string(),
+        mode: VideoCutMode::Copy,
+        delete_original: false,
+    };
+
+    // Execute command
+    assert!(video::split::execute(args).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
-use crate::utils::{cleanup_test_dir, create_test_video, setup_test_dir};
+use crate::utils::{
+    cleanup_test_dir, create_test_video, create_test_video_with_audio, setup_test_dir,
+};
 use anyhow::Result;
 use datatool::commands::video;
+use datatool::utils::read_table;
 use datatool::VideoSummaryArgs;
 use std::fs;
 
@@ -16,6 +19,8 @@ fn test_video_summary_file_success() -> Result<()> {
     // Define args
     let args = VideoSummaryArgs {
         target: input_path.to_string_lossy().to_string(),
+        per_file: false,
+        output: None,
     };
 
     // Execute command
@@ -46,6 +51,8 @@ fn test_video_summary_directory_success() -> Result<()> {
     // Define args
     let args = VideoSummaryArgs {
         target: input_dir.to_string_lossy().to_string(),
+        per_file: false,
+        output: None,
     };
 
     // Execute command
@@ -57,6 +64,49 @@ fn test_video_summary_directory_success() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_video_summary_table_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    fs::create_dir(&input_dir)?;
+
+    // Create test files with and without audio
+    create_test_video(&input_dir.join("silent.mp4"), 640, 480, 2.0, 25)?;
+    create_test_video_with_audio(&input_dir.join("sound.mkv"), 2.0, 48000, 2)?;
+    let output_path = test_dir.join("summary.csv");
+
+    // Define args
+    let args = VideoSummaryArgs {
+        target: input_dir.to_string_lossy().to_string(),
+        per_file: false,
+        output: Some(output_path.to_string_lossy().to_string()),
+    };
+
+    // Execute command
+    video::summary::execute(args)?;
+
+    // Verify output, rows are sorted by path
+    let df = read_table(&output_path)?.collect()?;
+    assert_eq!(df.height(), 2);
+    let codecs: Vec<&str> = df
+        .column("video_codec")?
+        .str()?
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(codecs, vec!["h264", "h264"]);
+    let widths: Vec<i64> = df.column("width")?.i64()?.into_no_null_iter().collect();
+    assert_eq!(widths, vec![640, 320]);
+    let audio_codec = df.column("audio_codec")?.str()?;
+    assert_eq!(audio_codec.get(0), None);
+    assert_eq!(audio_codec.get(1), Some("aac"));
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
 #[test]
 fn test_video_summary_nonexistent_path() -> Result<()> {
     // Set up the directory for testing
@@ -66,6 +116,8 @@ fn test_video_summary_nonexistent_path() -> Result<()> {
     // Define args with nonexistent path
     let args = VideoSummaryArgs {
         target: nonexistent_path.to_string_lossy().to_string(),
+        per_file: false,
+        output: None,
     };
 
     // Execute command and expect error
@@ -90,6 +142,8 @@ fn test_video_summary_invalid_extension() -> Result<()> {
     // Define args
     let args = VideoSummaryArgs {
         target: invalid_path.to_string_lossy().to_string(),
+        per_file: false,
+        output: None,
     };
 
     // Execute command and expect error
@@ -0,0 +1,121 @@
+use crate::utils::{cleanup_test_dir, create_test_video, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::video;
+use datatool::{FrameFormat, VideoThumbnailsArgs};
+use std::fs;
+
+#[test]
+fn test_video_thumbnails_file_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 640, 360, 2.0, 10)?;
+    let output_path = test_dir.join("sheet.png");
+
+    // Define args
+    let args = VideoThumbnailsArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: output_path.to_string_lossy().to_string(),
+        count: 9,
+        columns: None,
+        width: 320,
+        no_timestamps: false,
+        format: FrameFormat::Jpeg,
+        quality: 90,
+    };
+
+    // Execute command
+    video::thumbnails::execute(args)?;
+
+    // Verify output, 3x3 tiles of 320x180 separated by 4 px
+    let image = image::open(&output_path)?;
+    assert_eq!((image.width(), image.height()), (976, 556));
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_thumbnails_directory_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test files in a nested directory, two of them sharing their stem
+    let input_dir = test_dir.join("videos");
+    fs::create_dir_all(input_dir.join("nested"))?;
+    create_test_video(&input_dir.join("first.mp4"), 320, 240, 1.0, 10)?;
+    create_test_video(&input_dir.join("first.mkv"), 320, 240, 1.0, 10)?;
+    create_test_video(
+        &input_dir.join("nested").join("second.mp4"),
+        320,
+        240,
+        1.0,
+        10,
+    )?;
+    let output_dir = test_dir.join("sheets");
+
+    // Define args
+    let args = VideoThumbnailsArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        output: output_dir.to_string_lossy().to_string(),
+        count: 4,
+        columns: Some(4),
+        width: 160,
+        no_timestamps: true,
+        format: FrameFormat::Jpeg,
+        quality: 90,
+    };
+
+    // Execute command
+    video::thumbnails::execute(args)?;
+
+    // Verify output, one row of 4 tiles per video
+    for path in [
+        output_dir.join("first.mp4.jpg"),
+        output_dir.join("first.mkv.jpg"),
+        output_dir.join("nested").join("second.mp4.jpg"),
+    ] {
+        let image = image::open(&path)?;
+        assert_eq!((image.width(), image.height()), (660, 128));
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_thumbnails_zero_count_failure() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 1.0, 10)?;
+
+    // Define args
+    let args = VideoThumbnailsArgs {
+        input: input_path.to_string_lossy().to_string(),
+        output: test_dir.join("sheets").to_string_lossy().to_string(),
+        count: 0,
+        columns: None,
+        width: 320,
+        no_timestamps: false,
+        format: FrameFormat::Jpeg,
+        quality: 90,
+    };
+
+    // Execute command
+    let result = video::thumbnails::execute(args);
+    assert!(result.is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use crate::utils::{cleanup_test_dir, create_test_video, setup_test_dir};
+use anyhow::Result;
+use datatool::commands::video;
+use datatool::commands::video::video_utils::video_duration;
+use datatool::{VideoCutMode, VideoTrimArgs};
+use std::fs;
+
+#[test]
+fn test_video_trim_reencode_success() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 3.0, 10)?;
+    let output_path = test_dir.join("output.mp4");
+
+    // Define args
+    let args = VideoTrimArgs {
+        input: input_path.to_string_lossy().to_string(),
+        length: 1.0,
+        output: output_path.to_string_lossy().to_string(),
+        offset: 1.0,
+        mode: VideoCutMode::Reencode,
+    };
+
+    // Execute command
+    video::trim::execute(args)?;
+
+    // Verify output
+    let duration = video_duration(&output_path)?;
+    assert!((duration - 1.0).abs() < 0.15);
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_trim_copy_directory_success() -> Result<()> {
+    // Set up the directories for testing
+    let test_dir = setup_test_dir()?;
+    let input_dir = test_dir.join("input");
+    let nested_dir = input_dir.join("nested");
+    fs::create_dir_all(&nested_dir)?;
+
+    // Create test files in nested structure
+    create_test_video(&input_dir.join("first.mp4"), 320, 240, 3.0, 10)?;
+    create_test_video(&nested_dir.join("second.mkv"), 320, 240, 3.0, 10)?;
+    let output_dir = test_dir.join("output");
+
+    // Define args
+    let args = VideoTrimArgs {
+        input: input_dir.to_string_lossy().to_string(),
+        length: 2.0,
+        output: output_dir.to_string_lossy().to_string(),
+        offset: 0.0,
+        mode: VideoCutMode::Copy,
+    };
+
+    // Execute command
+    video::trim::execute(args)?;
+
+    // Verify output, copied clips end on the requested length
+    for path in [
+        output_dir.join("first.mp4"),
+        output_dir.join("nested").join("second.mkv"),
+    ] {
+        let duration = video_duration(&path)?;
+        assert!(duration > 1.5 && duration < 2.5);
+    }
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_trim_invalid_length_error() -> Result<()> {
+    // Set up the directory for testing
+    let test_dir = setup_test_dir()?;
+
+    // Create test file
+    let input_path = test_dir.join("input.mp4");
+    create_test_video(&input_path, 320, 240, 1.0, 10)?;
+
+    // Define args
+    let args = VideoTrimArgs {
+        input: input_path.to_string_lossy().to_string(),
+        length: 5.0,
+        output: test_dir.join("output.mp4").to_string_lossy().to_string(),
+        offset: 0.0,
+        mode: VideoCutMode::Copy,
+    };
+
+    // Execute command
+    assert!(video::trim::execute(args).is_err());
+
+    // Clean up dir
+    cleanup_test_dir(&test_dir)?;
+
+    Ok(())
+}